@@ -4,7 +4,7 @@ use std::fs::create_dir_all;
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
 use isotonic_credit_agency::msg::{
-    ExecuteMsg, InstantiateMsg, ListMarketsResponse, MarketResponse, QueryMsg,
+    ExecuteMsg, InstantiateMsg, ListMarketsResponse, MarketResponse, MigrateMsg, QueryMsg,
 };
 use isotonic_credit_agency::state::Config;
 
@@ -17,6 +17,7 @@ fn main() {
     export_schema(&schema_for!(InstantiateMsg), &out_dir);
     export_schema(&schema_for!(ExecuteMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(MigrateMsg), &out_dir);
     export_schema(&schema_for!(Config), &out_dir);
     export_schema(&schema_for!(MarketResponse), &out_dir);
     export_schema(&schema_for!(ListMarketsResponse), &out_dir);
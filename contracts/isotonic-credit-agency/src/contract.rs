@@ -1,6 +1,6 @@
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, SudoMsg};
-use crate::state::{Config, CONFIG, NEXT_REPLY_ID};
+use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, SudoMsg};
+use crate::state::{self, Config, CONFIG, NEXT_REPLY_ID};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{to_binary, Addr, Binary, Env, MessageInfo, Reply};
@@ -8,7 +8,7 @@ use cw2::set_contract_version;
 use cw_utils::parse_reply_instantiate_data;
 use osmo_bindings::{OsmosisMsg, OsmosisQuery};
 
-use either::Either;
+use utils::events::{ParameterChange, TypedEvent};
 use utils::token::Token;
 
 pub type Response = cosmwasm_std::Response<OsmosisMsg>;
@@ -62,7 +62,7 @@ pub fn execute(
     use ExecuteMsg::*;
 
     match msg {
-        CreateMarket(market_cfg) => execute::create_market(deps, env, info, market_cfg),
+        CreateMarket(market_cfg) => execute::create_market(deps, env, info, *market_cfg),
         Liquidate {
             account,
             collateral_denom,
@@ -91,6 +91,19 @@ pub fn execute(
             max_collateral,
             amount_to_repay,
         } => execute::repay_with_collateral(deps, info.sender, max_collateral, amount_to_repay),
+        AccrueAll { start_after, limit } => execute::accrue_all(
+            deps,
+            info.sender,
+            start_after
+                .map(|sa| sa.native().ok_or(ContractError::Cw20TokensNotSupported))
+                .transpose()?,
+            limit,
+        ),
+        Batch { ops } => execute::batch(deps, env, info, ops),
+        BatchCallback { account, callback } => {
+            let account = deps.api.addr_validate(&account)?;
+            execute::batch_callback(deps, env, info, account, callback)
+        }
     }
 }
 
@@ -103,15 +116,16 @@ mod execute {
     use utils::{
         coin::{coin_native, Coin},
         credit_line::{CreditLineResponse, CreditLineValues},
+        events::Liquidate,
         price::PriceRate,
     };
 
     use crate::{
-        msg::MarketConfig,
-        state::{MarketState, ENTERED_MARKETS, MARKETS, REPLY_IDS},
+        msg::{BatchCallback, MarketConfig, Operation},
+        state::{MarketState, MARKETS, REPLY_IDS},
     };
     use isotonic_market::{
-        msg::{ExecuteMsg as MarketExecuteMsg, QueryMsg as MarketQueryMsg},
+        msg::{ExecuteMsg as MarketExecuteMsg, OverdueResponse, QueryMsg as MarketQueryMsg},
         state::Config as MarketConfiguration,
     };
 
@@ -149,7 +163,7 @@ mod execute {
 
             let err = match state {
                 Instantiating => ContractError::MarketCreating(market_token),
-                Ready(_) => ContractError::MarketAlreadyExists(market_token),
+                Ready(_) | Deprecated(_) => ContractError::MarketAlreadyExists(market_token),
             };
             return Err(err);
         }
@@ -173,6 +187,9 @@ mod execute {
             collateral_ratio: market_cfg.collateral_ratio,
             price_oracle: market_cfg.price_oracle,
             reserve_factor: market_cfg.reserve_factor,
+            origination_fee: market_cfg.origination_fee,
+            borrowing_enabled: market_cfg.borrowing_enabled,
+            collateral_enabled: market_cfg.collateral_enabled,
         };
         let market_instantiate = WasmMsg::Instantiate {
             admin: Some(env.contract.address.to_string()),
@@ -193,21 +210,17 @@ mod execute {
         info: MessageInfo,
         account: Addr,
         collateral_denom: String,
-        amount_to_repay: Coin,
+        mut amount_to_repay: Coin,
     ) -> Result<Response, ContractError> {
         let collateral_market = query::market(deps.as_ref(), collateral_denom.to_string())?.market;
         let debt_market = query::market(deps.as_ref(), amount_to_repay.denom.to_string())?.market;
 
-        let markets = ENTERED_MARKETS
-            .may_load(deps.storage, &account)?
-            .unwrap_or_default();
-
-        if !markets.contains(&collateral_market) {
+        if !state::is_on_market(deps.storage, &account, &collateral_market) {
             return Err(ContractError::NotOnMarket {
                 address: account,
                 market: collateral_market,
             });
-        } else if !markets.contains(&debt_market) {
+        } else if !state::is_on_market(deps.storage, &account, &debt_market) {
             return Err(ContractError::NotOnMarket {
                 address: account,
                 market: debt_market,
@@ -216,6 +229,23 @@ mod execute {
 
         let cfg = CONFIG.load(deps.storage)?;
 
+        let tcr = query::total_credit_line(deps.as_ref(), account.to_string())?;
+        let total_credit_line = tcr.validate(&Token::Native(cfg.common_token.clone()))?;
+        // Healthy accounts can still be liquidated when they have overdue term loans, but only
+        // up to the overdue debt
+        if total_credit_line.debt <= total_credit_line.credit_line {
+            let overdue: OverdueResponse = deps.querier.query_wasm_smart(
+                debt_market.clone(),
+                &MarketQueryMsg::Overdue {
+                    account: account.to_string(),
+                },
+            )?;
+            if !overdue.overdue {
+                return Err(ContractError::LiquidationNotAllowed {});
+            }
+            amount_to_repay.amount = std::cmp::min(amount_to_repay.amount, overdue.amount);
+        }
+
         let initiation_fee = amount_to_repay.amount * cfg.liquidation_initiation_fee;
         let lender_fee = amount_to_repay.amount * cfg.liquidation_fee;
         let amount_to_cover = Coin {
@@ -226,12 +256,6 @@ mod execute {
                 .checked_add(lender_fee)?,
         };
 
-        let tcr = query::total_credit_line(deps.as_ref(), account.to_string())?;
-        let total_credit_line = tcr.validate(&Token::Native(cfg.common_token.clone()))?;
-        if total_credit_line.debt <= total_credit_line.credit_line {
-            return Err(ContractError::LiquidationNotAllowed {});
-        }
-
         let collateral_market_cfg: MarketConfiguration = deps
             .querier
             .query_wasm_smart(collateral_market.clone(), &MarketQueryMsg::Configuration {})?;
@@ -259,6 +283,10 @@ mod execute {
         let sell_limit = if simulated_debt.amount.is_zero() {
             // if this is going to erase all debt, just allow the selling of all collateral
             Uint128::MAX
+        } else if collateral_market_cfg.collateral_ratio.is_zero() {
+            // collateral of deprecated markets can ramp to a zero ratio, selling it doesn't
+            // lower the credit line then
+            Uint128::MAX
         } else {
             let sell_limit_in_common = divide(
                 tcr.credit_line.saturating_sub(simulated_debt)?.amount,
@@ -317,11 +345,20 @@ mod execute {
             )],
         });
 
+        let event = Liquidate {
+            liquidator: info.sender.to_string(),
+            account: account.to_string(),
+            collateral_denom: collateral_denom.clone(),
+            debt_denom: amount_to_repay.denom.to_string(),
+            repaid: amount_to_repay.amount,
+        };
+
         Ok(Response::new()
             .add_attribute("action", "liquidate")
             .add_attribute("liquidation_initiator", info.sender)
             .add_attribute("account", account)
             .add_attribute("collateral_denom", collateral_denom)
+            .add_event(event.to_event())
             .add_submessage(swap_withdraw_from_msg)
             .add_submessage(repay_to_msg)
             .add_submessage(distribute_ltokens_msg)
@@ -335,11 +372,7 @@ mod execute {
     ) -> Result<Response, ContractError> {
         let market = info.sender;
 
-        ENTERED_MARKETS.update(deps.storage, &account, |maybe_set| -> Result<_, StdError> {
-            let mut markets = maybe_set.unwrap_or_default();
-            markets.insert(market.clone());
-            Ok(markets)
-        })?;
+        state::enter_market(deps.storage, &account, &market)?;
 
         Ok(Response::new()
             .add_attribute("action", "enter_market")
@@ -347,19 +380,12 @@ mod execute {
             .add_attribute("account", account))
     }
 
-    pub fn exit_market(
-        deps: DepsMut,
-        info: MessageInfo,
-        market: Addr,
-    ) -> Result<Response, ContractError> {
-        let common_token = CONFIG.load(deps.storage)?.common_token;
-        let mut markets = ENTERED_MARKETS
-            .may_load(deps.storage, &info.sender)?
-            .unwrap_or_default();
-
-        if !markets.contains(&market) {
+    /// Removes `market` from the markets entered by `account`, failing if the account has debt
+    /// there. Doesn't check the credit line left.
+    fn leave_market(deps: DepsMut, account: &Addr, market: &Addr) -> Result<(), ContractError> {
+        if !state::is_on_market(deps.storage, account, market) {
             return Err(ContractError::NotOnMarket {
-                address: info.sender,
+                address: account.clone(),
                 market: market.clone(),
             });
         }
@@ -367,24 +393,35 @@ mod execute {
         let market_credit_line: CreditLineResponse = deps.querier.query_wasm_smart(
             market.clone(),
             &MarketQueryMsg::CreditLine {
-                account: info.sender.to_string(),
+                account: account.to_string(),
             },
         )?;
 
         if !market_credit_line.debt.amount.is_zero() {
             return Err(ContractError::DebtOnMarket {
-                address: info.sender,
-                market,
+                address: account.clone(),
+                market: market.clone(),
                 debt: market_credit_line.debt,
             });
         }
 
+        state::exit_market(deps.storage, account, market);
+        Ok(())
+    }
+
+    pub fn exit_market(
+        mut deps: DepsMut,
+        info: MessageInfo,
+        market: Addr,
+    ) -> Result<Response, ContractError> {
+        let common_token = CONFIG.load(deps.storage)?.common_token;
+
         // It can be removed before everything is checked, as if anything would fail, this removal
         // would not be applied. And in `reduced_credit_line` we don't want this market to be
         // there, so removing early.
-        markets.remove(&market);
+        leave_market(deps.branch(), &info.sender, &market)?;
 
-        let reduced_credit_line = markets
+        let reduced_credit_line = state::entered_markets(deps.storage, &info.sender)?
             .iter()
             .map(|market| -> Result<CreditLineValues, ContractError> {
                 let price_response: CreditLineResponse = deps.querier.query_wasm_smart(
@@ -413,8 +450,6 @@ mod execute {
             });
         }
 
-        ENTERED_MARKETS.save(deps.storage, &info.sender, &markets)?;
-
         Ok(Response::new()
             .add_attribute("action", "exit_market")
             .add_attribute("market", market)
@@ -427,20 +462,16 @@ mod execute {
         max_collateral: Coin,
         amount_to_repay: Coin,
     ) -> Result<Response, ContractError> {
-        let collateral_market =
-            query::market(deps.as_ref(), max_collateral.denom.to_string())?.market;
+        let max_collateral_denom = max_collateral.denom.to_string();
+        let collateral_market = query::market(deps.as_ref(), max_collateral_denom.clone())?.market;
         let debt_market = query::market(deps.as_ref(), amount_to_repay.denom.to_string())?.market;
 
-        let markets = ENTERED_MARKETS
-            .may_load(deps.storage, &sender)?
-            .unwrap_or_default();
-
-        if !markets.contains(&collateral_market) {
+        if !state::is_on_market(deps.storage, &sender, &collateral_market) {
             return Err(ContractError::NotOnMarket {
                 address: sender,
                 market: collateral_market,
             });
-        } else if !markets.contains(&debt_market) {
+        } else if !state::is_on_market(deps.storage, &sender, &debt_market) {
             return Err(ContractError::NotOnMarket {
                 address: sender,
                 market: debt_market,
@@ -507,9 +538,204 @@ mod execute {
         });
 
         Ok(Response::new()
+            .add_attribute("action", "repay_with_collateral")
+            .add_attribute("sender", sender)
+            .add_attribute("collateral_denom", max_collateral_denom)
+            .add_attribute("debt_denom", amount_to_repay.denom.to_string())
+            .add_attribute("amount", amount_to_repay.amount)
             .add_submessage(swap_withdraw_from_msg)
             .add_submessage(repay_to_msg))
     }
+
+    /// Handler for `ExecuteMsg::AccrueAll`
+    pub fn accrue_all(
+        deps: DepsMut,
+        sender: Addr,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> Result<Response, ContractError> {
+        let markets = query::list_markets(deps.as_ref(), start_after, limit)?.markets;
+
+        let msg = to_binary(&MarketExecuteMsg::AccrueInterestFor {
+            recipient: sender.to_string(),
+        })?;
+        let accrue_msgs = markets.iter().map(|market| {
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: market.market.to_string(),
+                msg: msg.clone(),
+                funds: vec![],
+            })
+        });
+
+        let mut response = Response::new()
+            .add_attribute("action", "accrue_all")
+            .add_attribute("sender", sender)
+            .add_submessages(accrue_msgs);
+        if let Some(last) = markets.last().and_then(|m| m.market_token.clone().native()) {
+            response = response.add_attribute("last_market", last);
+        }
+
+        Ok(response)
+    }
+
+    /// Handler for `ExecuteMsg::Batch`
+    pub fn batch(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        ops: Vec<Operation>,
+    ) -> Result<Response, ContractError> {
+        if ops.is_empty() {
+            return Err(ContractError::EmptyBatch {});
+        }
+
+        let account = info.sender;
+        let ops_count = ops.len();
+        let mut expected_funds: Vec<cosmwasm_std::Coin> = vec![];
+        let mut check_credit_line = false;
+
+        let market_msg = |amount: &Coin,
+                          msg: MarketExecuteMsg,
+                          funds: Vec<cosmwasm_std::Coin>|
+         -> Result<WasmMsg, ContractError> {
+            let denom = amount
+                .denom
+                .as_native()
+                .ok_or(ContractError::Cw20TokensNotSupported)?;
+            let market = query::market(deps.as_ref(), denom.to_owned())?.market;
+            Ok(WasmMsg::Execute {
+                contract_addr: market.to_string(),
+                msg: to_binary(&msg)?,
+                funds,
+            })
+        };
+        let callback_msg = |callback: BatchCallback| -> Result<WasmMsg, ContractError> {
+            let msg = ExecuteMsg::BatchCallback {
+                account: account.to_string(),
+                callback,
+            };
+            Ok(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                msg: to_binary(&msg)?,
+                funds: vec![],
+            })
+        };
+
+        let mut msgs = vec![];
+        for op in ops {
+            let msg = match op {
+                Operation::Deposit { amount } => {
+                    let funds = add_funds(&mut expected_funds, &amount);
+                    let msg = MarketExecuteMsg::DepositFor {
+                        account: account.to_string(),
+                    };
+                    market_msg(&amount, msg, funds)?
+                }
+                Operation::Withdraw { amount } => {
+                    check_credit_line = true;
+                    let msg = MarketExecuteMsg::WithdrawFor {
+                        account: account.to_string(),
+                        amount: amount.amount,
+                    };
+                    market_msg(&amount, msg, vec![])?
+                }
+                Operation::Borrow { amount } => {
+                    check_credit_line = true;
+                    let msg = MarketExecuteMsg::BorrowFor {
+                        account: account.to_string(),
+                        amount: amount.amount,
+                    };
+                    market_msg(&amount, msg, vec![])?
+                }
+                Operation::Repay { amount } => {
+                    let funds = add_funds(&mut expected_funds, &amount);
+                    let msg = MarketExecuteMsg::RepayFor {
+                        account: account.to_string(),
+                    };
+                    market_msg(&amount, msg, funds)?
+                }
+                Operation::ExitMarket { market } => {
+                    check_credit_line = true;
+                    callback_msg(BatchCallback::ExitMarket { market })?
+                }
+            };
+            msgs.push(msg);
+        }
+
+        let mut sent_funds = info.funds;
+        sent_funds.sort_by(|a, b| a.denom.cmp(&b.denom));
+        expected_funds.sort_by(|a, b| a.denom.cmp(&b.denom));
+        if sent_funds != expected_funds {
+            return Err(ContractError::BatchFundsMismatch {
+                expected: expected_funds,
+                sent: sent_funds,
+            });
+        }
+
+        // Deposits and repayments alone can't make the account less healthy
+        if check_credit_line {
+            msgs.push(callback_msg(BatchCallback::CheckCreditLine {})?);
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "batch")
+            .add_attribute("sender", &account)
+            .add_attribute("operations", ops_count.to_string())
+            .add_messages(msgs))
+    }
+
+    /// Adds `amount` to the funds a batch expects, returning the funds to send along with it
+    fn add_funds(expected: &mut Vec<cosmwasm_std::Coin>, amount: &Coin) -> Vec<cosmwasm_std::Coin> {
+        let amount = cosmwasm_std::coin(amount.amount.u128(), amount.denom.to_string());
+        match expected.iter_mut().find(|coin| coin.denom == amount.denom) {
+            Some(coin) => coin.amount += amount.amount,
+            None => expected.push(amount.clone()),
+        }
+        vec![amount]
+    }
+
+    /// Handler for `ExecuteMsg::BatchCallback`
+    pub fn batch_callback(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        account: Addr,
+        callback: BatchCallback,
+    ) -> Result<Response, ContractError> {
+        ensure_eq!(
+            info.sender,
+            env.contract.address,
+            ContractError::Unauthorized {}
+        );
+
+        match callback {
+            BatchCallback::ExitMarket { market } => {
+                let market = deps.api.addr_validate(&market)?;
+                leave_market(deps, &account, &market)?;
+
+                Ok(Response::new()
+                    .add_attribute("action", "exit_market")
+                    .add_attribute("market", market)
+                    .add_attribute("account", account))
+            }
+            BatchCallback::CheckCreditLine {} => {
+                let common_token = CONFIG.load(deps.storage)?.common_token;
+                let credit_line = query::total_credit_line(deps.as_ref(), account.to_string())?
+                    .validate(&Token::Native(common_token))?;
+                if credit_line.credit_line < credit_line.debt {
+                    return Err(ContractError::NotEnoughCollat {
+                        debt: credit_line.debt,
+                        credit_line: credit_line.credit_line,
+                        collateral: credit_line.collateral,
+                    });
+                }
+
+                Ok(Response::new()
+                    .add_attribute("action", "check_credit_line")
+                    .add_attribute("account", account))
+            }
+        }
+    }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -538,22 +764,48 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractErr
             limit,
         } => to_binary(&query::entered_markets(deps, account, start_after, limit)?)?,
         IsOnMarket { account, market } => to_binary(&query::is_on_market(deps, account, market)?)?,
+        Portfolio { account } => to_binary(&query::portfolio(deps, account)?)?,
+        ProtocolStats { start_after, limit } => to_binary(&query::protocol_stats(
+            deps,
+            start_after
+                .map(|sa| sa.native().ok_or(ContractError::Cw20TokensNotSupported))
+                .transpose()?,
+            limit,
+        )?)?,
+        ListMarketParticipants {
+            market,
+            start_after,
+            limit,
+        } => to_binary(&query::market_participants(
+            deps,
+            market,
+            start_after,
+            limit,
+        )?)?,
     };
 
     Ok(res)
 }
 
 mod query {
-    use cosmwasm_std::{Order, StdResult};
+    use cosmwasm_std::{Order, StdResult, Uint128};
     use cw_storage_plus::Bound;
-    use isotonic_market::msg::QueryMsg as MarketQueryMsg;
+    use isotonic_market::msg::{
+        ApyResponse, InterestResponse, QueryMsg as MarketQueryMsg, ReserveResponse,
+        StableDebtResponse, TermLoansResponse, TokensBalanceResponse,
+    };
+    use isotonic_market::state::Config as MarketConfiguration;
+    use isotonic_token::msg::{QueryMsg as TokenQueryMsg, TokenInfoResponse};
     use utils::credit_line::{CreditLineResponse, CreditLineValues};
+    use utils::{coin::Coin, price::PriceRate};
 
     use crate::{
         msg::{
-            IsOnMarketResponse, ListEnteredMarketsResponse, ListMarketsResponse, MarketResponse,
+            IsOnMarketResponse, ListEnteredMarketsResponse, ListMarketParticipantsResponse,
+            ListMarketsResponse, MarketResponse, MarketStatsResponse, MarketStatus,
+            PortfolioPosition, PortfolioResponse, ProtocolStatsResponse,
         },
-        state::{ENTERED_MARKETS, MARKETS},
+        state::{ENTERED_MARKETS, MARKETS, MARKET_PARTICIPANTS},
     };
 
     use super::*;
@@ -563,6 +815,7 @@ mod query {
             .may_load(deps.storage, &market_token)?
             .ok_or_else(|| ContractError::NoMarket(market_token.clone()))?;
 
+        let status = state.status();
         let addr = state
             .to_addr()
             .ok_or_else(|| ContractError::MarketCreating(market_token.clone()))?;
@@ -570,6 +823,7 @@ mod query {
         Ok(MarketResponse {
             market_token: Token::Native(market_token),
             market: addr,
+            status,
         })
     }
 
@@ -592,9 +846,11 @@ mod query {
             .map(|m| {
                 let (market_token, market) = m?;
 
+                let status = market.status();
                 let result = market.to_addr().map(|addr| MarketResponse {
                     market_token: Token::Native(market_token),
                     market: addr,
+                    status,
                 });
 
                 Ok(result)
@@ -606,15 +862,180 @@ mod query {
         Ok(ListMarketsResponse { markets: markets? })
     }
 
+    /// Handler for `QueryMsg::Portfolio`
+    pub fn portfolio(deps: Deps, account: String) -> Result<PortfolioResponse, ContractError> {
+        let common_token = Token::Native(CONFIG.load(deps.storage)?.common_token);
+        let markets = state::entered_markets(deps.storage, &Addr::unchecked(&account))?;
+
+        let positions = markets
+            .into_iter()
+            .map(|market| -> Result<_, ContractError> {
+                let market_cfg: MarketConfiguration = deps
+                    .querier
+                    .query_wasm_smart(&market, &MarketQueryMsg::Configuration {})?;
+                let balances: TokensBalanceResponse = deps.querier.query_wasm_smart(
+                    &market,
+                    &MarketQueryMsg::TokensBalance {
+                        account: account.clone(),
+                    },
+                )?;
+                let credit_line: CreditLineResponse = deps.querier.query_wasm_smart(
+                    &market,
+                    &MarketQueryMsg::CreditLine {
+                        account: account.clone(),
+                    },
+                )?;
+                let apy: ApyResponse = deps
+                    .querier
+                    .query_wasm_smart(&market, &MarketQueryMsg::Apy {})?;
+                let stable_debt: StableDebtResponse = deps.querier.query_wasm_smart(
+                    &market,
+                    &MarketQueryMsg::StableDebt {
+                        account: account.clone(),
+                    },
+                )?;
+                let term_debt = term_debt(deps, &market, &account)?;
+
+                Ok(PortfolioPosition {
+                    market,
+                    market_token: Token::Native(market_cfg.market_token),
+                    ltokens: balances.ltokens,
+                    btokens: balances.btokens,
+                    stable_debt: stable_debt.amount,
+                    term_debt,
+                    credit_line,
+                    apy,
+                    collateral_ratio: market_cfg.collateral_ratio,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let total = positions
+            .iter()
+            .map(|position| position.credit_line.validate(&common_token))
+            .collect::<Result<Vec<CreditLineValues>, _>>()?
+            .iter()
+            .sum::<CreditLineValues>();
+        let health = total.health();
+
+        Ok(PortfolioResponse {
+            positions,
+            total: total.make_response(common_token),
+            health,
+        })
+    }
+
+    /// Sums the term loans of `account` in `market`, going through all pages
+    fn term_debt(deps: Deps, market: &Addr, account: &str) -> Result<Uint128, ContractError> {
+        let mut total = Uint128::zero();
+        let mut start_after = None;
+        loop {
+            let page: TermLoansResponse = deps.querier.query_wasm_smart(
+                market,
+                &MarketQueryMsg::TermLoans {
+                    account: account.to_owned(),
+                    start_after,
+                    limit: None,
+                },
+            )?;
+            match page.loans.last() {
+                Some(last) => start_after = Some(last.id),
+                None => return Ok(total),
+            }
+            total += page.loans.iter().map(|loan| loan.amount).sum::<Uint128>();
+        }
+    }
+
+    fn market_stats(
+        deps: Deps,
+        market_token: String,
+        market: Addr,
+        status: MarketStatus,
+    ) -> Result<MarketStatsResponse, ContractError> {
+        let market_cfg: MarketConfiguration = deps
+            .querier
+            .query_wasm_smart(&market, &MarketQueryMsg::Configuration {})?;
+        let supply_of = |token: &Addr| -> StdResult<Uint128> {
+            let info: TokenInfoResponse = deps
+                .querier
+                .query_wasm_smart(token, &TokenQueryMsg::TokenInfo {})?;
+            Ok(info.total_supply.display_amount())
+        };
+        let ReserveResponse { reserve } = deps
+            .querier
+            .query_wasm_smart(&market, &MarketQueryMsg::Reserve {})?;
+        let interest: InterestResponse = deps
+            .querier
+            .query_wasm_smart(&market, &MarketQueryMsg::Interest {})?;
+        let apy: ApyResponse = deps
+            .querier
+            .query_wasm_smart(&market, &MarketQueryMsg::Apy {})?;
+        let price: PriceRate = deps
+            .querier
+            .query_wasm_smart(&market, &MarketQueryMsg::PriceMarketLocalPerCommon {})?;
+
+        Ok(MarketStatsResponse {
+            market_token: Token::Native(market_token),
+            market,
+            status,
+            total_supplied: supply_of(&market_cfg.ltoken_contract)?,
+            total_borrowed: supply_of(&market_cfg.btoken_contract)? + interest.fixed_rate_debt,
+            reserve,
+            utilisation: interest.utilisation,
+            apy,
+            price: price.rate_sell_per_buy,
+        })
+    }
+
+    /// Handler for `QueryMsg::ProtocolStats`
+    pub fn protocol_stats(
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> Result<ProtocolStatsResponse, ContractError> {
+        let common_token = Token::Native(CONFIG.load(deps.storage)?.common_token);
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        // Totals visit every market, so only the first page carries them
+        let with_totals = start_after.is_none();
+        let start = start_after.as_deref().map(Bound::exclusive);
+
+        let mut markets = vec![];
+        let mut tvl = Uint128::zero();
+        let mut total_debt = Uint128::zero();
+        for entry in MARKETS.range(deps.storage, start, None, Order::Ascending) {
+            if !with_totals && markets.len() >= limit {
+                break;
+            }
+
+            let (market_token, state) = entry?;
+            let status = state.status();
+            let market = match state.to_addr() {
+                Some(market) => market,
+                None => continue,
+            };
+
+            let stats = market_stats(deps, market_token, market, status)?;
+            tvl += stats.total_supplied * stats.price;
+            total_debt += stats.total_borrowed * stats.price;
+            if markets.len() < limit {
+                markets.push(stats);
+            }
+        }
+
+        Ok(ProtocolStatsResponse {
+            markets,
+            tvl: with_totals.then(|| Coin::new(tvl.u128(), common_token.clone())),
+            total_debt: with_totals.then(|| Coin::new(total_debt.u128(), common_token)),
+        })
+    }
+
     /// Handler for `QueryMsg::TotalCreditLine`
     pub fn total_credit_line(
         deps: Deps,
         account: String,
     ) -> Result<CreditLineResponse, ContractError> {
         let common_token = CONFIG.load(deps.storage)?.common_token;
-        let markets = ENTERED_MARKETS
-            .may_load(deps.storage, &Addr::unchecked(&account))?
-            .unwrap_or_default();
+        let markets = state::entered_markets(deps.storage, &Addr::unchecked(&account))?;
 
         let total_credit_line: CreditLineValues = markets
             .into_iter()
@@ -641,27 +1062,40 @@ mod query {
         start_after: Option<String>,
         limit: Option<u32>,
     ) -> Result<ListEnteredMarketsResponse, ContractError> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
         let account = Addr::unchecked(account);
-        let markets = ENTERED_MARKETS
-            .may_load(deps.storage, &account)?
-            .unwrap_or_default()
-            .into_iter();
-
-        let markets = if let Some(start_after) = &start_after {
-            Either::Left(
-                markets
-                    .skip_while(move |market| market != start_after)
-                    .skip(1),
-            )
-        } else {
-            Either::Right(markets)
-        };
+        let start_after = start_after.map(Addr::unchecked);
+        let start = start_after.as_ref().map(Bound::exclusive);
 
-        let markets = markets.take(limit.unwrap_or(u32::MAX) as usize).collect();
+        let markets = ENTERED_MARKETS
+            .prefix(&account)
+            .keys(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .collect::<StdResult<_>>()?;
 
         Ok(ListEnteredMarketsResponse { markets })
     }
 
+    pub fn market_participants(
+        deps: Deps,
+        market: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> Result<ListMarketParticipantsResponse, ContractError> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let market = Addr::unchecked(market);
+        let start_after = start_after.map(Addr::unchecked);
+        let start = start_after.as_ref().map(Bound::exclusive);
+
+        let accounts = MARKET_PARTICIPANTS
+            .prefix(&market)
+            .keys(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .collect::<StdResult<_>>()?;
+
+        Ok(ListMarketParticipantsResponse { accounts })
+    }
+
     pub fn is_on_market(
         deps: Deps,
         account: String,
@@ -669,12 +1103,9 @@ mod query {
     ) -> Result<IsOnMarketResponse, ContractError> {
         let account = Addr::unchecked(account);
         let market = Addr::unchecked(market);
-        let markets = ENTERED_MARKETS
-            .may_load(deps.storage, &account)?
-            .unwrap_or_default();
 
         Ok(IsOnMarketResponse {
-            participating: markets.contains(&market),
+            participating: state::is_on_market(deps.storage, &account, &market),
         })
     }
 }
@@ -717,7 +1148,8 @@ mod reply {
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn sudo(deps: DepsMut, _env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
     use SudoMsg::*;
-    match msg {
+    let change = ParameterChange::from_msg(&msg)?;
+    let response = match msg {
         AdjustMarketId { new_market_id } => sudo::adjust_market_id(deps, new_market_id),
         AdjustTokenId { new_token_id } => sudo::adjust_token_id(deps, new_token_id),
         AdjustCommonToken { new_common_token } => sudo::adjust_common_token(
@@ -734,7 +1166,26 @@ pub fn sudo(deps: DepsMut, _env: Env, msg: SudoMsg) -> Result<Response, Contract
             liquidation_fee,
             liquidation_initiation_fee,
         } => sudo::adjust_liquidation(deps, liquidation_fee, liquidation_initiation_fee),
-    }
+        DeprecateMarket {
+            market_token,
+            collateral_ratio_ramp_end,
+            interest_rates,
+        } => sudo::deprecate_market(
+            deps,
+            market_token
+                .native()
+                .ok_or(ContractError::Cw20TokensNotSupported)?,
+            collateral_ratio_ramp_end,
+            interest_rates,
+        ),
+        RemoveMarket { market_token } => sudo::remove_market(
+            deps,
+            market_token
+                .native()
+                .ok_or(ContractError::Cw20TokensNotSupported)?,
+        ),
+    }?;
+    Ok(response.add_event(change.to_event()))
 }
 
 mod sudo {
@@ -743,7 +1194,12 @@ mod sudo {
 
     use cosmwasm_std::{Decimal, Order, WasmMsg};
 
-    use isotonic_market::msg::{ExecuteMsg as MarketExecuteMsg, MigrateMsg as MarketMigrateMsg};
+    use isotonic_market::msg::{
+        ExecuteMsg as MarketExecuteMsg, MigrateMsg as MarketMigrateMsg, QueryMsg as MarketQueryMsg,
+    };
+    use isotonic_market::state::Config as MarketConfiguration;
+    use isotonic_token::msg::{QueryMsg as TokenQueryMsg, TokenInfoResponse};
+    use utils::{interest::Interest, time::Expiration};
 
     pub fn adjust_market_id(deps: DepsMut, new_market_id: u64) -> Result<Response, ContractError> {
         let mut cfg = CONFIG.load(deps.storage)?;
@@ -772,8 +1228,8 @@ mod sudo {
         })?;
         let messages = MARKETS
             .range(deps.storage, None, None, Order::Ascending)
-            .filter_map(|m| match m {
-                Ok((_, MarketState::Ready(addr))) => Some(SubMsg::new(WasmMsg::Execute {
+            .filter_map(|m| match m.map(|(_, state)| state.to_addr()) {
+                Ok(Some(addr)) => Some(SubMsg::new(WasmMsg::Execute {
                     contract_addr: addr.to_string(),
                     msg: msg.clone(),
                     funds: vec![],
@@ -801,11 +1257,69 @@ mod sudo {
         Ok(Response::new())
     }
 
+    pub fn deprecate_market(
+        deps: DepsMut,
+        market_token: String,
+        collateral_ratio_ramp_end: Option<Expiration>,
+        interest_rates: Option<Interest>,
+    ) -> Result<Response, ContractError> {
+        let addr = MARKETS
+            .may_load(deps.storage, &market_token)?
+            .ok_or_else(|| ContractError::NoMarket(market_token.clone()))?
+            .to_addr()
+            .ok_or_else(|| ContractError::MarketCreating(market_token.clone()))?;
+        MARKETS.save(
+            deps.storage,
+            &market_token,
+            &MarketState::Deprecated(addr.clone()),
+        )?;
+
+        let msg = to_binary(&MarketExecuteMsg::Deprecate {
+            collateral_ratio_ramp_end,
+            interest_rates,
+        })?;
+        Ok(Response::new()
+            .add_attribute("action", "deprecate_market")
+            .add_attribute("market_token", market_token)
+            .add_message(WasmMsg::Execute {
+                contract_addr: addr.to_string(),
+                msg,
+                funds: vec![],
+            }))
+    }
+
+    pub fn remove_market(deps: DepsMut, market_token: String) -> Result<Response, ContractError> {
+        let addr = match MARKETS.may_load(deps.storage, &market_token)? {
+            Some(MarketState::Deprecated(addr)) => addr,
+            Some(_) => return Err(ContractError::MarketNotDeprecated(market_token)),
+            None => return Err(ContractError::NoMarket(market_token)),
+        };
+
+        let market_cfg: MarketConfiguration = deps
+            .querier
+            .query_wasm_smart(addr, &MarketQueryMsg::Configuration {})?;
+        for token in [market_cfg.ltoken_contract, market_cfg.btoken_contract] {
+            let info: TokenInfoResponse = deps
+                .querier
+                .query_wasm_smart(token, &TokenQueryMsg::TokenInfo {})?;
+            if !info.total_supply.is_zero() {
+                return Err(ContractError::MarketNotEmpty(market_token));
+            }
+        }
+
+        MARKETS.remove(deps.storage, &market_token);
+        Ok(Response::new()
+            .add_attribute("action", "remove_market")
+            .add_attribute("market_token", market_token))
+    }
+
     fn find_market(deps: Deps, market_addr: &Addr) -> bool {
         let found = MARKETS
             .range(deps.storage, None, None, Order::Ascending)
             .find(|m| match m {
-                Ok((_, MarketState::Ready(addr))) => market_addr == addr,
+                Ok((_, MarketState::Ready(addr) | MarketState::Deprecated(addr))) => {
+                    market_addr == addr
+                }
                 _ => false,
             });
         found.is_some()
@@ -832,3 +1346,11 @@ mod sudo {
         }))
     }
 }
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    state::migrate_entered_markets(deps.storage)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new())
+}
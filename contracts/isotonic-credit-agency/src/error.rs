@@ -39,6 +39,12 @@ pub enum ContractError {
     #[error("A market for base asset {0} already exists")]
     MarketAlreadyExists(String),
 
+    #[error("Market for base asset {0} is not deprecated")]
+    MarketNotDeprecated(String),
+
+    #[error("Market for base asset {0} still has supply or debt")]
+    MarketNotEmpty(String),
+
     #[error("Account cannot be liquidated as it does not have more debt then credit line")]
     LiquidationNotAllowed {},
 
@@ -86,6 +92,15 @@ pub enum ContractError {
     #[error("Cw20 tokens are not supported yet")]
     Cw20TokensNotSupported,
 
+    #[error("Batch has no operations")]
+    EmptyBatch {},
+
+    #[error("Funds sent don't match the batch deposits and repayments: expected {expected:?}, got {sent:?}")]
+    BatchFundsMismatch {
+        expected: Vec<cosmwasm_std::Coin>,
+        sent: Vec<cosmwasm_std::Coin>,
+    },
+
     #[error("Repaying loan using collateral not allowed with these values - the account could end up undercollateralized")]
     RepayingLoanUsingCollateralFailed {},
 
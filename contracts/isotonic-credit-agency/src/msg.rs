@@ -1,7 +1,9 @@
 use cosmwasm_std::{Addr, Decimal, Uint128};
-use isotonic_market::msg::MigrateMsg as MarketMigrateMsg;
+use isotonic_market::msg::{ApyResponse, MigrateMsg as MarketMigrateMsg};
 
-use utils::{coin::Coin, interest::Interest, token::Token};
+use utils::{
+    coin::Coin, credit_line::CreditLineResponse, interest::Interest, time::Expiration, token::Token,
+};
 
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -28,8 +30,10 @@ pub struct InstantiateMsg {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
-    CreateMarket(MarketConfig),
-    /// Tries to perform liquidation on passed account using a specific kind of collateral
+    CreateMarket(Box<MarketConfig>),
+    /// Tries to perform liquidation on passed account using a specific kind of collateral.
+    /// Accounts within their credit line can only be liquidated for their overdue term loans,
+    /// and `amount_to_repay` is capped at that debt.
     Liquidate {
         account: String,
         collateral_denom: Token,
@@ -55,6 +59,57 @@ pub enum ExecuteMsg {
         /// How much of the loan is trying to be repaid
         amount_to_repay: Coin,
     },
+    /// Charges interest on a page of markets, as ordered by `ListMarkets`. Any accrual
+    /// reward the markets pay out goes to the sender.
+    AccrueAll {
+        start_after: Option<Token>,
+        limit: Option<u32>,
+    },
+    /// Performs `ops` in order on behalf of the sender. The credit line is checked once, after
+    /// all operations, so intermediate states may be undercollateralized. Funds sent with this
+    /// message have to match the deposits and repayments exactly.
+    Batch {
+        ops: Vec<Operation>,
+    },
+    /// Step of a `Batch` which has to run after the market operations before it.
+    /// Only callable by the credit agency itself.
+    BatchCallback {
+        account: String,
+        callback: BatchCallback,
+    },
+}
+
+/// Single step of `ExecuteMsg::Batch`. The market is selected by the denom of the amount.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Operation {
+    Deposit {
+        amount: Coin,
+    },
+    Withdraw {
+        amount: Coin,
+    },
+    Borrow {
+        amount: Coin,
+    },
+    /// Repays variable-rate debt. Surplus is returned to the sender.
+    Repay {
+        amount: Coin,
+    },
+    /// Same as `ExecuteMsg::ExitMarket`, except that the credit line is only checked at the end
+    ExitMarket {
+        market: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchCallback {
+    ExitMarket {
+        market: String,
+    },
+    /// Fails if the debt of the account exceeds its credit line
+    CheckCreditLine {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -79,6 +134,19 @@ pub struct MarketConfig {
     pub price_oracle: String,
     /// Defines the portion of borrower interest that is converted into reserves (0 <= x <= 1)
     pub reserve_factor: Decimal,
+    /// Portion of every borrowed amount added to the debt and converted into reserves (0 <= x < 1)
+    #[serde(default)]
+    pub origination_fee: Decimal,
+    /// If not set, the market token can be supplied but never borrowed
+    #[serde(default = "default_enabled")]
+    pub borrowing_enabled: bool,
+    /// If not set, deposits into this market never count towards the credit line
+    #[serde(default = "default_enabled")]
+    pub collateral_enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -111,6 +179,24 @@ pub enum QueryMsg {
     /// included in market before leaving it (to not waste tokens on obsolete call).
     /// Returns `IsOnMarketResponse`
     IsOnMarket { account: String, market: String },
+    /// Positions of the account on every entered market, with their values, APYs and totals.
+    /// Returns `PortfolioResponse`
+    Portfolio { account: String },
+    /// Supply, debt, reserves, utilisation, APYs and price of every market, paginated by base
+    /// asset. The first page (without `start_after`) also has the value supplied to and
+    /// borrowed from all markets in the common token, which queries every market.
+    /// Returns `ProtocolStatsResponse`
+    ProtocolStats {
+        start_after: Option<Token>,
+        limit: Option<u32>,
+    },
+    /// Lists accounts participating in the given market, ordered by address.
+    /// Returns `ListMarketParticipantsResponse`
+    ListMarketParticipants {
+        market: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
@@ -135,14 +221,40 @@ pub enum SudoMsg {
         liquidation_fee: Option<Decimal>,
         liquidation_initiation_fee: Option<Decimal>,
     },
+    /// Starts winding down the market for `market_token`. It takes no new deposits or
+    /// borrows, the collateral ratio optionally ramps to zero by `collateral_ratio_ramp_end`
+    /// and the interest rates can be stepped up to `interest_rates`.
+    DeprecateMarket {
+        market_token: Token,
+        collateral_ratio_ramp_end: Option<Expiration>,
+        interest_rates: Option<Interest>,
+    },
+    /// Removes a deprecated market from the list of markets, once nothing is supplied to or
+    /// borrowed from it anymore
+    RemoveMarket {
+        market_token: Token,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct MarketResponse {
     pub market_token: Token,
     pub market: Addr,
+    pub status: MarketStatus,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum MarketStatus {
+    Active,
+    /// Being wound down, no new deposits or borrows are accepted
+    Deprecated,
 }
 
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct MigrateMsg {}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct ListMarketsResponse {
     pub markets: Vec<MarketResponse>,
@@ -153,6 +265,65 @@ pub struct ListEnteredMarketsResponse {
     pub markets: Vec<Addr>,
 }
 
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ListMarketParticipantsResponse {
+    pub accounts: Vec<Addr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct PortfolioPosition {
+    pub market: Addr,
+    pub market_token: Token,
+    /// L-Token balance in the market token, including pending interest
+    pub ltokens: Uint128,
+    /// B-Token balance in the market token, including pending interest
+    pub btokens: Uint128,
+    /// Stable-rate debt in the market token, including accrued interest
+    pub stable_debt: Uint128,
+    /// Debt of all term loans in the market token, including interest and penalties
+    pub term_debt: Uint128,
+    /// Collateral, credit line and debt of this position in the common token, the debt
+    /// covering B-Tokens as well as stable-rate and term debt
+    pub credit_line: CreditLineResponse,
+    pub apy: ApyResponse,
+    pub collateral_ratio: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct PortfolioResponse {
+    pub positions: Vec<PortfolioPosition>,
+    /// Sum of the credit lines of all positions
+    pub total: CreditLineResponse,
+    /// Credit line divided by debt, the account can be liquidated below 1.
+    /// Not set if there is no debt.
+    pub health: Option<Decimal>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct MarketStatsResponse {
+    pub market_token: Token,
+    pub market: Addr,
+    pub status: MarketStatus,
+    /// Total L-Token supply in the market token
+    pub total_supplied: Uint128,
+    /// Total B-Token supply and fixed-rate debt in the market token
+    pub total_borrowed: Uint128,
+    pub reserve: Uint128,
+    pub utilisation: Decimal,
+    pub apy: ApyResponse,
+    /// Price of one market token in the common token
+    pub price: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ProtocolStatsResponse {
+    pub markets: Vec<MarketStatsResponse>,
+    /// Value supplied to all markets, in the common token. Only on the first page.
+    pub tvl: Option<Coin>,
+    /// Value borrowed from all markets, in the common token. Only on the first page.
+    pub total_debt: Option<Coin>,
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct IsOnMarketResponse {
     pub participating: bool,
@@ -1,3 +1,6 @@
+mod accrue_all;
+mod batch;
+mod deprecate;
 mod instantiate;
 mod liquidate;
 mod market_create;
@@ -0,0 +1,61 @@
+use cosmwasm_std::{coin, Decimal};
+use utils::token::Token;
+
+use super::suite::{SuiteBuilder, COMMON};
+
+#[test]
+fn accrues_interest_on_all_markets() {
+    let gov = "gov";
+    let lender = "lender";
+    let keeper = "keeper";
+    let mut suite = SuiteBuilder::new()
+        .with_gov(gov)
+        .with_funds(lender, &[coin(2000, "ATOM"), coin(2000, "OSMO")])
+        .with_pool(1, (coin(100, COMMON), coin(100, "ATOM")))
+        .with_pool(2, (coin(100, COMMON), coin(100, "OSMO")))
+        .build();
+
+    for (name, denom) in [("atom", "ATOM"), ("osmo", "OSMO")] {
+        suite
+            .create_market_quick(
+                gov,
+                name,
+                denom,
+                None,
+                (Decimal::percent(4), Decimal::percent(20)),
+                Decimal::percent(10),
+            )
+            .unwrap();
+        suite.sudo_adjust_market_accrual_reward(denom, 50).unwrap();
+        suite
+            .deposit_tokens_on_market(lender, coin(2000, denom))
+            .unwrap();
+        suite
+            .borrow_tokens_from_market(lender, coin(800, denom))
+            .unwrap();
+    }
+
+    suite.advance_seconds(isotonic_market::state::SECONDS_IN_YEAR as u64);
+    let now = suite.app().block_info().time.seconds();
+
+    // 12% interest on 800 borrowed tokens, 10% of it goes to reserves,
+    // half of the reserves go to the keeper
+    suite.accrue_all(keeper, None, 1).unwrap();
+    assert_eq!(suite.query_market_config("ATOM").unwrap().last_charged, now);
+    assert_eq!(
+        suite.app().wrap().query_balance(keeper, "ATOM").unwrap(),
+        coin(4, "ATOM")
+    );
+    assert_eq!(
+        suite.app().wrap().query_balance(keeper, "OSMO").unwrap(),
+        coin(0, "OSMO")
+    );
+
+    suite
+        .accrue_all(keeper, Token::Native("ATOM".to_owned()), None)
+        .unwrap();
+    assert_eq!(
+        suite.app().wrap().query_balance(keeper, "OSMO").unwrap(),
+        coin(4, "OSMO")
+    );
+}
@@ -0,0 +1,179 @@
+use cosmwasm_std::{coin, Uint128};
+use utils::coin::coin_native;
+use utils::credit_line::CreditLineValues;
+
+use super::suite::{Suite, SuiteBuilder, COMMON};
+use crate::error::ContractError;
+use crate::msg::{BatchCallback, Operation};
+
+const OSMO: &str = "OSMO";
+const ETH: &str = "ETH";
+
+fn suite_with_markets() -> Suite {
+    let mut suite = SuiteBuilder::new()
+        .with_gov("gov")
+        .with_funds("lender", &[coin(1000, ETH)])
+        .with_funds("user", &[coin(1000, OSMO)])
+        .with_pool(1, (coin(100, COMMON), coin(100, OSMO)))
+        .with_pool(2, (coin(100, COMMON), coin(100, ETH)))
+        .build();
+
+    suite
+        .create_market_quick("gov", "osmo", OSMO, None, None, None)
+        .unwrap();
+    suite
+        .create_market_quick("gov", "eth", ETH, None, None, None)
+        .unwrap();
+    suite
+        .deposit_tokens_on_market("lender", coin(1000, ETH))
+        .unwrap();
+
+    suite
+}
+
+#[test]
+fn deposit_and_borrow() {
+    let mut suite = suite_with_markets();
+
+    suite
+        .batch(
+            "user",
+            vec![
+                Operation::Deposit {
+                    amount: coin_native(1000, OSMO),
+                },
+                Operation::Borrow {
+                    amount: coin_native(500, ETH),
+                },
+            ],
+            &[coin(1000, OSMO)],
+        )
+        .unwrap();
+
+    let balance = suite.app().wrap().query_balance("user", ETH).unwrap();
+    assert_eq!(balance.amount.u128(), 500);
+    assert_eq!(
+        suite.query_total_credit_line("user").unwrap(),
+        CreditLineValues {
+            collateral: Uint128::new(1000),
+            credit_line: Uint128::new(500),
+            debt: Uint128::new(500),
+        }
+        .make_response(suite.common_token().clone())
+    );
+}
+
+#[test]
+fn repay_and_withdraw() {
+    let mut suite = suite_with_markets();
+    suite
+        .batch(
+            "user",
+            vec![
+                Operation::Deposit {
+                    amount: coin_native(1000, OSMO),
+                },
+                Operation::Borrow {
+                    amount: coin_native(500, ETH),
+                },
+            ],
+            &[coin(1000, OSMO)],
+        )
+        .unwrap();
+    let osmo_market = suite.query_market(OSMO).unwrap().market;
+
+    // Withdrawing before repaying leaves the account undercollateralized
+    suite
+        .withdraw_tokens_from_market("user", coin(1000, OSMO))
+        .unwrap_err();
+
+    // Within a batch only the final state counts
+    suite
+        .batch(
+            "user",
+            vec![
+                Operation::Withdraw {
+                    amount: coin_native(1000, OSMO),
+                },
+                Operation::Repay {
+                    amount: coin_native(500, ETH),
+                },
+                Operation::ExitMarket {
+                    market: osmo_market.to_string(),
+                },
+            ],
+            &[coin(500, ETH)],
+        )
+        .unwrap();
+
+    let balance = suite.app().wrap().query_balance("user", OSMO).unwrap();
+    assert_eq!(balance.amount.u128(), 1000);
+    assert_eq!(
+        suite.query_total_credit_line("user").unwrap(),
+        CreditLineValues::zero().make_response(suite.common_token().clone())
+    );
+    assert!(!suite.is_on_market("user", osmo_market.as_str()).unwrap());
+}
+
+#[test]
+fn undercollateralized_result() {
+    let mut suite = suite_with_markets();
+
+    let err = suite
+        .batch(
+            "user",
+            vec![
+                Operation::Deposit {
+                    amount: coin_native(1000, OSMO),
+                },
+                Operation::Borrow {
+                    amount: coin_native(600, ETH),
+                },
+            ],
+            &[coin(1000, OSMO)],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::NotEnoughCollat {
+            debt: Uint128::new(600),
+            credit_line: Uint128::new(500),
+            collateral: Uint128::new(1000),
+        },
+        err.downcast().unwrap()
+    );
+
+    // Nothing from the batch is applied
+    let balance = suite.app().wrap().query_balance("user", OSMO).unwrap();
+    assert_eq!(balance.amount.u128(), 1000);
+}
+
+#[test]
+fn invalid_batches() {
+    let mut suite = suite_with_markets();
+
+    let err = suite.batch("user", vec![], &[]).unwrap_err();
+    assert_eq!(ContractError::EmptyBatch {}, err.downcast().unwrap());
+
+    let err = suite
+        .batch(
+            "user",
+            vec![Operation::Deposit {
+                amount: coin_native(1000, OSMO),
+            }],
+            &[coin(500, OSMO)],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::BatchFundsMismatch {
+            expected: vec![coin(1000, OSMO)],
+            sent: vec![coin(500, OSMO)],
+        },
+        err.downcast().unwrap()
+    );
+
+    // Callbacks can only be sent by the credit agency
+    let err = suite
+        .batch_callback("user", "user", BatchCallback::CheckCreditLine {})
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+}
@@ -0,0 +1,214 @@
+use super::suite::{SuiteBuilder, COMMON};
+use crate::error::ContractError;
+use crate::msg::MarketStatus;
+
+use cosmwasm_std::{coin, Decimal, Uint128};
+use isotonic_market::ContractError as MarketError;
+use utils::time::Expiration;
+use utils::token::Token;
+
+#[test]
+fn deprecated_market_rejects_deposits_and_borrows() {
+    let lender = "lender";
+    let mut suite = SuiteBuilder::new()
+        .with_gov("gov")
+        .with_funds(lender, &[coin(1000, "OSMO")])
+        .with_pool(1, (coin(100, COMMON), coin(100, "OSMO")))
+        .build();
+
+    suite
+        .create_market_quick("gov", "osmo", "OSMO", None, None, None)
+        .unwrap();
+    suite
+        .deposit_tokens_on_market(lender, coin(500, "OSMO"))
+        .unwrap();
+
+    suite.sudo_deprecate_market("OSMO", None).unwrap();
+    assert_eq!(
+        suite.query_market("OSMO").unwrap().status,
+        MarketStatus::Deprecated
+    );
+
+    let err = suite
+        .deposit_tokens_on_market(lender, coin(500, "OSMO"))
+        .unwrap_err();
+    assert_eq!(MarketError::MarketDeprecated {}, err.downcast().unwrap());
+    let err = suite
+        .borrow_tokens_from_market(lender, coin(100, "OSMO"))
+        .unwrap_err();
+    assert_eq!(MarketError::MarketDeprecated {}, err.downcast().unwrap());
+
+    // Existing lenders can still leave
+    suite
+        .withdraw_tokens_from_market(lender, coin(500, "OSMO"))
+        .unwrap();
+}
+
+#[test]
+fn deprecation_ramps_collateral_ratio_to_zero() {
+    let lender = "lender";
+    let mut suite = SuiteBuilder::new()
+        .with_gov("gov")
+        .with_funds(lender, &[coin(1000, "OSMO")])
+        .with_pool(1, (coin(100, COMMON), coin(100, "OSMO")))
+        .build();
+
+    suite
+        .create_market_quick("gov", "osmo", "OSMO", None, None, None)
+        .unwrap();
+    suite
+        .deposit_tokens_on_market(lender, coin(1000, "OSMO"))
+        .unwrap();
+
+    let end = suite.app().block_info().time.plus_seconds(100);
+    suite
+        .sudo_deprecate_market("OSMO", Expiration::at_timestamp(end))
+        .unwrap();
+
+    suite.advance_seconds(50);
+    let cfg = suite.query_market_config("OSMO").unwrap();
+    assert!(cfg.deprecated);
+
+    suite.advance_seconds(50);
+    let cfg = suite.query_market_config("OSMO").unwrap();
+    assert_eq!(cfg.collateral_ratio, Decimal::zero());
+
+    // Collateral backing nothing can still be withdrawn, so the market can be emptied
+    suite
+        .withdraw_tokens_from_market(lender, coin(1000, "OSMO"))
+        .unwrap();
+    suite.sudo_remove_market("OSMO").unwrap();
+    assert!(suite.list_markets().unwrap().markets.is_empty());
+}
+
+#[test]
+fn list_markets_shows_status() {
+    let mut suite = SuiteBuilder::new().with_gov("gov").build();
+
+    suite
+        .create_market_quick("gov", "osmo", "OSMO", None, None, None)
+        .unwrap();
+    suite
+        .create_market_quick("gov", "atom", "ATOM", None, None, None)
+        .unwrap();
+    suite.sudo_deprecate_market("OSMO", None).unwrap();
+
+    let markets = suite.list_markets().unwrap().markets;
+    let statuses: Vec<_> = markets
+        .into_iter()
+        .map(|m| (m.market_token.native().unwrap(), m.status))
+        .collect();
+    assert_eq!(
+        statuses,
+        vec![
+            ("ATOM".to_owned(), MarketStatus::Active),
+            ("OSMO".to_owned(), MarketStatus::Deprecated),
+        ]
+    );
+}
+
+#[test]
+fn remove_market() {
+    let lender = "lender";
+    let mut suite = SuiteBuilder::new()
+        .with_gov("gov")
+        .with_funds(lender, &[coin(1000, "OSMO")])
+        .with_pool(1, (coin(100, COMMON), coin(100, "OSMO")))
+        .build();
+
+    suite
+        .create_market_quick("gov", "osmo", "OSMO", None, None, None)
+        .unwrap();
+    suite
+        .deposit_tokens_on_market(lender, coin(1000, "OSMO"))
+        .unwrap();
+
+    let err = suite.sudo_remove_market("OSMO").unwrap_err();
+    assert_eq!(
+        ContractError::MarketNotDeprecated("OSMO".to_owned()),
+        err.downcast().unwrap()
+    );
+
+    suite.sudo_deprecate_market("OSMO", None).unwrap();
+    let err = suite.sudo_remove_market("OSMO").unwrap_err();
+    assert_eq!(
+        ContractError::MarketNotEmpty("OSMO".to_owned()),
+        err.downcast().unwrap()
+    );
+
+    suite
+        .withdraw_tokens_from_market(lender, coin(1000, "OSMO"))
+        .unwrap();
+    suite.sudo_remove_market("OSMO").unwrap();
+
+    let err = suite.query_market("OSMO").unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("No market set up for base asset OSMO"));
+    assert!(suite.list_markets().unwrap().markets.is_empty());
+}
+
+#[test]
+fn collateral_of_deprecated_market_can_be_liquidated() {
+    let debtor = "debtor";
+    let liquidator = "liquidator";
+    let investor = "investor";
+    let osmo = "OSMO";
+    let atom = "ATOM";
+    let juno = "JUNO";
+
+    let mut suite = SuiteBuilder::new()
+        .with_gov("gov")
+        .with_common_token(osmo)
+        .with_funds(debtor, &[coin(580, atom)])
+        .with_funds(investor, &[coin(500, juno)])
+        .with_pool(
+            1,
+            (coin(100_000_000_000, osmo), coin(100_000_000_000, atom)),
+        )
+        .with_pool(
+            2,
+            (coin(100_000_000_000, osmo), coin(100_000_000_000, juno)),
+        )
+        .build();
+
+    suite
+        .create_market_quick("gov", "atom", atom, Decimal::percent(70), None, None)
+        .unwrap();
+    suite
+        .create_market_quick("gov", "juno", juno, Decimal::percent(70), None, None)
+        .unwrap();
+    suite
+        .deposit_tokens_on_market(investor, coin(500, juno))
+        .unwrap();
+    suite
+        .deposit_tokens_on_market(debtor, coin(580, atom))
+        .unwrap();
+    suite
+        .borrow_tokens_from_market(debtor, coin(400, juno))
+        .unwrap();
+
+    // The ATOM collateral stops backing the debt
+    let end = suite.app().block_info().time.plus_seconds(100);
+    suite
+        .sudo_deprecate_market(atom, Expiration::at_timestamp(end))
+        .unwrap();
+    suite.advance_seconds(100);
+
+    suite
+        .liquidate(
+            liquidator,
+            debtor,
+            Token::Native(atom.to_owned()),
+            coin(200, juno),
+        )
+        .unwrap();
+
+    let crl = suite
+        .query_total_credit_line(debtor)
+        .unwrap()
+        .validate(&Token::Native(osmo.to_owned()))
+        .unwrap();
+    assert_eq!(crl.debt, Uint128::new(200));
+    assert!(crl.credit_line.is_zero());
+}
@@ -1,9 +1,12 @@
-use super::suite::{SuiteBuilder, COMMON};
+use super::suite::{Suite, SuiteBuilder, COMMON};
 use crate::error::ContractError;
 
+use isotonic_market::msg::TermLending;
+
 use cosmwasm_std::{coin, coins, Decimal, Uint128};
 use osmo_bindings::Swap;
 use utils::credit_line::{CreditLineResponse, CreditLineValues};
+use utils::events::{parse_all, DebtKind, Liquidate, Repay};
 use utils::token::Token;
 
 const YEAR_IN_SECONDS: u64 = 365 * 24 * 3600;
@@ -117,7 +120,7 @@ fn liquidating_whole_debt() {
         )])
         .unwrap();
 
-    suite
+    let response = suite
         .liquidate(
             liquidator,
             debtor,
@@ -126,6 +129,22 @@ fn liquidating_whole_debt() {
         )
         .unwrap();
 
+    assert_eq!(
+        parse_all::<Liquidate>(&response.events).unwrap(),
+        vec![Liquidate {
+            liquidator: liquidator.to_owned(),
+            account: debtor.to_owned(),
+            collateral_denom: atom.to_owned(),
+            debt_denom: juno.to_owned(),
+            repaid: Uint128::new(500),
+        }]
+    );
+    let repaid = parse_all::<Repay>(&response.events).unwrap();
+    assert_eq!(repaid.len(), 1);
+    assert_eq!(repaid[0].account, debtor);
+    assert_eq!(repaid[0].kind, DebtKind::Variable);
+    assert_eq!(repaid[0].amount, Uint128::new(500));
+
     // reset pools
     suite
         .set_pool(&[(
@@ -559,3 +578,159 @@ fn receive_reward_fails_when_insufficient_collateral() {
         err.downcast().unwrap()
     );
 }
+
+/// ATOM and JUNO markets priced 1:1 in OSMO, the common token. `investor` lends 500 JUNO,
+/// which can be borrowed on term, and `debtor` deposits 1000 ATOM as collateral.
+fn suite_with_term_lending(debtor: &str, investor: &str) -> Suite {
+    let osmo = "OSMO";
+    let atom = "ATOM";
+    let juno = "JUNO";
+
+    let mut suite = SuiteBuilder::new()
+        .with_gov("gov")
+        .with_common_token(osmo)
+        .with_liquidation_fee(Decimal::percent(5))
+        .with_liquidation_initiation_fee(Decimal::percent(1))
+        .with_funds(debtor, &coins(1000, atom))
+        .with_funds(investor, &coins(500, juno))
+        .with_pool(
+            1,
+            (coin(100_000_000_000, osmo), coin(100_000_000_000, atom)),
+        )
+        .with_pool(
+            2,
+            (coin(100_000_000_000, osmo), coin(100_000_000_000, juno)),
+        )
+        .build();
+
+    suite
+        .create_market_quick("gov", "atom", atom, Decimal::percent(70), None, None)
+        .unwrap();
+    suite
+        .create_market_quick("gov", "juno", juno, Decimal::percent(70), None, None)
+        .unwrap();
+    suite
+        .sudo_adjust_market_term_lending(
+            juno,
+            Some(TermLending {
+                premium: Decimal::percent(2),
+                penalty_rate: Decimal::percent(10),
+                max_duration: YEAR_IN_SECONDS,
+                early_repayment_fee: Decimal::percent(1),
+            }),
+        )
+        .unwrap();
+
+    suite
+        .deposit_tokens_on_market(investor, coin(500, juno))
+        .unwrap();
+    suite
+        .deposit_tokens_on_market(debtor, coin(1000, atom))
+        .unwrap();
+
+    suite
+}
+
+#[test]
+fn overdue_term_loan_can_be_liquidated() {
+    let debtor = "debtor";
+    let liquidator = "liquidator";
+    let some_investor = "investor";
+
+    let osmo = "OSMO";
+    let atom = "ATOM";
+    let juno = "JUNO";
+
+    let mut suite = suite_with_term_lending(debtor, some_investor);
+    suite
+        .borrow_term_from_market(debtor, coin(100, juno), 1000)
+        .unwrap();
+
+    // The account is healthy and the loan is not due yet
+    let err = suite
+        .liquidate(
+            liquidator,
+            debtor,
+            Token::Native(atom.into()),
+            coin(100, juno),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::LiquidationNotAllowed {},
+        err.downcast().unwrap()
+    );
+
+    suite.advance_seconds(1001);
+    let loans = suite.query_market_term_loans(juno, debtor).unwrap().loans;
+    assert!(loans[0].overdue);
+    let debt = loans[0].amount;
+
+    suite
+        .liquidate(
+            liquidator,
+            debtor,
+            Token::Native(atom.into()),
+            coin(debt.u128(), juno),
+        )
+        .unwrap();
+
+    assert_eq!(
+        suite.query_market_term_loans(juno, debtor).unwrap().loans,
+        vec![]
+    );
+    let crl = suite
+        .query_total_credit_line(debtor)
+        .unwrap()
+        .validate(&Token::Native(osmo.to_string()))
+        .unwrap();
+    assert!(crl.debt.is_zero());
+}
+
+#[test]
+fn healthy_account_liquidation_capped_at_overdue_debt() {
+    let debtor = "debtor";
+    let liquidator = "liquidator";
+    let some_investor = "investor";
+
+    let osmo = "OSMO";
+    let atom = "ATOM";
+    let juno = "JUNO";
+
+    let mut suite = suite_with_term_lending(debtor, some_investor);
+    suite
+        .borrow_tokens_from_market(debtor, coin(100, juno))
+        .unwrap();
+    suite
+        .borrow_term_from_market(debtor, coin(100, juno), 1000)
+        .unwrap();
+
+    suite.advance_seconds(1001);
+    let debt_before = suite
+        .query_total_credit_line(debtor)
+        .unwrap()
+        .validate(&Token::Native(osmo.to_string()))
+        .unwrap()
+        .debt;
+    let overdue = suite.query_market_term_loans(juno, debtor).unwrap().loans[0].amount;
+
+    // The liquidator asks for the whole debt, but only the overdue loan is repaid
+    suite
+        .liquidate(
+            liquidator,
+            debtor,
+            Token::Native(atom.into()),
+            coin(debt_before.u128(), juno),
+        )
+        .unwrap();
+
+    assert_eq!(
+        suite.query_market_term_loans(juno, debtor).unwrap().loans,
+        vec![]
+    );
+    let crl = suite
+        .query_total_credit_line(debtor)
+        .unwrap()
+        .validate(&Token::Native(osmo.to_string()))
+        .unwrap();
+    assert_eq!(crl.debt, debt_before - overdue);
+}
@@ -348,3 +348,62 @@ fn exit_market_with_ltokens() {
 
     assert_eq!(markets, vec![market1, market2]);
 }
+
+#[test]
+fn entered_markets_pagination() {
+    let gov = "gov";
+    let actor = "actor";
+
+    let mut suite = SuiteBuilder::new().with_gov(gov).build();
+
+    let mut markets: Vec<_> = ["OSMO", "ETH", "BTC"]
+        .into_iter()
+        .map(|denom| {
+            suite
+                .create_market_quick(gov, &denom.to_lowercase(), denom, None, None, None)
+                .unwrap();
+            let market = suite.query_market(denom).unwrap().market;
+            suite.enter_market(market.as_str(), actor).unwrap();
+            market
+        })
+        .collect();
+    markets.sort();
+
+    let page = suite.list_entered_markets(actor, None, 2).unwrap();
+    assert_eq!(page, markets[..2]);
+    let page = suite
+        .list_entered_markets(actor, page[1].to_string(), 2)
+        .unwrap();
+    assert_eq!(page, markets[2..]);
+}
+
+#[test]
+fn list_market_participants() {
+    let gov = "gov";
+    let denom = "OSMO";
+
+    let mut suite = SuiteBuilder::new().with_gov(gov).build();
+
+    suite
+        .create_market_quick(gov, "osmo", denom, None, None, None)
+        .unwrap();
+    let market = suite.query_market(denom).unwrap().market;
+
+    for actor in ["carol", "alice", "bob"] {
+        suite.enter_market(market.as_str(), actor).unwrap();
+    }
+    suite.exit_market("bob", market.as_str()).unwrap();
+
+    let participants = suite
+        .list_market_participants(market.as_str(), None, None)
+        .unwrap();
+    assert_eq!(
+        participants,
+        vec![Addr::unchecked("alice"), Addr::unchecked("carol")]
+    );
+
+    let participants = suite
+        .list_market_participants(market.as_str(), "alice".to_owned(), 1)
+        .unwrap();
+    assert_eq!(participants, vec![Addr::unchecked("carol")]);
+}
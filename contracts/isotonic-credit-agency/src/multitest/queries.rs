@@ -1,6 +1,9 @@
+use cosmwasm_std::{coin, Decimal, Uint128};
+use isotonic_market::msg::{StableBorrowing, TermLending};
+use utils::credit_line::CreditLineValues;
 use utils::token::Token;
 
-use super::suite::SuiteBuilder;
+use super::suite::{SuiteBuilder, COMMON};
 
 #[test]
 fn query_market() {
@@ -65,7 +68,6 @@ fn list_markets_empty_list() {
 
 fn generate_denoms(prefix: &str, start: u32, end: u32) -> Vec<Token> {
     (start..end)
-        .into_iter()
         .map(|i| Token::Native(format!("{}{:02}", prefix, i)))
         .collect()
 }
@@ -149,3 +151,238 @@ fn list_markets_custom_pagination() {
     list2.sort();
     assert_eq!(list2, generate_denoms("TOKEN", 3, 5));
 }
+
+#[test]
+fn portfolio() {
+    let actor = "actor";
+    let lender = "lender";
+    let mut suite = SuiteBuilder::new()
+        .with_gov("gov")
+        .with_funds(actor, &[coin(1000, "OSMO")])
+        .with_funds(lender, &[coin(500, "ETH")])
+        // 1 OSMO is worth 2 common tokens
+        .with_pool(1, (coin(200, COMMON), coin(100, "OSMO")))
+        // 1 ETH is worth 0.5 common tokens
+        .with_pool(2, (coin(50, COMMON), coin(100, "ETH")))
+        .build();
+
+    suite
+        .create_market_quick("gov", "osmo", "OSMO", None, None, None)
+        .unwrap();
+    suite
+        .create_market_quick("gov", "eth", "ETH", None, None, None)
+        .unwrap();
+
+    assert_eq!(suite.query_portfolio(actor).unwrap().positions, []);
+
+    suite
+        .deposit_tokens_on_market(actor, coin(1000, "OSMO"))
+        .unwrap();
+    suite
+        .deposit_tokens_on_market(lender, coin(500, "ETH"))
+        .unwrap();
+    suite
+        .borrow_tokens_from_market(actor, coin(200, "ETH"))
+        .unwrap();
+
+    let portfolio = suite.query_portfolio(actor).unwrap();
+    assert_eq!(
+        portfolio.total,
+        CreditLineValues {
+            collateral: Uint128::new(2000),
+            credit_line: Uint128::new(1000),
+            debt: Uint128::new(100),
+        }
+        .make_response(suite.common_token().clone())
+    );
+    assert_eq!(portfolio.health, Some(Decimal::percent(1000)));
+
+    let osmo = portfolio
+        .positions
+        .iter()
+        .find(|p| p.market_token == Token::Native("OSMO".to_owned()))
+        .unwrap();
+    assert_eq!(osmo.market, suite.query_market("OSMO").unwrap().market);
+    assert_eq!(osmo.ltokens, Uint128::new(1000));
+    assert_eq!(osmo.btokens, Uint128::zero());
+    assert_eq!(osmo.collateral_ratio, Decimal::percent(50));
+    assert_eq!(osmo.apy.lender, Decimal::zero());
+
+    let eth = portfolio
+        .positions
+        .iter()
+        .find(|p| p.market_token == Token::Native("ETH".to_owned()))
+        .unwrap();
+    assert_eq!(eth.ltokens, Uint128::zero());
+    assert_eq!(eth.btokens, Uint128::new(200));
+    assert_eq!(eth.credit_line.debt.amount, Uint128::new(100));
+    assert!(eth.apy.borrower > Decimal::zero());
+}
+
+#[test]
+fn portfolio_includes_fixed_rate_debt() {
+    let actor = "actor";
+    let lender = "lender";
+    let mut suite = SuiteBuilder::new()
+        .with_gov("gov")
+        .with_funds(actor, &[coin(1000, "OSMO")])
+        .with_funds(lender, &[coin(500, "ETH")])
+        // 1 OSMO is worth 2 common tokens
+        .with_pool(1, (coin(200, COMMON), coin(100, "OSMO")))
+        // 1 ETH is worth 0.5 common tokens
+        .with_pool(2, (coin(50, COMMON), coin(100, "ETH")))
+        .build();
+
+    suite
+        .create_market_quick("gov", "osmo", "OSMO", None, None, None)
+        .unwrap();
+    suite
+        .create_market_quick("gov", "eth", "ETH", None, None, None)
+        .unwrap();
+    suite
+        .sudo_adjust_market_stable_borrowing(
+            "ETH",
+            Some(StableBorrowing {
+                premium: Decimal::percent(2),
+                rebalance_up_utilisation: Decimal::percent(90),
+                rebalance_down_delta: Decimal::percent(5),
+            }),
+        )
+        .unwrap();
+    suite
+        .sudo_adjust_market_term_lending(
+            "ETH",
+            Some(TermLending {
+                premium: Decimal::percent(2),
+                penalty_rate: Decimal::percent(10),
+                max_duration: 1000,
+                early_repayment_fee: Decimal::zero(),
+            }),
+        )
+        .unwrap();
+
+    suite
+        .deposit_tokens_on_market(actor, coin(1000, "OSMO"))
+        .unwrap();
+    suite
+        .deposit_tokens_on_market(lender, coin(500, "ETH"))
+        .unwrap();
+    suite
+        .borrow_tokens_from_market(actor, coin(100, "ETH"))
+        .unwrap();
+    suite
+        .borrow_stable_from_market(actor, coin(60, "ETH"))
+        .unwrap();
+    suite
+        .borrow_term_from_market(actor, coin(40, "ETH"), 1000)
+        .unwrap();
+
+    let portfolio = suite.query_portfolio(actor).unwrap();
+    // (100 + 60 + 40) ETH * 0.5
+    assert_eq!(
+        portfolio.total,
+        CreditLineValues {
+            collateral: Uint128::new(2000),
+            credit_line: Uint128::new(1000),
+            debt: Uint128::new(100),
+        }
+        .make_response(suite.common_token().clone())
+    );
+    assert_eq!(portfolio.health, Some(Decimal::percent(1000)));
+
+    let eth = portfolio
+        .positions
+        .iter()
+        .find(|p| p.market_token == Token::Native("ETH".to_owned()))
+        .unwrap();
+    assert_eq!(eth.btokens, Uint128::new(100));
+    assert_eq!(eth.stable_debt, Uint128::new(60));
+    assert_eq!(eth.term_debt, Uint128::new(40));
+    assert_eq!(eth.credit_line.debt.amount, Uint128::new(100));
+
+    let osmo = portfolio
+        .positions
+        .iter()
+        .find(|p| p.market_token == Token::Native("OSMO".to_owned()))
+        .unwrap();
+    assert_eq!(osmo.stable_debt, Uint128::zero());
+    assert_eq!(osmo.term_debt, Uint128::zero());
+}
+
+#[test]
+fn protocol_stats() {
+    let actor = "actor";
+    let lender = "lender";
+    let mut suite = SuiteBuilder::new()
+        .with_gov("gov")
+        .with_funds(actor, &[coin(1000, "OSMO")])
+        .with_funds(lender, &[coin(500, "ETH")])
+        // 1 OSMO is worth 2 common tokens
+        .with_pool(1, (coin(200, COMMON), coin(100, "OSMO")))
+        // 1 ETH is worth 0.5 common tokens
+        .with_pool(2, (coin(50, COMMON), coin(100, "ETH")))
+        .build();
+
+    suite
+        .create_market_quick("gov", "osmo", "OSMO", None, None, None)
+        .unwrap();
+    suite
+        .create_market_quick("gov", "eth", "ETH", None, None, None)
+        .unwrap();
+    suite
+        .deposit_tokens_on_market(actor, coin(1000, "OSMO"))
+        .unwrap();
+    suite
+        .deposit_tokens_on_market(lender, coin(500, "ETH"))
+        .unwrap();
+    suite
+        .borrow_tokens_from_market(actor, coin(150, "ETH"))
+        .unwrap();
+    suite
+        .sudo_adjust_market_term_lending(
+            "ETH",
+            Some(TermLending {
+                premium: Decimal::percent(2),
+                penalty_rate: Decimal::percent(10),
+                max_duration: 1000,
+                early_repayment_fee: Decimal::zero(),
+            }),
+        )
+        .unwrap();
+    suite
+        .borrow_term_from_market(actor, coin(50, "ETH"), 1000)
+        .unwrap();
+
+    let common = suite.common_token().clone();
+    let stats = suite.query_protocol_stats(None, 1).unwrap();
+    // 1000 OSMO * 2.0 + 500 ETH * 0.5
+    assert_eq!(
+        stats.tvl,
+        Some(utils::coin::Coin::new(2250, common.clone()))
+    );
+    // (150 + 50 fixed-rate) ETH * 0.5
+    assert_eq!(stats.total_debt, Some(utils::coin::Coin::new(100, common)));
+
+    assert_eq!(stats.markets.len(), 1);
+    let eth = &stats.markets[0];
+    assert_eq!(eth.market_token, Token::Native("ETH".to_owned()));
+    assert_eq!(eth.market, suite.query_market("ETH").unwrap().market);
+    assert_eq!(eth.total_supplied, Uint128::new(500));
+    assert_eq!(eth.total_borrowed, Uint128::new(200));
+    assert_eq!(eth.utilisation, Decimal::percent(40));
+    assert_eq!(eth.price, Decimal::percent(50));
+    assert!(eth.apy.borrower > Decimal::zero());
+
+    let next = suite
+        .query_protocol_stats(eth.market_token.clone(), None)
+        .unwrap();
+    assert_eq!(next.tvl, None);
+    assert_eq!(next.total_debt, None);
+    assert_eq!(next.markets.len(), 1);
+    let osmo = &next.markets[0];
+    assert_eq!(osmo.market_token, Token::Native("OSMO".to_owned()));
+    assert_eq!(osmo.total_supplied, Uint128::new(1000));
+    assert_eq!(osmo.total_borrowed, Uint128::zero());
+    assert_eq!(osmo.utilisation, Decimal::zero());
+    assert_eq!(osmo.price, Decimal::percent(200));
+}
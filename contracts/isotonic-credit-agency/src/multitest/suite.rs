@@ -5,6 +5,7 @@ use cosmwasm_std::{Addr, Coin, ContractInfoResponse, Decimal, QueryRequest, Uint
 use cw_multi_test::{AppResponse, Contract, ContractWrapper, Executor};
 use isotonic_market::msg::{
     ExecuteMsg as MarketExecuteMsg, MigrateMsg as MarketMigrateMsg, QueryMsg as MarketQueryMsg,
+    StableBorrowing, SudoMsg as MarketSudoMsg, TermLending, TermLoansResponse,
 };
 use isotonic_market::state::SECONDS_IN_YEAR;
 use isotonic_osmosis_oracle::msg::{
@@ -12,11 +13,12 @@ use isotonic_osmosis_oracle::msg::{
 };
 use osmo_bindings::{OsmosisMsg, OsmosisQuery, Step, Swap, SwapAmount, SwapResponse};
 use osmo_bindings_test::{OsmosisApp, Pool};
-use utils::{credit_line::CreditLineResponse, interest::Interest, token::Token};
+use utils::{credit_line::CreditLineResponse, interest::Interest, time::Expiration, token::Token};
 
 use crate::msg::{
-    ExecuteMsg, InstantiateMsg, IsOnMarketResponse, ListEnteredMarketsResponse,
-    ListMarketsResponse, MarketConfig, MarketResponse, QueryMsg, SudoMsg,
+    BatchCallback, ExecuteMsg, InstantiateMsg, IsOnMarketResponse, ListEnteredMarketsResponse,
+    ListMarketParticipantsResponse, ListMarketsResponse, MarketConfig, MarketResponse, Operation,
+    PortfolioResponse, ProtocolStatsResponse, QueryMsg, SudoMsg,
 };
 use crate::state::Config;
 
@@ -39,7 +41,8 @@ fn contract_credit_agency() -> Box<dyn Contract<OsmosisMsg, OsmosisQuery>> {
         crate::contract::query,
     )
     .with_sudo(crate::contract::sudo)
-    .with_reply(crate::contract::reply);
+    .with_reply(crate::contract::reply)
+    .with_migrate(crate::contract::migrate);
 
     Box::new(contract)
 }
@@ -51,7 +54,8 @@ pub fn contract_market() -> Box<dyn Contract<OsmosisMsg, OsmosisQuery>> {
         isotonic_market::contract::query,
     )
     .with_reply(isotonic_market::contract::reply)
-    .with_migrate(isotonic_market::contract::migrate);
+    .with_migrate(isotonic_market::contract::migrate)
+    .with_sudo(isotonic_market::contract::sudo);
 
     Box::new(contract)
 }
@@ -306,7 +310,7 @@ impl Suite {
         self.app.execute_contract(
             Addr::unchecked(caller),
             self.contract.clone(),
-            &ExecuteMsg::CreateMarket(cfg),
+            &ExecuteMsg::CreateMarket(Box::new(cfg)),
             &[],
         )
     }
@@ -341,6 +345,9 @@ impl Suite {
                     .unwrap_or_else(|| Decimal::percent(50)),
                 price_oracle: self.oracle_contract.to_string(),
                 reserve_factor: reserve_factor.into().unwrap_or_else(|| Decimal::percent(0)),
+                origination_fee: Decimal::zero(),
+                borrowing_enabled: true,
+                collateral_enabled: true,
             },
         )
     }
@@ -493,6 +500,62 @@ impl Suite {
         )
     }
 
+    pub fn borrow_stable_from_market(
+        &mut self,
+        account: &str,
+        tokens: Coin,
+    ) -> AnyResult<AppResponse> {
+        let market = self.query_market(tokens.denom.as_str())?;
+
+        self.app.execute_contract(
+            Addr::unchecked(account),
+            market.market,
+            &MarketExecuteMsg::BorrowStable {
+                amount: tokens.amount,
+                max_rate: None,
+            },
+            &[],
+        )
+    }
+
+    pub fn borrow_term_from_market(
+        &mut self,
+        account: &str,
+        tokens: Coin,
+        duration: u64,
+    ) -> AnyResult<AppResponse> {
+        let market = self.query_market(tokens.denom.as_str())?;
+
+        self.app.execute_contract(
+            Addr::unchecked(account),
+            market.market,
+            &MarketExecuteMsg::BorrowTerm {
+                amount: tokens.amount,
+                duration,
+                max_rate: None,
+            },
+            &[],
+        )
+    }
+
+    /// Withdraw tokens from market selected by denom and amount of Coin
+    pub fn withdraw_tokens_from_market(
+        &mut self,
+        account: &str,
+        tokens: Coin,
+    ) -> AnyResult<AppResponse> {
+        let market = self.query_market(tokens.denom.as_str())?;
+
+        self.app.execute_contract(
+            Addr::unchecked(account),
+            market.market,
+            &MarketExecuteMsg::Withdraw {
+                amount: tokens.amount,
+            },
+            &[],
+        )
+    }
+
     pub fn liquidate(
         &mut self,
         sender: &str,
@@ -547,6 +610,55 @@ impl Suite {
         )
     }
 
+    pub fn batch(
+        &mut self,
+        sender: &str,
+        ops: Vec<Operation>,
+        funds: &[Coin],
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            self.contract.clone(),
+            &ExecuteMsg::Batch { ops },
+            funds,
+        )
+    }
+
+    pub fn batch_callback(
+        &mut self,
+        sender: &str,
+        account: &str,
+        callback: BatchCallback,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            self.contract.clone(),
+            &ExecuteMsg::BatchCallback {
+                account: account.to_owned(),
+                callback,
+            },
+            &[],
+        )
+    }
+
+    pub fn accrue_all(
+        &mut self,
+        sender: &str,
+        start_after: impl Into<Option<Token>>,
+        limit: impl Into<Option<u32>>,
+    ) -> AnyResult<AppResponse> {
+        let ca = self.contract.clone();
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            ca,
+            &ExecuteMsg::AccrueAll {
+                start_after: start_after.into(),
+                limit: limit.into(),
+            },
+            &[],
+        )
+    }
+
     pub fn list_entered_markets(
         &self,
         account: &str,
@@ -566,7 +678,58 @@ impl Suite {
     }
 
     pub fn list_all_entered_markets(&self, account: &str) -> AnyResult<Vec<Addr>> {
-        self.list_entered_markets(account, None, None)
+        let mut markets: Vec<Addr> = vec![];
+        loop {
+            let start_after = markets.last().map(Addr::to_string);
+            let page = self.list_entered_markets(account, start_after, None)?;
+            if page.is_empty() {
+                return Ok(markets);
+            }
+            markets.extend(page);
+        }
+    }
+
+    pub fn list_market_participants(
+        &self,
+        market: &str,
+        start_after: impl Into<Option<String>>,
+        limit: impl Into<Option<u32>>,
+    ) -> AnyResult<Vec<Addr>> {
+        let resp: ListMarketParticipantsResponse = self.app.wrap().query_wasm_smart(
+            Addr::unchecked(&self.contract),
+            &QueryMsg::ListMarketParticipants {
+                market: market.to_owned(),
+                start_after: start_after.into(),
+                limit: limit.into(),
+            },
+        )?;
+
+        Ok(resp.accounts)
+    }
+
+    pub fn query_portfolio(&self, account: &str) -> AnyResult<PortfolioResponse> {
+        let resp: PortfolioResponse = self.app.wrap().query_wasm_smart(
+            Addr::unchecked(&self.contract),
+            &QueryMsg::Portfolio {
+                account: account.to_owned(),
+            },
+        )?;
+        Ok(resp)
+    }
+
+    pub fn query_protocol_stats(
+        &self,
+        start_after: impl Into<Option<Token>>,
+        limit: impl Into<Option<u32>>,
+    ) -> AnyResult<ProtocolStatsResponse> {
+        let resp: ProtocolStatsResponse = self.app.wrap().query_wasm_smart(
+            Addr::unchecked(&self.contract),
+            &QueryMsg::ProtocolStats {
+                start_after: start_after.into(),
+                limit: limit.into(),
+            },
+        )?;
+        Ok(resp)
     }
 
     pub fn is_on_market(&self, account: &str, market: &str) -> AnyResult<bool> {
@@ -640,4 +803,82 @@ impl Suite {
             },
         )
     }
+
+    pub fn sudo_deprecate_market(
+        &mut self,
+        denom: &str,
+        collateral_ratio_ramp_end: impl Into<Option<Expiration>>,
+    ) -> AnyResult<AppResponse> {
+        let contract = self.contract.clone();
+        self.app.wasm_sudo(
+            contract,
+            &SudoMsg::DeprecateMarket {
+                market_token: Token::Native(denom.to_owned()),
+                collateral_ratio_ramp_end: collateral_ratio_ramp_end.into(),
+                interest_rates: None,
+            },
+        )
+    }
+
+    pub fn sudo_remove_market(&mut self, denom: &str) -> AnyResult<AppResponse> {
+        let contract = self.contract.clone();
+        self.app.wasm_sudo(
+            contract,
+            &SudoMsg::RemoveMarket {
+                market_token: Token::Native(denom.to_owned()),
+            },
+        )
+    }
+
+    /// Changes the accrual reward of the market for `denom`. Pass new reward as percentage.
+    pub fn sudo_adjust_market_accrual_reward(
+        &mut self,
+        denom: &str,
+        new_reward: u64,
+    ) -> AnyResult<AppResponse> {
+        let market = self.query_market(denom)?.market;
+        self.app.wasm_sudo(
+            market,
+            &MarketSudoMsg::AdjustAccrualReward {
+                new_reward: Decimal::percent(new_reward),
+            },
+        )
+    }
+
+    pub fn sudo_adjust_market_stable_borrowing(
+        &mut self,
+        denom: &str,
+        new_config: Option<StableBorrowing>,
+    ) -> AnyResult<AppResponse> {
+        let market = self.query_market(denom)?.market;
+        self.app
+            .wasm_sudo(market, &MarketSudoMsg::AdjustStableBorrowing { new_config })
+    }
+
+    pub fn sudo_adjust_market_term_lending(
+        &mut self,
+        denom: &str,
+        new_config: Option<TermLending>,
+    ) -> AnyResult<AppResponse> {
+        let market = self.query_market(denom)?.market;
+        self.app
+            .wasm_sudo(market, &MarketSudoMsg::AdjustTermLending { new_config })
+    }
+
+    pub fn query_market_term_loans(
+        &self,
+        denom: &str,
+        account: &str,
+    ) -> AnyResult<TermLoansResponse> {
+        let market = self.query_market(denom)?.market;
+        let response: TermLoansResponse = self.app.wrap().query_wasm_smart(
+            market,
+            &MarketQueryMsg::TermLoans {
+                account: account.to_owned(),
+                start_after: None,
+                limit: None,
+            },
+        )?;
+        Ok(response)
+    }
 }
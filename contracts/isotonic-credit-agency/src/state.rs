@@ -3,9 +3,11 @@ use std::collections::HashSet;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Addr, Decimal};
+use cosmwasm_std::{Addr, Decimal, Order, StdResult, Storage};
 use cw_storage_plus::{Item, Map};
 
+use crate::msg::MarketStatus;
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct Config {
     /// The address that controls the credit agency and can set up markets
@@ -29,13 +31,22 @@ pub struct Config {
 pub enum MarketState {
     Instantiating,
     Ready(Addr),
+    /// Market being wound down, which takes no new deposits or borrows
+    Deprecated(Addr),
 }
 
 impl MarketState {
     pub fn to_addr(self) -> Option<Addr> {
         match self {
             MarketState::Instantiating => None,
-            MarketState::Ready(addr) => Some(addr),
+            MarketState::Ready(addr) | MarketState::Deprecated(addr) => Some(addr),
+        }
+    }
+
+    pub fn status(&self) -> MarketStatus {
+        match self {
+            MarketState::Deprecated(_) => MarketStatus::Deprecated,
+            _ => MarketStatus::Active,
         }
     }
 }
@@ -48,6 +59,90 @@ pub const REPLY_IDS: Map<u64, String> = Map::new("reply_ids");
 pub const NEXT_REPLY_ID: Item<u64> = Item::new("next_reply_id");
 /// A map of base asset -> market contract address
 pub const MARKETS: Map<&str, MarketState> = Map::new("market");
-/// A set of "entered markets" for each account, as in markets in which the account is
-/// actively participating.
-pub const ENTERED_MARKETS: Map<&Addr, HashSet<Addr>> = Map::new("entered_martkets");
+/// The "entered markets", as in markets in which an account is actively participating,
+/// keyed by (account, market)
+pub const ENTERED_MARKETS: Map<(&Addr, &Addr), ()> = Map::new("entered_markets");
+/// Reverse index of `ENTERED_MARKETS`, keyed by (market, account)
+pub const MARKET_PARTICIPANTS: Map<(&Addr, &Addr), ()> = Map::new("market_participants");
+/// Former layout of `ENTERED_MARKETS`, with the whole set of markets stored per account.
+/// Only read when migrating.
+const LEGACY_ENTERED_MARKETS: Map<&Addr, HashSet<Addr>> = Map::new("entered_martkets");
+
+pub fn enter_market(storage: &mut dyn Storage, account: &Addr, market: &Addr) -> StdResult<()> {
+    ENTERED_MARKETS.save(storage, (account, market), &())?;
+    MARKET_PARTICIPANTS.save(storage, (market, account), &())
+}
+
+pub fn exit_market(storage: &mut dyn Storage, account: &Addr, market: &Addr) {
+    ENTERED_MARKETS.remove(storage, (account, market));
+    MARKET_PARTICIPANTS.remove(storage, (market, account));
+}
+
+pub fn is_on_market(storage: &dyn Storage, account: &Addr, market: &Addr) -> bool {
+    ENTERED_MARKETS.has(storage, (account, market))
+}
+
+/// All markets entered by `account`, ordered by address
+pub fn entered_markets(storage: &dyn Storage, account: &Addr) -> StdResult<Vec<Addr>> {
+    ENTERED_MARKETS
+        .prefix(account)
+        .keys(storage, None, None, Order::Ascending)
+        .collect()
+}
+
+/// Moves entered markets from the former per-account set into `ENTERED_MARKETS` and
+/// `MARKET_PARTICIPANTS`
+pub fn migrate_entered_markets(storage: &mut dyn Storage) -> StdResult<()> {
+    let legacy = LEGACY_ENTERED_MARKETS
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (account, markets) in legacy {
+        for market in markets {
+            enter_market(storage, &account, &market)?;
+        }
+        LEGACY_ENTERED_MARKETS.remove(storage, &account);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use cosmwasm_std::testing::MockStorage;
+
+    #[test]
+    fn migrate_legacy_entered_markets() {
+        let mut storage = MockStorage::new();
+        let (alice, bob) = (Addr::unchecked("alice"), Addr::unchecked("bob"));
+        let (atom, osmo) = (Addr::unchecked("atom"), Addr::unchecked("osmo"));
+
+        let markets: HashSet<_> = [osmo.clone(), atom.clone()].into();
+        LEGACY_ENTERED_MARKETS
+            .save(&mut storage, &alice, &markets)
+            .unwrap();
+        LEGACY_ENTERED_MARKETS
+            .save(&mut storage, &bob, &[osmo.clone()].into())
+            .unwrap();
+
+        migrate_entered_markets(&mut storage).unwrap();
+
+        assert_eq!(
+            entered_markets(&storage, &alice).unwrap(),
+            vec![atom.clone(), osmo.clone()]
+        );
+        assert_eq!(entered_markets(&storage, &bob).unwrap(), vec![osmo.clone()]);
+        let participants: Vec<_> = MARKET_PARTICIPANTS
+            .prefix(&osmo)
+            .keys(&storage, None, None, Order::Ascending)
+            .collect::<StdResult<_>>()
+            .unwrap();
+        assert_eq!(participants, vec![alice.clone(), bob]);
+        assert!(LEGACY_ENTERED_MARKETS.is_empty(&storage));
+
+        // Running it again is a no-op
+        migrate_entered_markets(&mut storage).unwrap();
+        assert_eq!(entered_markets(&storage, &alice).unwrap(), vec![atom, osmo]);
+    }
+}
@@ -13,8 +13,11 @@ use crate::msg::{
     ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, QueryTotalCreditLine, SudoMsg,
     TransferableAmountResponse,
 };
-use crate::state::{Config, CONFIG, RESERVE};
+use crate::state::{
+    Config, BORROW_INDEX, CONFIG, RATE_AT_TARGET, RESERVE, STABLE_TOTAL, SUPPLY_INDEX,
+};
 
+use utils::events::{ParameterChange, TypedEvent};
 use utils::token::Token;
 
 pub type Response = cosmwasm_std::Response<OsmosisMsg>;
@@ -67,6 +70,10 @@ pub fn instantiate(
         label: format!("btoken_contract_{}", env.contract.address),
     };
 
+    if msg.origination_fee >= Decimal::one() {
+        return Err(ContractError::InvalidOriginationFee {});
+    }
+
     let cfg = Config {
         // those will be overwritten in a response
         ltoken_contract: Addr::unchecked(""),
@@ -82,8 +89,7 @@ pub fn instantiate(
         market_cap: msg.market_cap,
         rates: msg.interest_rate.validate()?,
         interest_charge_period: msg.interest_charge_period,
-        last_charged: env.block.time.seconds()
-            - env.block.time.seconds() % msg.interest_charge_period,
+        last_charged: env.block.time.seconds(),
         common_token: msg
             .common_token
             .native()
@@ -92,10 +98,22 @@ pub fn instantiate(
         price_oracle: msg.price_oracle,
         credit_agency: info.sender.clone(),
         reserve_factor: msg.reserve_factor,
+        origination_fee: msg.origination_fee,
+        liquidity_haircut: None,
+        accrual_reward: Decimal::zero(),
+        distribution_reserve_share: Decimal::zero(),
+        stable_borrowing: None,
+        term_lending: None,
+        reserve_split: vec![],
+        deprecated: false,
+        borrowing_enabled: msg.borrowing_enabled,
+        collateral_enabled: msg.collateral_enabled,
     };
     CONFIG.save(deps.storage, &cfg)?;
 
     RESERVE.save(deps.storage, &Uint128::zero())?;
+    BORROW_INDEX.save(deps.storage, &Decimal::one())?;
+    SUPPLY_INDEX.save(deps.storage, &Decimal::one())?;
 
     Ok(Response::new()
         .add_attribute("method", "instantiate")
@@ -180,6 +198,22 @@ pub fn execute(
             let account = deps.api.addr_validate(&account)?;
             execute::repay_to(deps, env, info, account, amount)
         }
+        DepositFor { account } => {
+            let account = deps.api.addr_validate(&account)?;
+            execute::deposit_for(deps, env, info, account)
+        }
+        WithdrawFor { account, amount } => {
+            let account = deps.api.addr_validate(&account)?;
+            execute::withdraw_for(deps, env, info, account, amount)
+        }
+        BorrowFor { account, amount } => {
+            let account = deps.api.addr_validate(&account)?;
+            execute::borrow_for(deps, env, info, account, amount)
+        }
+        RepayFor { account } => {
+            let account = deps.api.addr_validate(&account)?;
+            execute::repay_for(deps, env, info, account)
+        }
         AdjustCommonToken { new_token } => execute::adjust_common_token(
             deps,
             info.sender,
@@ -193,6 +227,35 @@ pub fn execute(
             buy,
         } => execute::swap_withdraw_from(deps, env, info.sender, account, sell_limit, buy),
         DistributeAsLTokens {} => execute::distribute_as_ltokens(deps, info),
+        AfterTransfer {
+            sender,
+            recipient,
+            amount,
+        } => {
+            let sender = deps.api.addr_validate(&sender)?;
+            let recipient = deps.api.addr_validate(&recipient)?;
+            execute::after_transfer(deps, info, sender, recipient, amount)
+        }
+        AccrueInterest {} => execute::accrue_interest(deps, env, info.sender),
+        AccrueInterestFor { recipient } => {
+            let recipient = deps.api.addr_validate(&recipient)?;
+            execute::accrue_interest_for(deps, env, info.sender, recipient)
+        }
+        BorrowStable { amount, max_rate } => {
+            execute::borrow_stable(deps, env, info, amount, max_rate)
+        }
+        RepayStable {} => execute::repay_stable(deps, env, info),
+        BorrowTerm {
+            amount,
+            duration,
+            max_rate,
+        } => execute::borrow_term(deps, env, info, amount, duration, max_rate),
+        RepayTerm { loan_id } => execute::repay_term(deps, env, info, loan_id),
+        WithdrawReserve { bucket } => execute::withdraw_reserve(deps, env, bucket),
+        Deprecate {
+            collateral_ratio_ramp_end,
+            interest_rates,
+        } => execute::deprecate(deps, env, info, collateral_ratio_ramp_end, interest_rates),
     }
 }
 
@@ -204,6 +267,8 @@ mod cr_utils {
 
     use cosmwasm_std::{DivideByZeroError, Fraction};
 
+    use crate::ramp;
+
     pub fn divide(top: Uint128, bottom: Decimal) -> Result<Uint128, DivideByZeroError> {
         (top * bottom.denominator()).checked_div(bottom.numerator())
     }
@@ -252,10 +317,16 @@ mod cr_utils {
     /// Helper returning amount of tokens available to transfer/withdraw
     pub fn transferable_amount(
         deps: Deps,
+        env: &Env,
         config: &Config,
         account: impl Into<String>,
     ) -> Result<Uint128, ContractError> {
         let account = account.into();
+        // Deposits which are not collateral don't back any debt
+        if !config.collateral_enabled {
+            return Ok(query::ltoken_balance(deps, config, &account)?.amount);
+        }
+
         let credit: CreditLineResponse = deps.querier.query_wasm_smart(
             &config.credit_agency,
             &QueryTotalCreditLine::TotalCreditLine {
@@ -265,8 +336,14 @@ mod cr_utils {
         let credit = credit.validate(&Token::Native(config.common_token.clone()))?;
 
         let available = query_available_tokens(deps, config, account.clone())?;
-        let mut can_transfer = divide(available, config.collateral_ratio)
-            .map_err(|_| ContractError::ZeroCollateralRatio {})?;
+        let collateral_ratio = ramp::collateral_ratio(deps, config, env.block.time)?;
+        // Collateral of deprecated markets can ramp to a zero ratio, it backs nothing then
+        let mut can_transfer = if collateral_ratio.is_zero() {
+            available
+        } else {
+            divide(available, collateral_ratio)
+                .map_err(|_| ContractError::ZeroCollateralRatio {})?
+        };
         if credit.debt.u128() == 0 {
             can_transfer = std::cmp::max(
                 can_transfer,
@@ -279,12 +356,20 @@ mod cr_utils {
 
 mod execute {
     use cosmwasm_std::{CosmosMsg, QueryRequest};
-    use isotonic_osmosis_oracle::msg::QueryMsg as OracleQueryMsg;
-    use osmo_bindings::{Swap, SwapAmount, SwapAmountWithLimit, SwapResponse};
+    use osmo_bindings::{SwapAmount, SwapAmountWithLimit, SwapResponse};
+    use utils::{
+        events::{Borrow, DebtKind, Deposit, Distribute, Repay, TypedEvent, Withdraw},
+        interest::Interest,
+        time::Expiration,
+    };
 
     use crate::{
-        interest::{calculate_interest, epochs_passed, InterestUpdate},
-        msg::CreditAgencyExecuteMsg,
+        cost_basis, history,
+        interest::{calculate_interest, seconds_passed, InterestUpdate},
+        msg::{CreditAgencyExecuteMsg, RampParameter},
+        oracle, ramp, reserve, stable,
+        state::RESERVE_BUCKETS,
+        term,
     };
 
     use super::*;
@@ -293,36 +378,72 @@ mod execute {
         use super::*;
 
         /// Function that is supposed to be called before every mint/burn operation.
-        /// It calculates ratio for increasing both btokens and ltokens.a
+        /// It calculates ratio for increasing both btokens and ltokens.
         /// btokens formula:
-        /// b_ratio = calculate_interest() * epochs_passed * epoch_length / 31.556.736
+        /// b_ratio = (1 + interest * charge_period / 31.536.000) ^ (seconds_passed / charge_period) - 1
         /// ltokens formula:
         /// l_ratio = b_supply() * b_ratio / l_supply()
         pub(crate) fn charge_interest(
             deps: DepsMut,
             env: Env,
         ) -> Result<Vec<SubMsg>, ContractError> {
+            Ok(charge(deps, env)?.msgs)
+        }
+
+        /// Rebase messages from `charge_interest`, with the ratios they scale the token
+        /// multipliers by
+        pub(crate) struct Charge {
+            pub msgs: Vec<SubMsg>,
+            pub ltoken_ratio: Decimal,
+            pub btoken_ratio: Decimal,
+        }
+
+        /// `charge_interest`, also returning the rebase ratios, so amounts minted or burned
+        /// after the rebases can be converted to stored amounts
+        pub(crate) fn charge(deps: DepsMut, env: Env) -> Result<Charge, ContractError> {
             use isotonic_token::msg::ExecuteMsg;
 
             let mut cfg = CONFIG.load(deps.storage)?;
-            let epochs_passed = epochs_passed(&cfg, env)?;
-            cfg.last_charged += epochs_passed * cfg.interest_charge_period;
+            let seconds_passed = seconds_passed(&cfg, &env);
+            cfg.last_charged = env.block.time.seconds();
             CONFIG.save(deps.storage, &cfg)?;
 
-            if epochs_passed == 0 {
-                return Ok(vec![]);
+            let mut charge = Charge {
+                msgs: vec![],
+                ltoken_ratio: Decimal::one(),
+                btoken_ratio: Decimal::one(),
+            };
+            if seconds_passed == 0 {
+                return Ok(charge);
             }
 
-            if let Some(InterestUpdate {
-                reserve,
-                ltoken_ratio,
-                btoken_ratio,
-            }) = calculate_interest(deps.as_ref(), epochs_passed)?
-            {
+            let update = calculate_interest(deps.as_ref(), &env, seconds_passed)?;
+            if let Some(update) = update {
+                let InterestUpdate {
+                    reserve,
+                    ltoken_ratio,
+                    btoken_ratio,
+                    borrow_index,
+                    supply_index,
+                    rate_at_target,
+                    ref stable_total,
+                    ..
+                } = update;
+
                 RESERVE.save(deps.storage, &reserve)?;
+                STABLE_TOTAL.save(deps.storage, stable_total)?;
+                BORROW_INDEX.save(deps.storage, &borrow_index)?;
+                SUPPLY_INDEX.save(deps.storage, &supply_index)?;
+                if let Some(rate_at_target) = rate_at_target {
+                    RATE_AT_TARGET.save(deps.storage, &rate_at_target)?;
+                }
+                history::record(deps.storage, env.block.time, seconds_passed, &update)?;
+
+                charge.ltoken_ratio = ltoken_ratio + Decimal::one();
+                charge.btoken_ratio = btoken_ratio + Decimal::one();
 
                 let btoken_rebase = to_binary(&ExecuteMsg::Rebase {
-                    ratio: btoken_ratio + Decimal::one(),
+                    ratio: charge.btoken_ratio,
                 })?;
                 let bwrapped = SubMsg::new(WasmMsg::Execute {
                     contract_addr: cfg.btoken_contract.to_string(),
@@ -331,7 +452,7 @@ mod execute {
                 });
 
                 let ltoken_rebase = to_binary(&ExecuteMsg::Rebase {
-                    ratio: ltoken_ratio + Decimal::one(),
+                    ratio: charge.ltoken_ratio,
                 })?;
                 let lwrapped = SubMsg::new(WasmMsg::Execute {
                     contract_addr: cfg.ltoken_contract.to_string(),
@@ -339,10 +460,27 @@ mod execute {
                     funds: vec![],
                 });
 
-                Ok(vec![bwrapped, lwrapped])
-            } else {
-                Ok(vec![])
+                charge.msgs = vec![bwrapped, lwrapped];
             }
+
+            // Interest up to now was charged with the ramps in place, they can be finished
+            ramp::finish_ramps(deps.storage, env.block.time)?;
+
+            Ok(charge)
+        }
+
+        /// Stored amount of `amount` of `token`, once its multiplier is scaled by `rebase`
+        pub(crate) fn stored_amount(
+            deps: Deps,
+            token: &Addr,
+            rebase: Decimal,
+            amount: Uint128,
+        ) -> StdResult<Uint128> {
+            let multiplier: isotonic_token::msg::MultiplierResponse = deps
+                .querier
+                .query_wasm_smart(token, &isotonic_token::msg::QueryMsg::Multiplier {})?;
+            Ok(isotonic_token::DisplayAmount::raw(amount)
+                .to_stored_amount(multiplier.multiplier * rebase))
         }
 
         /// Validates funds sent with the message, that they contain only the base asset. Returns
@@ -374,21 +512,52 @@ mod execute {
             }))
         }
 
+        /// Moves the origination fee charged on a new debt to the reserve
+        pub(crate) fn add_origination_fee(
+            storage: &mut dyn cosmwasm_std::Storage,
+            fee: Uint128,
+        ) -> StdResult<()> {
+            if !fee.is_zero() {
+                RESERVE.update(storage, |reserve| -> StdResult<_> { Ok(reserve + fee) })?;
+            }
+            Ok(())
+        }
+
+        /// Fails if the market is being wound down
+        pub fn ensure_not_deprecated(cfg: &Config) -> Result<(), ContractError> {
+            if cfg.deprecated {
+                return Err(ContractError::MarketDeprecated {});
+            }
+            Ok(())
+        }
+
+        /// Fails if the market token can't be borrowed
+        pub fn ensure_borrowing_enabled(cfg: &Config) -> Result<(), ContractError> {
+            if !cfg.borrowing_enabled {
+                return Err(ContractError::BorrowingDisabled {});
+            }
+            Ok(())
+        }
+
         pub fn deposit_to(
-            deps: DepsMut,
+            mut deps: DepsMut,
             env: Env,
             info: MessageInfo,
             cfg: Config,
             account: Addr,
             funds_sent: Uint128,
         ) -> Result<Response, ContractError> {
-            let mut response = Response::new();
-
             // Create rebase messagess for tokens based on interest and supply
-            let charge_msgs = charge_interest(deps, env)?;
-            if !charge_msgs.is_empty() {
-                response = response.add_submessages(charge_msgs);
-            }
+            let charge = charge(deps.branch(), env)?;
+            let stored_amount = stored_amount(
+                deps.as_ref(),
+                &cfg.ltoken_contract,
+                charge.ltoken_ratio,
+                funds_sent,
+            )?;
+            let response = Response::new().add_submessages(charge.msgs);
+
+            cost_basis::update(deps, &cfg, &account, |basis| basis.deposited += funds_sent)?;
 
             let mint_msg = to_binary(&isotonic_token::msg::ExecuteMsg::Mint {
                 recipient: account.to_string(),
@@ -400,10 +569,18 @@ mod execute {
                 funds: vec![],
             });
 
-            response = response
+            let event = Deposit {
+                sender: info.sender.to_string(),
+                recipient: account.to_string(),
+                amount: funds_sent,
+                stored_amount,
+            };
+
+            let response = response
                 .add_attribute("action", "deposit")
                 .add_attribute("sender", info.sender)
                 .add_attribute("destination", &account)
+                .add_event(event.to_event())
                 .add_submessage(wrapped_msg)
                 .add_submessage(enter_market(&cfg, &account)?);
             Ok(response)
@@ -412,9 +589,37 @@ mod execute {
 
     /// Handler for `ExecuteMsg::Deposit`
     pub fn deposit(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+        let account = info.sender.clone();
+        capped_deposit(deps, env, info, account)
+    }
+
+    /// Handler for `ExecuteMsg::DepositFor`
+    /// Requires sender to be a Credit Agency, otherwise fails
+    pub fn deposit_for(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        account: Addr,
+    ) -> Result<Response, ContractError> {
+        let cfg = CONFIG.load(deps.storage)?;
+        if cfg.credit_agency != info.sender {
+            return Err(ContractError::RequiresCreditAgency {});
+        }
+
+        capped_deposit(deps, env, info, account)
+    }
+
+    /// Deposits the funds sent to `account`, as long as the market is not deprecated and the
+    /// deposit fits under the market cap
+    fn capped_deposit(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        account: Addr,
+    ) -> Result<Response, ContractError> {
         let cfg = CONFIG.load(deps.storage)?;
+        helpers::ensure_not_deprecated(&cfg)?;
 
-        let sender = info.sender.clone();
         let funds_sent = helpers::validate_funds(&info.funds, &cfg.market_token)?;
 
         if let Some(cap) = cfg.market_cap {
@@ -431,7 +636,7 @@ mod execute {
             }
         }
 
-        helpers::deposit_to(deps, env, info, cfg, sender, funds_sent)
+        helpers::deposit_to(deps, env, info, cfg, account, funds_sent)
     }
 
     /// Handler for `ExecuteMsg::Withdraw`
@@ -443,24 +648,58 @@ mod execute {
     ) -> Result<Response, ContractError> {
         let cfg = CONFIG.load(deps.storage)?;
 
-        if cr_utils::transferable_amount(deps.as_ref(), &cfg, &info.sender)? < amount {
+        if cr_utils::transferable_amount(deps.as_ref(), &env, &cfg, &info.sender)? < amount {
             return Err(ContractError::CannotWithdraw {
                 account: info.sender.to_string(),
                 amount,
             });
         }
 
-        let mut response = Response::new();
+        let account = info.sender.clone();
+        withdraw_from(deps, env, cfg, info.sender, account, amount)
+    }
 
-        // Create rebase messagess for tokens based on interest and supply
-        let charge_msgs = helpers::charge_interest(deps, env)?;
-        if !charge_msgs.is_empty() {
-            response = response.add_submessages(charge_msgs);
+    /// Handler for `ExecuteMsg::WithdrawFor`
+    /// Requires sender to be a Credit Agency, otherwise fails. Skips the credit line check.
+    pub fn withdraw_for(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        account: Addr,
+        amount: Uint128,
+    ) -> Result<Response, ContractError> {
+        let cfg = CONFIG.load(deps.storage)?;
+        if cfg.credit_agency != info.sender {
+            return Err(ContractError::RequiresCreditAgency {});
         }
 
+        withdraw_from(deps, env, cfg, info.sender, account, amount)
+    }
+
+    /// Burns `amount` of L Tokens of `account` and sends it the base asset
+    fn withdraw_from(
+        mut deps: DepsMut,
+        env: Env,
+        cfg: Config,
+        sender: Addr,
+        account: Addr,
+        amount: Uint128,
+    ) -> Result<Response, ContractError> {
+        // Create rebase messagess for tokens based on interest and supply
+        let charge = helpers::charge(deps.branch(), env)?;
+        let stored_amount = helpers::stored_amount(
+            deps.as_ref(),
+            &cfg.ltoken_contract,
+            charge.ltoken_ratio,
+            amount,
+        )?;
+        let response = Response::new().add_submessages(charge.msgs);
+
+        cost_basis::update(deps, &cfg, &account, |basis| basis.withdrawn += amount)?;
+
         // Burn the L tokens
         let burn_msg = to_binary(&isotonic_token::msg::ExecuteMsg::BurnFrom {
-            owner: info.sender.to_string(),
+            owner: account.to_string(),
             amount: isotonic_token::DisplayAmount::raw(amount),
         })?;
         let wrapped_msg = SubMsg::new(WasmMsg::Execute {
@@ -471,13 +710,21 @@ mod execute {
 
         // Send the base assets from contract to lender
         let send_msg = CosmosMsg::Bank(BankMsg::Send {
-            to_address: info.sender.to_string(),
+            to_address: account.to_string(),
             amount: vec![coin(amount.u128(), cfg.market_token)],
         });
 
-        response = response
+        let event = Withdraw {
+            account: account.to_string(),
+            recipient: account.to_string(),
+            amount,
+            stored_amount,
+        };
+
+        let response = response
             .add_attribute("action", "withdraw")
-            .add_attribute("sender", info.sender)
+            .add_attribute("sender", sender)
+            .add_event(event.to_event())
             .add_submessage(wrapped_msg)
             .add_message(send_msg);
         Ok(response)
@@ -491,26 +738,74 @@ mod execute {
         amount: Uint128,
     ) -> Result<Response, ContractError> {
         let cfg = CONFIG.load(deps.storage)?;
+        helpers::ensure_not_deprecated(&cfg)?;
+        helpers::ensure_borrowing_enabled(&cfg)?;
+        // The fee is added to the debt, so it counts towards the credit line
+        let fee = amount * cfg.origination_fee;
 
-        if !cr_utils::can_borrow(deps.as_ref(), &cfg, &info.sender, amount)? {
+        if !cr_utils::can_borrow(deps.as_ref(), &cfg, &info.sender, amount + fee)? {
             return Err(ContractError::CannotBorrow {
                 amount,
                 account: info.sender.to_string(),
             });
         }
 
-        let mut response = Response::new();
+        let account = info.sender.clone();
+        borrow_to(deps, env, cfg, info.sender, account, amount)
+    }
+
+    /// Handler for `ExecuteMsg::BorrowFor`
+    /// Requires sender to be a Credit Agency, otherwise fails. Skips the credit line check.
+    pub fn borrow_for(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        account: Addr,
+        amount: Uint128,
+    ) -> Result<Response, ContractError> {
+        let cfg = CONFIG.load(deps.storage)?;
+        if cfg.credit_agency != info.sender {
+            return Err(ContractError::RequiresCreditAgency {});
+        }
+        helpers::ensure_not_deprecated(&cfg)?;
+        helpers::ensure_borrowing_enabled(&cfg)?;
+
+        borrow_to(deps, env, cfg, info.sender, account, amount)
+    }
+
+    /// Mints B Tokens for `amount` and the origination fee to `account`, and sends it `amount`
+    /// of the base asset
+    fn borrow_to(
+        mut deps: DepsMut,
+        env: Env,
+        cfg: Config,
+        sender: Addr,
+        account: Addr,
+        amount: Uint128,
+    ) -> Result<Response, ContractError> {
+        let fee = amount * cfg.origination_fee;
 
         // Create rebase messagess for tokens based on interest and supply
-        let charge_msgs = helpers::charge_interest(deps, env)?;
-        if !charge_msgs.is_empty() {
-            response = response.add_submessages(charge_msgs);
+        let charge = helpers::charge(deps.branch(), env)?;
+        let stored_amount = helpers::stored_amount(
+            deps.as_ref(),
+            &cfg.btoken_contract,
+            charge.btoken_ratio,
+            amount + fee,
+        )?;
+        let mut response = Response::new().add_submessages(charge.msgs);
+
+        if !fee.is_zero() {
+            helpers::add_origination_fee(deps.storage, fee)?;
+            response = response.add_attribute("origination_fee", fee);
         }
 
-        // Mint desired amount of btokens,
+        cost_basis::update(deps, &cfg, &account, |basis| basis.borrowed += amount)?;
+
+        // Mint desired amount of btokens, including the fee
         let msg = to_binary(&isotonic_token::msg::ExecuteMsg::Mint {
-            recipient: info.sender.to_string(),
-            amount: isotonic_token::DisplayAmount::raw(amount),
+            recipient: account.to_string(),
+            amount: isotonic_token::DisplayAmount::raw(amount + fee),
         })?;
         let mint_msg = SubMsg::new(WasmMsg::Execute {
             contract_addr: cfg.btoken_contract.to_string(),
@@ -518,45 +813,83 @@ mod execute {
             funds: vec![],
         });
 
-        // Sent tokens to sender's account
+        // Sent tokens to borrower's account
         let bank_msg = CosmosMsg::Bank(BankMsg::Send {
-            to_address: info.sender.to_string(),
+            to_address: account.to_string(),
             amount: vec![coin(amount.u128(), &cfg.market_token)],
         });
 
+        let event = Borrow {
+            account: account.to_string(),
+            kind: DebtKind::Variable,
+            amount,
+            fee,
+            stored_amount,
+        };
+
         response = response
             .add_attribute("action", "borrow")
-            .add_attribute("sender", info.sender.clone())
+            .add_attribute("sender", sender)
+            .add_event(event.to_event())
             .add_submessage(mint_msg)
-            .add_submessage(helpers::enter_market(&cfg, &info.sender)?)
+            .add_submessage(helpers::enter_market(&cfg, &account)?)
             .add_message(bank_msg);
         Ok(response)
     }
 
     /// Handler for `ExecuteMsg::Repay`
-    pub fn repay(
-        mut deps: DepsMut,
+    pub fn repay(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+        let cfg = CONFIG.load(deps.storage)?;
+        let account = info.sender.clone();
+        repay_variable(deps, env, info, cfg, account)
+    }
+
+    /// Handler for `ExecuteMsg::RepayFor`
+    /// Requires sender to be a Credit Agency, otherwise fails
+    pub fn repay_for(
+        deps: DepsMut,
         env: Env,
         info: MessageInfo,
+        account: Addr,
     ) -> Result<Response, ContractError> {
         let cfg = CONFIG.load(deps.storage)?;
+        if cfg.credit_agency != info.sender {
+            return Err(ContractError::RequiresCreditAgency {});
+        }
+
+        repay_variable(deps, env, info, cfg, account)
+    }
+
+    /// Repays variable-rate debt of `account` with the funds sent, returning the surplus to
+    /// `account`
+    fn repay_variable(
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        cfg: Config,
+        account: Addr,
+    ) -> Result<Response, ContractError> {
         let funds_sent = helpers::validate_funds(&info.funds, &cfg.market_token)?;
 
-        let debt = query::btoken_balance(deps.as_ref(), &cfg, &info.sender)?;
+        let debt = query::btoken_balance(deps.as_ref(), &cfg, &account)?;
         // If there are more tokens sent then there are to repay, burn only desired
         // amount and return the difference
         let repay_amount = std::cmp::min(funds_sent, debt.amount);
 
-        let mut response = Response::new();
-
         // Create rebase messagess for tokens based on interest and supply
-        let charge_msgs = helpers::charge_interest(deps.branch(), env)?;
-        if !charge_msgs.is_empty() {
-            response = response.add_submessages(charge_msgs);
-        }
+        let charge = helpers::charge(deps.branch(), env)?;
+        let stored_amount = helpers::stored_amount(
+            deps.as_ref(),
+            &cfg.btoken_contract,
+            charge.btoken_ratio,
+            repay_amount,
+        )?;
+        let mut response = Response::new().add_submessages(charge.msgs);
+
+        cost_basis::update(deps, &cfg, &account, |basis| basis.repaid += repay_amount)?;
 
         let msg = to_binary(&isotonic_token::msg::ExecuteMsg::BurnFrom {
-            owner: info.sender.to_string(),
+            owner: account.to_string(),
             amount: isotonic_token::DisplayAmount::raw(repay_amount),
         })?;
         let burn_msg = SubMsg::new(WasmMsg::Execute {
@@ -565,16 +898,25 @@ mod execute {
             funds: vec![],
         });
 
+        let event = Repay {
+            sender: info.sender.to_string(),
+            account: account.to_string(),
+            kind: DebtKind::Variable,
+            amount: repay_amount,
+            stored_amount,
+        };
+
         response = response
             .add_attribute("action", "repay")
-            .add_attribute("sender", info.sender.clone())
+            .add_attribute("sender", info.sender)
+            .add_event(event.to_event())
             .add_submessage(burn_msg);
 
         // Return surplus of sent tokens
         if funds_sent > repay_amount {
             let tokens_to_return = funds_sent - repay_amount;
             let bank_msg = CosmosMsg::Bank(BankMsg::Send {
-                to_address: info.sender.to_string(),
+                to_address: account.to_string(),
                 amount: vec![coin(tokens_to_return.u128(), cfg.market_token)],
             });
             response = response.add_message(bank_msg);
@@ -586,7 +928,7 @@ mod execute {
     /// Handler for `ExecuteMsg::RepayTo`
     /// Requires sender to be a Credit Agency, otherwise fails
     pub fn repay_to(
-        deps: DepsMut,
+        mut deps: DepsMut,
         env: Env,
         info: MessageInfo,
         account: Addr,
@@ -599,38 +941,110 @@ mod execute {
 
         let funds = helpers::validate_funds(&info.funds, &cfg.market_token)?;
 
+        let now = env.block.time.seconds();
         let btokens_balance = query::btoken_balance(deps.as_ref(), &cfg, &account)?;
-        // if account has less btokens then caller wants to pay off, liquidation fails
-        if funds > btokens_balance.amount {
+        let stable_debt = stable::debt(deps.storage, &account)?.amount_at(&cfg, now)?;
+        let term_debt = term::debt(deps.storage, &cfg, &account, now)?;
+        let total_debt = btokens_balance.amount + stable_debt + term_debt;
+        // if account has less debt then caller wants to pay off, liquidation fails
+        if funds > total_debt {
             return Err(ContractError::LiquidationInsufficientBTokens {
                 account: account.to_string(),
-                btokens: btokens_balance.amount,
+                btokens: total_debt,
             });
         }
 
-        let mut response = Response::new();
-
         // Create rebase messagess for tokens based on interest and supply
-        let charge_msgs = helpers::charge_interest(deps, env)?;
-        if !charge_msgs.is_empty() {
-            response = response.add_submessages(charge_msgs);
+        let charge = helpers::charge(deps.branch(), env)?;
+        let mut response = Response::new().add_submessages(charge.msgs);
+
+        // Overdue term loans are repaid first, then variable-rate debt, stable-rate debt and
+        // the remaining term loans
+        let (overdue, current): (Vec<_>, _) = term::loans(deps.storage, &account)?
+            .into_iter()
+            .partition(|loan| loan.is_overdue(now));
+        let amount = std::cmp::min(amount, funds);
+        let overdue_repayment =
+            term::repay_many(deps.storage, &cfg, now, &account, overdue, amount)?;
+        let mut left = amount - overdue_repayment.repaid;
+
+        let variable_amount = std::cmp::min(left, btokens_balance.amount);
+        left -= variable_amount;
+        let stable_amount = stable::repay(deps.storage, &cfg, now, &account, left)?;
+        left -= stable_amount;
+        let current_repayment = term::repay_many(deps.storage, &cfg, now, &account, current, left)?;
+        let surplus = funds
+            - overdue_repayment.repaid
+            - variable_amount
+            - stable_amount
+            - current_repayment.repaid;
+
+        let repaid = [
+            (DebtKind::Variable, variable_amount),
+            (DebtKind::Stable, stable_amount),
+            (
+                DebtKind::Term,
+                overdue_repayment.repaid + current_repayment.repaid,
+            ),
+        ];
+        for (kind, amount) in repaid {
+            if !amount.is_zero() {
+                let stored_amount = match kind {
+                    DebtKind::Variable => helpers::stored_amount(
+                        deps.as_ref(),
+                        &cfg.btoken_contract,
+                        charge.btoken_ratio,
+                        amount,
+                    )?,
+                    DebtKind::Stable | DebtKind::Term => Uint128::zero(),
+                };
+                let event = Repay {
+                    sender: info.sender.to_string(),
+                    account: account.to_string(),
+                    kind,
+                    amount,
+                    stored_amount,
+                };
+                response = response.add_event(event.to_event());
+            }
         }
 
-        let msg = to_binary(&isotonic_token::msg::ExecuteMsg::BurnFrom {
-            owner: account.to_string(),
-            amount: isotonic_token::DisplayAmount::raw(amount),
-        })?;
-        let burn_msg = SubMsg::new(WasmMsg::Execute {
-            contract_addr: cfg.btoken_contract.to_string(),
-            msg,
-            funds: vec![],
-        });
+        let to_reserve = overdue_repayment.to_reserve + current_repayment.to_reserve;
+        if !to_reserve.is_zero() {
+            RESERVE.update(deps.storage, |reserve| -> StdResult<_> {
+                Ok(reserve + to_reserve)
+            })?;
+        }
+
+        if !variable_amount.is_zero() {
+            cost_basis::update(deps, &cfg, &account, |basis| {
+                basis.repaid += variable_amount
+            })?;
+
+            let msg = to_binary(&isotonic_token::msg::ExecuteMsg::BurnFrom {
+                owner: account.to_string(),
+                amount: isotonic_token::DisplayAmount::raw(variable_amount),
+            })?;
+            let burn_msg = SubMsg::new(WasmMsg::Execute {
+                contract_addr: cfg.btoken_contract.to_string(),
+                msg,
+                funds: vec![],
+            });
+            response = response.add_submessage(burn_msg);
+        }
+
+        // Return what is left after all debts to the payer
+        if !surplus.is_zero() {
+            response = response.add_message(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: vec![coin(surplus.u128(), cfg.market_token)],
+            });
+        }
 
         response = response
             .add_attribute("action", "repay_to")
             .add_attribute("sender", info.sender)
-            .add_attribute("debtor", account)
-            .add_submessage(burn_msg);
+            .add_attribute("debtor", account);
         Ok(response)
     }
 
@@ -641,6 +1055,10 @@ mod execute {
         account: Addr,
     ) -> Result<Response, ContractError> {
         let cfg = CONFIG.load(deps.storage)?;
+        // Liquidations still deposit fees through the credit agency
+        if info.sender != cfg.credit_agency {
+            helpers::ensure_not_deprecated(&cfg)?;
+        }
 
         let funds_sent = helpers::validate_funds(&info.funds, &cfg.market_token)?;
 
@@ -665,24 +1083,8 @@ mod execute {
         Ok(Response::new())
     }
 
-    fn query_pool_id(
-        deps: Deps,
-        cfg: &Config,
-        denom1: impl Into<String>,
-        denom2: impl Into<String>,
-    ) -> Result<u64, ContractError> {
-        let pool_id: u64 = deps.querier.query_wasm_smart(
-            cfg.price_oracle.clone(),
-            &OracleQueryMsg::PoolId {
-                denom1: denom1.into(),
-                denom2: denom2.into(),
-            },
-        )?;
-        Ok(pool_id)
-    }
-
     pub fn swap_withdraw_from(
-        deps: DepsMut,
+        mut deps: DepsMut,
         env: Env,
         sender: Addr,
         account: String,
@@ -701,6 +1103,16 @@ mod execute {
 
         // if swap is between same denoms, don't perform a swap
         if cfg.market_token == buy.denom.to_string() {
+            let owner = deps.api.addr_validate(&account)?;
+            // Interest is not charged, the multiplier stays as it is
+            let stored_amount = helpers::stored_amount(
+                deps.as_ref(),
+                &cfg.ltoken_contract,
+                Decimal::one(),
+                buy.amount,
+            )?;
+            cost_basis::update(deps, &cfg, &owner, |basis| basis.withdrawn += buy.amount)?;
+
             // Burn the L tokens
             let burn_msg = to_binary(&isotonic_token::msg::ExecuteMsg::BurnFrom {
                 owner: account,
@@ -711,47 +1123,37 @@ mod execute {
                 msg: burn_msg,
                 funds: vec![],
             });
+            let event = Withdraw {
+                account: owner.to_string(),
+                recipient: sender.to_string(),
+                amount: buy.amount,
+                stored_amount,
+            };
             return Ok(Response::new()
+                .add_event(event.to_event())
                 .add_submessage(burn_msg)
                 .add_message(send_msg));
         }
 
-        let (swap, route) = if cfg.market_token == cfg.common_token {
-            let pool_id = query_pool_id(
-                deps.as_ref(),
-                &cfg,
-                &cfg.common_token,
-                buy.denom.to_string(),
-            )?;
-            let swap = Swap::new(pool_id, cfg.common_token.clone(), buy.denom.to_string());
-
-            // if market uses common token, there is no need for extra route
-            (swap, vec![])
-        } else if cfg.common_token == buy.denom.to_string() {
-            let pool_id = query_pool_id(
+        // Every swap goes through the common token
+        let mut route = vec![];
+        if cfg.market_token != cfg.common_token {
+            route.extend(oracle::query_route(
                 deps.as_ref(),
                 &cfg,
                 &cfg.market_token,
-                buy.denom.to_string(),
-            )?;
-            let swap = Swap::new(pool_id, cfg.market_token.clone(), buy.denom.to_string());
-
-            // if buy denom is common token, there is no need for extra route
-            (swap, vec![])
-        } else {
-            let pool_id = query_pool_id(deps.as_ref(), &cfg, &cfg.market_token, &cfg.common_token)?;
-            let swap = Swap::new(pool_id, cfg.market_token.clone(), cfg.common_token.clone());
-
-            let pool_id = query_pool_id(
+                &cfg.common_token,
+            )?);
+        }
+        if cfg.common_token != buy.denom.to_string() {
+            route.extend(oracle::query_route(
                 deps.as_ref(),
                 &cfg,
                 &cfg.common_token,
                 buy.denom.to_string(),
-            )?;
-            let route = vec![osmo_bindings::Step::new(pool_id, buy.denom.to_string())];
-
-            (swap, route)
-        };
+            )?);
+        }
+        let (swap, route) = oracle::osmosis_route(route)?;
 
         let amount = SwapAmountWithLimit::ExactOut {
             output: buy.amount,
@@ -773,13 +1175,18 @@ mod execute {
             }
         };
 
-        let mut response = Response::new();
-
         // Create rebase messagess for tokens based on interest and supply
-        let charge_msgs = helpers::charge_interest(deps, env)?;
-        if !charge_msgs.is_empty() {
-            response = response.add_submessages(charge_msgs);
-        }
+        let charge = helpers::charge(deps.branch(), env)?;
+        let stored_amount = helpers::stored_amount(
+            deps.as_ref(),
+            &cfg.ltoken_contract,
+            charge.ltoken_ratio,
+            estimate,
+        )?;
+        let response = Response::new().add_submessages(charge.msgs);
+
+        let owner = deps.api.addr_validate(&account)?;
+        cost_basis::update(deps, &cfg, &owner, |basis| basis.withdrawn += estimate)?;
 
         // Burn the L tokens
         let burn_msg = to_binary(&isotonic_token::msg::ExecuteMsg::BurnFrom {
@@ -798,13 +1205,43 @@ mod execute {
             amount,
         });
 
+        let event = Withdraw {
+            account: owner.to_string(),
+            recipient: sender.to_string(),
+            amount: estimate,
+            stored_amount,
+        };
         let response = response
+            .add_event(event.to_event())
             .add_submessage(burn_msg)
             .add_message(swap_msg)
             .add_message(send_msg);
         Ok(response)
     }
 
+    /// Handler for `ExecuteMsg::AfterTransfer`
+    pub fn after_transfer(
+        deps: DepsMut,
+        info: MessageInfo,
+        sender: Addr,
+        recipient: Addr,
+        amount: Uint128,
+    ) -> Result<Response, ContractError> {
+        let cfg = CONFIG.load(deps.storage)?;
+
+        if cfg.ltoken_contract != info.sender {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        cost_basis::transfer(deps, &cfg, &sender, &recipient, amount)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "after_transfer")
+            .add_attribute("sender", sender)
+            .add_attribute("recipient", recipient)
+            .add_attribute("amount", amount))
+    }
+
     pub fn distribute_as_ltokens(
         deps: DepsMut,
         info: MessageInfo,
@@ -817,12 +1254,24 @@ mod execute {
 
         let funds_sent = helpers::validate_funds(&info.funds, &cfg.market_token)?;
 
-        let ltoken_supply = query::token_info(deps.as_ref(), &cfg)?
-            .ltoken
+        let to_reserve = funds_sent * cfg.distribution_reserve_share;
+        if !to_reserve.is_zero() {
+            RESERVE.update(deps.storage, |reserve| -> StdResult<_> {
+                Ok(reserve + to_reserve)
+            })?;
+        }
+        let to_lenders = funds_sent - to_reserve;
+
+        let ltoken_supply = query::token_info(deps.as_ref(), &cfg)?
+            .ltoken
             .total_supply
             .display_amount();
 
-        let rebase_by = Decimal::from_ratio(ltoken_supply + funds_sent, ltoken_supply);
+        let rebase_by = Decimal::from_ratio(ltoken_supply + to_lenders, ltoken_supply);
+        let supply_index = SUPPLY_INDEX
+            .may_load(deps.storage)?
+            .unwrap_or_else(Decimal::one);
+        SUPPLY_INDEX.save(deps.storage, &supply_index.checked_mul(rebase_by)?)?;
 
         // Rebasing only the L Tokens basically means the funds get distributed to all the lenders
         // according to their share of the supply.
@@ -833,11 +1282,401 @@ mod execute {
             funds: vec![],
         });
 
+        let event = Distribute {
+            sender: info.sender.to_string(),
+            denom: cfg.market_token,
+            amount: funds_sent,
+        };
+
         Ok(Response::new()
             .add_attribute("action", "distribute_as_ltokens")
             .add_attribute("sender", info.sender)
+            .add_event(event.to_event())
             .add_submessage(rebase_msg))
     }
+
+    /// Handler for `ExecuteMsg::BorrowStable`
+    pub fn borrow_stable(
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        amount: Uint128,
+        max_rate: Option<Decimal>,
+    ) -> Result<Response, ContractError> {
+        let cfg = CONFIG.load(deps.storage)?;
+        helpers::ensure_not_deprecated(&cfg)?;
+        helpers::ensure_borrowing_enabled(&cfg)?;
+        let (_, rate) = stable::quote(deps.as_ref(), &env, &cfg, amount)?;
+        if let Some(max_rate) = max_rate {
+            if rate > max_rate {
+                return Err(ContractError::StableRateTooHigh { rate, max_rate });
+            }
+        }
+
+        // The fee is added to the debt, as for variable-rate borrowing
+        let fee = amount * cfg.origination_fee;
+        if !cr_utils::can_borrow(deps.as_ref(), &cfg, &info.sender, amount + fee)? {
+            return Err(ContractError::CannotBorrow {
+                amount,
+                account: info.sender.to_string(),
+            });
+        }
+
+        let charge_msgs = helpers::charge_interest(deps.branch(), env.clone())?;
+        stable::borrow(
+            deps.storage,
+            &cfg,
+            env.block.time.seconds(),
+            &info.sender,
+            amount + fee,
+            rate,
+        )?;
+        helpers::add_origination_fee(deps.storage, fee)?;
+
+        let bank_msg = CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![coin(amount.u128(), &cfg.market_token)],
+        });
+
+        let event = Borrow {
+            account: info.sender.to_string(),
+            kind: DebtKind::Stable,
+            amount,
+            fee,
+            stored_amount: Uint128::zero(),
+        };
+
+        Ok(Response::new()
+            .add_attribute("action", "borrow_stable")
+            .add_attribute("sender", info.sender.clone())
+            .add_attribute("rate", rate.to_string())
+            .add_attribute("origination_fee", fee)
+            .add_event(event.to_event())
+            .add_submessages(charge_msgs)
+            .add_submessage(helpers::enter_market(&cfg, &info.sender)?)
+            .add_message(bank_msg))
+    }
+
+    /// Handler for `ExecuteMsg::RepayStable`
+    pub fn repay_stable(
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        let cfg = CONFIG.load(deps.storage)?;
+        let funds_sent = helpers::validate_funds(&info.funds, &cfg.market_token)?;
+
+        let charge_msgs = helpers::charge_interest(deps.branch(), env.clone())?;
+        let repaid = stable::repay(
+            deps.storage,
+            &cfg,
+            env.block.time.seconds(),
+            &info.sender,
+            funds_sent,
+        )?;
+
+        let event = Repay {
+            sender: info.sender.to_string(),
+            account: info.sender.to_string(),
+            kind: DebtKind::Stable,
+            amount: repaid,
+            stored_amount: Uint128::zero(),
+        };
+
+        let mut response = Response::new()
+            .add_attribute("action", "repay_stable")
+            .add_attribute("sender", info.sender.clone())
+            .add_attribute("repaid", repaid)
+            .add_event(event.to_event())
+            .add_submessages(charge_msgs);
+
+        // Return surplus of sent tokens
+        if funds_sent > repaid {
+            let bank_msg = CosmosMsg::Bank(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: vec![coin((funds_sent - repaid).u128(), cfg.market_token)],
+            });
+            response = response.add_message(bank_msg);
+        }
+
+        Ok(response)
+    }
+
+    /// Handler for `ExecuteMsg::BorrowTerm`
+    pub fn borrow_term(
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        amount: Uint128,
+        duration: u64,
+        max_rate: Option<Decimal>,
+    ) -> Result<Response, ContractError> {
+        let cfg = CONFIG.load(deps.storage)?;
+        helpers::ensure_not_deprecated(&cfg)?;
+        helpers::ensure_borrowing_enabled(&cfg)?;
+        let params = term::params(&cfg)?;
+        if duration == 0 || duration > params.max_duration {
+            return Err(ContractError::InvalidTermDuration {
+                max: params.max_duration,
+            });
+        }
+
+        let rate = term::quote(deps.as_ref(), &env, &cfg, amount)?;
+        if let Some(max_rate) = max_rate {
+            if rate > max_rate {
+                return Err(ContractError::TermRateTooHigh { rate, max_rate });
+            }
+        }
+
+        // The fee is added to the loan, as for variable-rate borrowing
+        let fee = amount * cfg.origination_fee;
+        if !cr_utils::can_borrow(deps.as_ref(), &cfg, &info.sender, amount + fee)? {
+            return Err(ContractError::CannotBorrow {
+                amount,
+                account: info.sender.to_string(),
+            });
+        }
+
+        let charge_msgs = helpers::charge_interest(deps.branch(), env.clone())?;
+        let now = env.block.time.seconds();
+        let loan = term::borrow(
+            deps.storage,
+            &cfg,
+            now,
+            &info.sender,
+            amount + fee,
+            rate,
+            now + duration,
+        )?;
+        helpers::add_origination_fee(deps.storage, fee)?;
+
+        let bank_msg = CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![coin(amount.u128(), &cfg.market_token)],
+        });
+
+        let event = Borrow {
+            account: info.sender.to_string(),
+            kind: DebtKind::Term,
+            amount,
+            fee,
+            stored_amount: Uint128::zero(),
+        };
+
+        Ok(Response::new()
+            .add_attribute("action", "borrow_term")
+            .add_event(event.to_event())
+            .add_attribute("sender", info.sender.clone())
+            .add_attribute("loan_id", loan.id.to_string())
+            .add_attribute("rate", rate.to_string())
+            .add_attribute("maturity", loan.maturity.to_string())
+            .add_attribute("origination_fee", fee)
+            .add_submessages(charge_msgs)
+            .add_submessage(helpers::enter_market(&cfg, &info.sender)?)
+            .add_message(bank_msg))
+    }
+
+    /// Handler for `ExecuteMsg::RepayTerm`
+    pub fn repay_term(
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        loan_id: u64,
+    ) -> Result<Response, ContractError> {
+        let cfg = CONFIG.load(deps.storage)?;
+        let funds_sent = helpers::validate_funds(&info.funds, &cfg.market_token)?;
+        let loan = term::load(deps.storage, &info.sender, loan_id)?;
+        let early_fee = cfg
+            .term_lending
+            .as_ref()
+            .map(|params| params.early_repayment_fee)
+            .unwrap_or_default();
+
+        let charge_msgs = helpers::charge_interest(deps.branch(), env.clone())?;
+        let repayment = term::repay(
+            deps.storage,
+            &cfg,
+            env.block.time.seconds(),
+            &info.sender,
+            loan,
+            funds_sent,
+            early_fee,
+        )?;
+        RESERVE.update(deps.storage, |reserve| -> StdResult<_> {
+            Ok(reserve + repayment.to_reserve)
+        })?;
+
+        let event = Repay {
+            sender: info.sender.to_string(),
+            account: info.sender.to_string(),
+            kind: DebtKind::Term,
+            amount: repayment.repaid,
+            stored_amount: Uint128::zero(),
+        };
+
+        let mut response = Response::new()
+            .add_attribute("action", "repay_term")
+            .add_event(event.to_event())
+            .add_attribute("sender", info.sender.clone())
+            .add_attribute("loan_id", loan_id.to_string())
+            .add_attribute("repaid", repayment.repaid)
+            .add_submessages(charge_msgs);
+
+        // Return surplus of sent tokens
+        if funds_sent > repayment.repaid {
+            let bank_msg = CosmosMsg::Bank(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: vec![coin(
+                    (funds_sent - repayment.repaid).u128(),
+                    cfg.market_token,
+                )],
+            });
+            response = response.add_message(bank_msg);
+        }
+
+        Ok(response)
+    }
+
+    /// Handler for `ExecuteMsg::AccrueInterest`
+    pub fn accrue_interest(
+        deps: DepsMut,
+        env: Env,
+        sender: Addr,
+    ) -> Result<Response, ContractError> {
+        accrue_interest_to(deps, env, sender)
+    }
+
+    /// Handler for `ExecuteMsg::AccrueInterestFor`
+    /// Requires sender to be a Credit Agency, otherwise fails
+    pub fn accrue_interest_for(
+        deps: DepsMut,
+        env: Env,
+        sender: Addr,
+        recipient: Addr,
+    ) -> Result<Response, ContractError> {
+        let cfg = CONFIG.load(deps.storage)?;
+        if cfg.credit_agency != sender {
+            return Err(ContractError::RequiresCreditAgency {});
+        }
+
+        accrue_interest_to(deps, env, recipient)
+    }
+
+    /// Handler for `ExecuteMsg::Deprecate`
+    pub fn deprecate(
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        collateral_ratio_ramp_end: Option<Expiration>,
+        interest_rates: Option<Interest>,
+    ) -> Result<Response, ContractError> {
+        let cfg = CONFIG.load(deps.storage)?;
+        if cfg.credit_agency != info.sender {
+            return Err(ContractError::RequiresCreditAgency {});
+        }
+
+        let mut response = Response::new().add_attribute("action", "deprecate");
+        if let Some(new_interest_rates) = interest_rates {
+            let adjusted =
+                sudo::adjust_interest_rates(deps.branch(), env.clone(), new_interest_rates)?;
+            response = response.add_submessages(adjusted.messages);
+        }
+        if let Some(end) = collateral_ratio_ramp_end {
+            // A ramp in progress is replaced, starting from its current value
+            let cancelled =
+                sudo::cancel_ramp(deps.branch(), env.clone(), RampParameter::CollateralRatio)?;
+            let ramped = sudo::ramp_collateral_ratio(deps.branch(), env, Decimal::zero(), end)?;
+            response = response
+                .add_submessages(cancelled.messages)
+                .add_submessages(ramped.messages);
+        }
+
+        CONFIG.update(deps.storage, |mut cfg| -> StdResult<_> {
+            cfg.deprecated = true;
+            Ok(cfg)
+        })?;
+
+        Ok(response)
+    }
+
+    /// Handler for `ExecuteMsg::WithdrawReserve`
+    pub fn withdraw_reserve(
+        mut deps: DepsMut,
+        env: Env,
+        bucket: String,
+    ) -> Result<Response, ContractError> {
+        let charge_msgs = helpers::charge_interest(deps.branch(), env.clone())?;
+
+        let cfg = CONFIG.load(deps.storage)?;
+        let recipient = cfg
+            .reserve_split
+            .iter()
+            .find(|b| b.name == bucket)
+            .ok_or_else(|| ContractError::UnknownReserveBucket(bucket.clone()))?
+            .recipient
+            .clone();
+
+        let total = RESERVE.load(deps.storage)?;
+        reserve::allocate(deps.storage, &cfg, total)?;
+
+        let allocated = RESERVE_BUCKETS.load(deps.storage, &bucket)?;
+        let available = deps
+            .querier
+            .query_balance(&env.contract.address, &cfg.market_token)?
+            .amount;
+        let amount = std::cmp::min(allocated, available);
+        if amount.is_zero() {
+            return Err(ContractError::NothingToWithdraw(bucket));
+        }
+
+        RESERVE_BUCKETS.save(deps.storage, &bucket, &(allocated - amount))?;
+        RESERVE.save(deps.storage, &(total - amount))?;
+
+        Ok(Response::new()
+            .add_attribute("action", "withdraw_reserve")
+            .add_attribute("bucket", bucket)
+            .add_attribute("amount", amount)
+            .add_submessages(charge_msgs)
+            .add_message(BankMsg::Send {
+                to_address: recipient,
+                amount: vec![coin(amount.u128(), cfg.market_token)],
+            }))
+    }
+
+    /// Charges interest and pays `accrual_reward` of the newly accrued reserves to `recipient`.
+    /// The reward is limited by the liquid funds the market holds.
+    fn accrue_interest_to(
+        mut deps: DepsMut,
+        env: Env,
+        recipient: Addr,
+    ) -> Result<Response, ContractError> {
+        let reserve_before = RESERVE.load(deps.storage)?;
+        let charge_msgs = helpers::charge_interest(deps.branch(), env.clone())?;
+        let reserve = RESERVE.load(deps.storage)?;
+
+        let cfg = CONFIG.load(deps.storage)?;
+        let available = deps
+            .querier
+            .query_balance(&env.contract.address, &cfg.market_token)?
+            .amount;
+        let reward = std::cmp::min((reserve - reserve_before) * cfg.accrual_reward, available);
+
+        let mut response = Response::new()
+            .add_attribute("action", "accrue_interest")
+            .add_attribute("recipient", &recipient)
+            .add_attribute("reward", reward)
+            .add_submessages(charge_msgs);
+
+        if !reward.is_zero() {
+            RESERVE.save(deps.storage, &(reserve - reward))?;
+            response = response.add_message(BankMsg::Send {
+                to_address: recipient.to_string(),
+                amount: vec![coin(reward.u128(), cfg.market_token)],
+            });
+        }
+
+        Ok(response)
+    }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -848,13 +1687,13 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractErro
         TokensBalance { account } => to_binary(&query::tokens_balance(deps, env, account)?)?,
         TransferableAmount { token, account } => {
             let token = deps.api.addr_validate(&token)?;
-            to_binary(&query::transferable_amount(deps, token, account)?)?
+            to_binary(&query::transferable_amount(deps, env, token, account)?)?
         }
         Withdrawable { account } => to_binary(&query::withdrawable(deps, env, account)?)?,
         Borrowable { account } => to_binary(&query::borrowable(deps, env, account)?)?,
         Interest {} => {
             let cfg = CONFIG.load(deps.storage)?;
-            to_binary(&query::interest(&cfg, &token_info(deps, &cfg)?)?)?
+            to_binary(&query::interest(deps, env, &cfg, &token_info(deps, &cfg)?)?)?
         }
         PriceMarketLocalPerCommon {} => to_binary(&query::price_market_local_per_common(deps)?)?,
         CreditLine { account } => {
@@ -862,24 +1701,75 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractErro
             to_binary(&query::credit_line(deps, env, account)?)?
         }
         Reserve {} => to_binary(&query::reserve(deps, env)?)?,
-        Apy {} => to_binary(&query::apy(deps)?)?,
+        Apy {} => to_binary(&query::apy(deps, env)?)?,
+        PendingRamps {} => to_binary(&query::pending_ramps(deps, env)?)?,
+        StableDebt { account } => {
+            let account = deps.api.addr_validate(&account)?;
+            to_binary(&query::stable_debt(deps, env, account)?)?
+        }
+        StableRate {} => to_binary(&query::stable_rate(deps, env)?)?,
+        TermLoans {
+            account,
+            start_after,
+            limit,
+        } => {
+            let account = deps.api.addr_validate(&account)?;
+            to_binary(&query::term_loans(deps, env, account, start_after, limit)?)?
+        }
+        TermRate { amount } => to_binary(&query::term_rate(deps, env, amount)?)?,
+        Overdue { account } => {
+            let account = deps.api.addr_validate(&account)?;
+            to_binary(&query::overdue(deps, env, account)?)?
+        }
+        ReserveBuckets {} => to_binary(&query::reserve_buckets(deps, env)?)?,
+        ListPositions { start_after, limit } => {
+            to_binary(&query::list_positions(deps, env, start_after, limit)?)?
+        }
+        AccountInterest { account } => {
+            let account = deps.api.addr_validate(&account)?;
+            to_binary(&query::account_interest(deps, env, account)?)?
+        }
+        RateHistory { start_after, limit } => {
+            to_binary(&query::rate_history(deps, start_after, limit)?)?
+        }
+        SimulateBorrow { account, amount } => {
+            let account = deps.api.addr_validate(&account)?;
+            to_binary(&query::simulate_borrow(deps, env, account, amount)?)?
+        }
+        SimulateWithdraw { account, amount } => {
+            let account = deps.api.addr_validate(&account)?;
+            to_binary(&query::simulate_withdraw(deps, env, account, amount)?)?
+        }
+        SimulateRepay { account, amount } => {
+            let account = deps.api.addr_validate(&account)?;
+            to_binary(&query::simulate_repay(deps, env, account, amount)?)?
+        }
     };
     Ok(res)
 }
 
-mod query {
+pub(crate) mod query {
     use super::*;
 
-    use cosmwasm_std::{coin, Coin, Decimal, Uint128};
+    use cosmwasm_std::{coin, Coin, Decimal, Order, Uint128};
     use cw20::BalanceResponse;
-    use isotonic_osmosis_oracle::msg::{PriceResponse, QueryMsg as OracleQueryMsg};
-    use isotonic_token::msg::QueryMsg as TokenQueryMsg;
+    use cw_storage_plus::Bound;
+    use isotonic_token::msg::{AllAccountsResponse, QueryMsg as TokenQueryMsg};
     use utils::credit_line::{CreditLineResponse, CreditLineValues};
     use utils::price::{coin_times_price_rate, PriceRate};
 
-    use crate::interest::{calculate_interest, epochs_passed, token_supply, utilisation};
-    use crate::msg::{ApyResponse, InterestResponse, ReserveResponse, TokensBalanceResponse};
-    use crate::state::{TokensInfo, SECONDS_IN_YEAR};
+    use crate::interest::{calculate_interest, seconds_passed, token_supply, utilisation};
+    use crate::msg::{
+        AccountInterestResponse, ApyResponse, InterestResponse, ListPositionsResponse,
+        OverdueResponse, PendingRampsResponse, PositionResponse, RateHistoryResponse,
+        ReserveBucketResponse, ReserveBucketsResponse, ReserveResponse, SimulationResponse,
+        StableDebtResponse, StableRateResponse, TermLoanResponse, TermLoansResponse,
+        TermRateResponse, TokensBalanceResponse,
+    };
+    use crate::state::{TokensInfo, COLLATERAL_RATIO_RAMP, INTEREST_RATES_RAMP, SECONDS_IN_YEAR};
+    use crate::state::{STABLE_DEBTS, TERM_BORROWERS, TERM_LOANS};
+    use crate::{cost_basis, history, ramp, stable, term};
+    use crate::{liquidity, oracle};
 
     fn token_balance(
         deps: Deps,
@@ -920,8 +1810,14 @@ mod query {
     pub fn config(deps: Deps, env: Env) -> Result<Config, ContractError> {
         let mut config = CONFIG.load(deps.storage)?;
 
-        let unhandled_charge_period = epochs_passed(&config, env)?;
-        config.last_charged += unhandled_charge_period * config.interest_charge_period;
+        // Report the config as if interest was just charged
+        config.last_charged = std::cmp::max(config.last_charged, env.block.time.seconds());
+        config.collateral_ratio = ramp::collateral_ratio(deps, &config, env.block.time)?;
+        if let Some(ramp) = INTEREST_RATES_RAMP.may_load(deps.storage)? {
+            if ramp.is_finished(env.block.time) {
+                config.rates = ramp.to;
+            }
+        }
 
         Ok(config)
     }
@@ -937,7 +1833,7 @@ mod query {
         let mut ltokens = ltoken_balance(deps, &config, account.clone())?.amount;
         let mut btokens = btoken_balance(deps, &config, account)?.amount;
 
-        if let Some(update) = calculate_interest(deps, epochs_passed(&config, env)?)? {
+        if let Some(update) = calculate_interest(deps, &env, seconds_passed(&config, &env))? {
             ltokens += ltokens * update.ltoken_ratio;
             btokens += btokens * update.btoken_ratio;
         }
@@ -948,6 +1844,7 @@ mod query {
     /// Handler for `QueryMsg::TransferableAmount`
     pub fn transferable_amount(
         deps: Deps,
+        env: Env,
         token: Addr,
         account: String,
     ) -> Result<TransferableAmountResponse, ContractError> {
@@ -957,7 +1854,7 @@ mod query {
                 transferable: Uint128::zero(),
             })
         } else if token == config.ltoken_contract {
-            let transferable = cr_utils::transferable_amount(deps, &config, account)?;
+            let transferable = cr_utils::transferable_amount(deps, &env, &config, account)?;
             Ok(TransferableAmountResponse { transferable })
         } else {
             Err(ContractError::UnrecognisedToken(token.to_string()))
@@ -970,7 +1867,7 @@ mod query {
 
         let cfg = CONFIG.load(deps.storage)?;
 
-        let transferable = cr_utils::transferable_amount(deps, &cfg, &account)?;
+        let transferable = cr_utils::transferable_amount(deps, &env, &cfg, &account)?;
         let ltoken_balance = ltoken_balance(deps, &cfg, &account)?;
         let allowed_to_withdraw = min(transferable, ltoken_balance.amount);
         let withdrawable = min(
@@ -988,10 +1885,23 @@ mod query {
         use std::cmp::min;
 
         let cfg = CONFIG.load(deps.storage)?;
+        if !cfg.borrowing_enabled {
+            return Ok(coin(0, cfg.market_token));
+        }
 
+        // The origination fee is added to the debt and has to fit in the credit line as well.
+        // It is rounded down, which can leave room for one more token.
         let available = cr_utils::query_available_tokens(deps, &cfg, account)?;
+        let mut with_fee = available.multiply_ratio(
+            Decimal::one().atomics(),
+            (Decimal::one() + cfg.origination_fee).atomics(),
+        );
+        let next = with_fee + Uint128::one();
+        if next + next * cfg.origination_fee <= available {
+            with_fee = next;
+        }
         let borrowable = min(
-            available,
+            with_fee,
             deps.querier
                 .query_balance(env.contract.address, &cfg.market_token)?
                 .amount,
@@ -1006,17 +1916,38 @@ mod query {
 
     /// Handler for `QueryMsg::Interest`
     pub fn interest(
+        deps: Deps,
+        env: Env,
         config: &Config,
         tokens_info: &TokensInfo,
     ) -> Result<InterestResponse, ContractError> {
-        let utilisation = utilisation(tokens_info);
-
-        let interest = config.rates.calculate_interest_rate(utilisation);
+        let fixed_rate_debt =
+            stable::total(deps.storage)?.amount_at(config, env.block.time.seconds())?;
+        let utilisation = utilisation(tokens_info, fixed_rate_debt);
+
+        let interest = ramp::interest_rate(deps, config, utilisation, env.block.time)?;
+
+        // As if interest was just charged
+        let update = calculate_interest(deps, &env, seconds_passed(config, &env))?;
+        let (borrow_index, supply_index) = match update {
+            Some(update) => (update.borrow_index, update.supply_index),
+            None => (
+                BORROW_INDEX
+                    .may_load(deps.storage)?
+                    .unwrap_or_else(Decimal::one),
+                SUPPLY_INDEX
+                    .may_load(deps.storage)?
+                    .unwrap_or_else(Decimal::one),
+            ),
+        };
 
         Ok(InterestResponse {
             interest,
             utilisation,
             charge_period: Timestamp::from_seconds(config.interest_charge_period),
+            fixed_rate_debt,
+            borrow_index,
+            supply_index,
         })
     }
 
@@ -1032,19 +1963,77 @@ mod query {
                 rate_sell_per_buy: Decimal::one(),
             })
         } else {
-            let price_response: PriceResponse = deps.querier.query_wasm_smart(
-                config.price_oracle.clone(),
-                &OracleQueryMsg::Price {
-                    sell: config.market_token.clone(),
-                    buy: config.common_token.clone(),
-                },
-            )?;
+            let rate =
+                oracle::query_price(deps, &config, &config.market_token, &config.common_token)?;
             Ok(PriceRate {
                 sell_denom: config.market_token.clone(),
                 buy_denom: config.common_token,
-                rate_sell_per_buy: price_response.rate,
+                rate_sell_per_buy: rate,
+            })
+        }
+    }
+
+    /// Account's position in the market token, with pending interest applied
+    struct Position {
+        collateral: Coin,
+        /// Variable-rate debt, backed by B-Tokens
+        debt: Coin,
+        /// Stable-rate and term debt
+        fixed_debt: Uint128,
+    }
+
+    impl Position {
+        fn load(
+            deps: Deps,
+            env: &Env,
+            config: &Config,
+            account: &Addr,
+        ) -> Result<Self, ContractError> {
+            let mut collateral = ltoken_balance(deps, config, account)?;
+            let mut debt = btoken_balance(deps, config, account)?;
+
+            // Simulate charging interest for any periods `charge_interest` wasn't called for yet
+            if let Some(update) = calculate_interest(deps, env, seconds_passed(config, env))? {
+                collateral.amount += collateral.amount * update.ltoken_ratio;
+                debt.amount += debt.amount * update.btoken_ratio;
+            }
+            let now = env.block.time.seconds();
+            let fixed_debt = stable::debt(deps.storage, account)?.amount_at(config, now)?
+                + term::debt(deps.storage, config, account, now)?;
+
+            Ok(Self {
+                collateral,
+                debt,
+                fixed_debt,
             })
         }
+
+        /// Values the position in the common token
+        fn value(
+            &self,
+            deps: Deps,
+            env: &Env,
+            config: &Config,
+        ) -> Result<CreditLineValues, ContractError> {
+            let debt = coin(
+                (self.debt.amount + self.fixed_debt).u128(),
+                &config.market_token,
+            );
+            if self.collateral.amount.is_zero() && debt.amount.is_zero() {
+                return Ok(CreditLineValues::zero());
+            }
+
+            let price_ratio = price_market_local_per_common(deps)?;
+            let collateral =
+                liquidity::collateral_value(deps, env, config, &self.collateral, &price_ratio)?;
+            let debt = coin_times_price_rate(&debt, &price_ratio)?.amount;
+            let credit_line = if config.collateral_enabled {
+                collateral * ramp::collateral_ratio(deps, config, env.block.time)?
+            } else {
+                Uint128::zero()
+            };
+            Ok(CreditLineValues::new(collateral, credit_line, debt))
+        }
     }
 
     /// Handler for `QueryMsg::CreditLine`
@@ -1054,45 +2043,143 @@ mod query {
         account: Addr,
     ) -> Result<CreditLineResponse, ContractError> {
         let config = CONFIG.load(deps.storage)?;
-        let mut collateral = ltoken_balance(deps, &config, &account)?;
-        let mut debt = btoken_balance(deps, &config, &account)?;
+        let position = Position::load(deps, &env, &config, &account)?;
+        Ok(position
+            .value(deps, &env, &config)?
+            .make_response(Token::Native(config.common_token)))
+    }
 
-        // Simulate charging interest for any periods `charge_interest` wasn't called for yet
-        if let Some(update) = calculate_interest(deps, epochs_passed(&config, env)?)? {
-            collateral.amount += collateral.amount * update.ltoken_ratio;
-            debt.amount += debt.amount * update.btoken_ratio;
-        }
+    /// Replaces this market's part of the account's total credit line with its value after
+    /// `action` is applied to the position
+    fn simulate(
+        deps: Deps,
+        env: &Env,
+        config: &Config,
+        account: &Addr,
+        allowed: bool,
+        action: impl FnOnce(&mut Position),
+    ) -> Result<SimulationResponse, ContractError> {
+        let common_token = Token::Native(config.common_token.clone());
+        let total: CreditLineResponse = deps.querier.query_wasm_smart(
+            &config.credit_agency,
+            &QueryTotalCreditLine::TotalCreditLine {
+                account: account.to_string(),
+            },
+        )?;
+        let total = total.validate(&common_token)?;
+
+        let mut position = Position::load(deps, env, config, account)?;
+        let before = position.value(deps, env, config)?;
+        action(&mut position);
+        let after = position.value(deps, env, config)?;
+
+        let credit_line = total.saturating_sub(before) + after;
+        Ok(SimulationResponse {
+            health: credit_line.health(),
+            credit_line: credit_line.make_response(common_token),
+            allowed,
+        })
+    }
 
-        if collateral.amount.is_zero() && debt.amount.is_zero() {
-            return Ok(CreditLineValues::zero().make_response(Token::Native(config.common_token)));
-        }
+    /// Handler for `QueryMsg::SimulateBorrow`
+    pub fn simulate_borrow(
+        deps: Deps,
+        env: Env,
+        account: Addr,
+        amount: Uint128,
+    ) -> Result<SimulationResponse, ContractError> {
+        let config = CONFIG.load(deps.storage)?;
+        let fee = amount * config.origination_fee;
+        let liquidity = deps
+            .querier
+            .query_balance(&env.contract.address, &config.market_token)?
+            .amount;
+
+        let allowed = !amount.is_zero()
+            && execute::helpers::ensure_not_deprecated(&config).is_ok()
+            && execute::helpers::ensure_borrowing_enabled(&config).is_ok()
+            && amount <= liquidity
+            && cr_utils::can_borrow(deps, &config, &account, amount + fee)?;
+        simulate(deps, &env, &config, &account, allowed, |position| {
+            position.debt.amount += amount + fee
+        })
+    }
 
-        let price_ratio = price_market_local_per_common(deps)?;
-        let collateral = coin_times_price_rate(&collateral, &price_ratio)?;
-        let debt = coin_times_price_rate(&debt, &price_ratio)?.amount;
-        let credit_line = collateral.amount * config.collateral_ratio;
-        Ok(CreditLineValues::new(collateral.amount, credit_line, debt)
-            .make_response(Token::Native(config.common_token)))
+    /// Handler for `QueryMsg::SimulateWithdraw`
+    pub fn simulate_withdraw(
+        deps: Deps,
+        env: Env,
+        account: Addr,
+        amount: Uint128,
+    ) -> Result<SimulationResponse, ContractError> {
+        let config = CONFIG.load(deps.storage)?;
+        let withdrawable = withdrawable(deps, env.clone(), account.to_string())?.amount;
+
+        let allowed = !amount.is_zero() && amount <= withdrawable;
+        simulate(deps, &env, &config, &account, allowed, |position| {
+            position.collateral.amount = position.collateral.amount.saturating_sub(amount)
+        })
+    }
+
+    /// Handler for `QueryMsg::SimulateRepay`
+    pub fn simulate_repay(
+        deps: Deps,
+        env: Env,
+        account: Addr,
+        amount: Uint128,
+    ) -> Result<SimulationResponse, ContractError> {
+        let config = CONFIG.load(deps.storage)?;
+
+        // Repaying more than the debt returns the surplus
+        let allowed = !amount.is_zero();
+        simulate(deps, &env, &config, &account, allowed, |position| {
+            position.debt.amount = position.debt.amount.saturating_sub(amount)
+        })
     }
 
     /// Handler for `QueryMsg::Reserve`
     pub fn reserve(deps: Deps, env: Env) -> Result<ReserveResponse, ContractError> {
         let config = CONFIG.load(deps.storage)?;
 
-        let reserve = calculate_interest(deps, epochs_passed(&config, env)?)?
+        let reserve = calculate_interest(deps, &env, seconds_passed(&config, &env))?
             .map(|update| update.reserve)
             .unwrap_or(RESERVE.load(deps.storage)?);
 
         Ok(ReserveResponse { reserve })
     }
 
+    /// Handler for `QueryMsg::ReserveBuckets`
+    pub fn reserve_buckets(deps: Deps, env: Env) -> Result<ReserveBucketsResponse, ContractError> {
+        let config = CONFIG.load(deps.storage)?;
+        let ReserveResponse { reserve: total } = reserve(deps, env)?;
+
+        let (amounts, unallocated) = crate::reserve::split(deps.storage, &config, total)?;
+        let buckets = config
+            .reserve_split
+            .into_iter()
+            .zip(amounts)
+            .map(|(bucket, amount)| ReserveBucketResponse {
+                name: bucket.name,
+                recipient: bucket.recipient,
+                weight: bucket.weight,
+                amount,
+            })
+            .collect();
+
+        Ok(ReserveBucketsResponse {
+            buckets,
+            unallocated,
+        })
+    }
+
     /// Handler for `QueryMsg::Apy`
-    pub fn apy(deps: Deps) -> Result<ApyResponse, ContractError> {
+    pub fn apy(deps: Deps, env: Env) -> Result<ApyResponse, ContractError> {
         let cfg = CONFIG.load(deps.storage)?;
         let charge_periods = SECONDS_IN_YEAR / (cfg.interest_charge_period as u128);
         let tokens_info = token_supply(deps, &cfg)?;
-        let utilisation = utilisation(&tokens_info);
-        let rate = cfg.rates.calculate_interest_rate(utilisation);
+        let stable_debt = stable::total(deps.storage)?.amount_at(&cfg, env.block.time.seconds())?;
+        let utilisation = utilisation(&tokens_info, stable_debt);
+        let rate = ramp::interest_rate(deps, &cfg, utilisation, env.block.time)?;
 
         let borrower = (Decimal::one() + rate / Uint128::new(charge_periods))
             .checked_pow(charge_periods as u32)?
@@ -1101,12 +2188,207 @@ mod query {
 
         Ok(ApyResponse { borrower, lender })
     }
+
+    /// Handler for `QueryMsg::PendingRamps`
+    pub fn pending_ramps(deps: Deps, env: Env) -> Result<PendingRampsResponse, ContractError> {
+        let now = env.block.time;
+
+        Ok(PendingRampsResponse {
+            collateral_ratio: COLLATERAL_RATIO_RAMP
+                .may_load(deps.storage)?
+                .filter(|ramp| !ramp.is_finished(now)),
+            interest_rates: INTEREST_RATES_RAMP
+                .may_load(deps.storage)?
+                .filter(|ramp| !ramp.is_finished(now)),
+        })
+    }
+
+    /// Handler for `QueryMsg::StableDebt`
+    pub fn stable_debt(
+        deps: Deps,
+        env: Env,
+        account: Addr,
+    ) -> Result<StableDebtResponse, ContractError> {
+        let cfg = CONFIG.load(deps.storage)?;
+        let debt = stable::debt(deps.storage, &account)?;
+
+        Ok(StableDebtResponse {
+            amount: debt.amount_at(&cfg, env.block.time.seconds())?,
+            rate: debt.rate,
+        })
+    }
+
+    /// Handler for `QueryMsg::StableRate`
+    pub fn stable_rate(deps: Deps, env: Env) -> Result<StableRateResponse, ContractError> {
+        let cfg = CONFIG.load(deps.storage)?;
+        let (_, rate) = stable::quote(deps, &env, &cfg, Uint128::zero())?;
+        Ok(StableRateResponse { rate })
+    }
+
+    // settings for pagination
+    const MAX_LIMIT: u32 = 30;
+    const DEFAULT_LIMIT: u32 = 10;
+
+    /// Handler for `QueryMsg::ListPositions`
+    pub fn list_positions(
+        deps: Deps,
+        env: Env,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> Result<ListPositionsResponse, ContractError> {
+        let config = CONFIG.load(deps.storage)?;
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+
+        // All pages hold the first `limit` holders after `start_after`, so the first `limit`
+        // of their union are exactly the next `limit` positions
+        let mut accounts = vec![];
+        for token in [&config.ltoken_contract, &config.btoken_contract] {
+            let page: AllAccountsResponse = deps.querier.query_wasm_smart(
+                token,
+                &TokenQueryMsg::AllAccounts {
+                    start_after: start_after.clone(),
+                    limit: Some(limit),
+                },
+            )?;
+            accounts.extend(page.accounts);
+        }
+        let start = start_after.map(Addr::unchecked);
+        let start = start.as_ref().map(Bound::exclusive);
+        for debtors in [
+            STABLE_DEBTS.keys(deps.storage, start.clone(), None, Order::Ascending),
+            TERM_BORROWERS.keys(deps.storage, start, None, Order::Ascending),
+        ] {
+            for account in debtors.take(limit as usize) {
+                accounts.push(account?.into_string());
+            }
+        }
+        accounts.sort();
+        accounts.dedup();
+        accounts.truncate(limit as usize);
+
+        let update = calculate_interest(deps, &env, seconds_passed(&config, &env))?;
+        let now = env.block.time.seconds();
+        let positions = accounts
+            .into_iter()
+            .map(|account| {
+                let mut ltokens = ltoken_balance(deps, &config, &account)?.amount;
+                let mut btokens = btoken_balance(deps, &config, &account)?.amount;
+                if let Some(update) = &update {
+                    ltokens += ltokens * update.ltoken_ratio;
+                    btokens += btokens * update.btoken_ratio;
+                }
+                let addr = Addr::unchecked(&account);
+                let stable_debt = stable::debt(deps.storage, &addr)?.amount_at(&config, now)?;
+                let term_debt = term::debt(deps.storage, &config, &addr, now)?;
+                Ok(PositionResponse {
+                    account,
+                    ltokens,
+                    btokens,
+                    stable_debt,
+                    term_debt,
+                })
+            })
+            .collect::<Result<_, ContractError>>()?;
+
+        Ok(ListPositionsResponse { positions })
+    }
+
+    /// Handler for `QueryMsg::AccountInterest`
+    pub fn account_interest(
+        deps: Deps,
+        env: Env,
+        account: Addr,
+    ) -> Result<AccountInterestResponse, ContractError> {
+        let config = CONFIG.load(deps.storage)?;
+        let mut ltokens = ltoken_balance(deps, &config, &account)?.amount;
+        let mut btokens = btoken_balance(deps, &config, &account)?.amount;
+        if let Some(update) = calculate_interest(deps, &env, seconds_passed(&config, &env))? {
+            ltokens += ltokens * update.ltoken_ratio;
+            btokens += btokens * update.btoken_ratio;
+        }
+
+        let (earned, paid) = cost_basis::interest(deps, &account, ltokens, btokens)?;
+        Ok(AccountInterestResponse { earned, paid })
+    }
+
+    /// Handler for `QueryMsg::RateHistory`
+    pub fn rate_history(
+        deps: Deps,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> Result<RateHistoryResponse, ContractError> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let snapshots = history::snapshots(deps.storage, start_after, limit)?;
+
+        Ok(RateHistoryResponse { snapshots })
+    }
+
+    /// Handler for `QueryMsg::TermLoans`
+    pub fn term_loans(
+        deps: Deps,
+        env: Env,
+        account: Addr,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> Result<TermLoansResponse, ContractError> {
+        let cfg = CONFIG.load(deps.storage)?;
+        let now = env.block.time.seconds();
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.map(Bound::exclusive);
+
+        let loans = TERM_LOANS
+            .prefix(&account)
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|loan| {
+                let (_, loan) = loan?;
+                Ok(TermLoanResponse {
+                    id: loan.id,
+                    amount: loan.debt_at(&cfg, now)?,
+                    rate: loan.rate,
+                    maturity: Timestamp::from_seconds(loan.maturity),
+                    overdue: loan.is_overdue(now),
+                })
+            })
+            .collect::<Result<_, ContractError>>()?;
+
+        Ok(TermLoansResponse { loans })
+    }
+
+    /// Handler for `QueryMsg::TermRate`
+    pub fn term_rate(
+        deps: Deps,
+        env: Env,
+        amount: Uint128,
+    ) -> Result<TermRateResponse, ContractError> {
+        let cfg = CONFIG.load(deps.storage)?;
+        let rate = term::quote(deps, &env, &cfg, amount)?;
+        Ok(TermRateResponse { rate })
+    }
+
+    /// Handler for `QueryMsg::Overdue`
+    pub fn overdue(deps: Deps, env: Env, account: Addr) -> Result<OverdueResponse, ContractError> {
+        let cfg = CONFIG.load(deps.storage)?;
+        let now = env.block.time.seconds();
+        let mut response = OverdueResponse {
+            overdue: false,
+            amount: Uint128::zero(),
+        };
+        for loan in term::loans(deps.storage, &account)? {
+            if loan.is_overdue(now) {
+                response.overdue = true;
+                response.amount += loan.debt_at(&cfg, now)?;
+            }
+        }
+        Ok(response)
+    }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
     use SudoMsg::*;
-    match msg {
+    let change = ParameterChange::from_msg(&msg)?;
+    let response = match msg {
         AdjustCollateralRatio { new_ratio } => sudo::adjust_collateral_ratio(deps, new_ratio),
         AdjustReserveFactor { new_factor } => sudo::adjust_reserve_factor(deps, new_factor),
         AdjustPriceOracle { new_oracle } => sudo::adjust_price_oracle(deps, new_oracle),
@@ -1114,7 +2396,28 @@ pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response, ContractE
         AdjustInterestRates { new_interest_rates } => {
             sudo::adjust_interest_rates(deps, env, new_interest_rates)
         }
-    }
+        AdjustLiquidityHaircut { new_haircut } => sudo::adjust_liquidity_haircut(deps, new_haircut),
+        AdjustAccrualReward { new_reward } => sudo::adjust_accrual_reward(deps, new_reward),
+        AdjustOriginationFee { new_fee } => sudo::adjust_origination_fee(deps, new_fee),
+        AdjustDistributionReserveShare { new_share } => {
+            sudo::adjust_distribution_reserve_share(deps, new_share)
+        }
+        AdjustMarketFlags {
+            borrowing_enabled,
+            collateral_enabled,
+        } => sudo::adjust_market_flags(deps, borrowing_enabled, collateral_enabled),
+        RampCollateralRatio { target, end } => sudo::ramp_collateral_ratio(deps, env, target, end),
+        RampInterestRates { target, end } => sudo::ramp_interest_rates(deps, env, target, end),
+        CancelRamp { parameter } => sudo::cancel_ramp(deps, env, parameter),
+        AdjustStableBorrowing { new_config } => sudo::adjust_stable_borrowing(deps, new_config),
+        RebalanceStableRate { account } => {
+            let account = deps.api.addr_validate(&account)?;
+            sudo::rebalance_stable_rate(deps, env, account)
+        }
+        AdjustTermLending { new_config } => sudo::adjust_term_lending(deps, new_config),
+        AdjustReserveSplit { buckets } => sudo::adjust_reserve_split(deps, env, buckets),
+    }?;
+    Ok(response.add_event(change.to_event()))
 }
 
 mod sudo {
@@ -1122,6 +2425,16 @@ mod sudo {
 
     use utils::interest::Interest;
 
+    use utils::time::Expiration;
+
+    use crate::msg::{
+        LiquidityHaircut, RampParameter, ReserveBucket, StableBorrowing, TermLending,
+    };
+    use crate::reserve;
+    use crate::stable;
+    use crate::state::RESERVE_BUCKETS;
+    use crate::state::{Ramp, COLLATERAL_RATIO_RAMP, INTEREST_RATES_RAMP};
+
     pub fn adjust_collateral_ratio(
         deps: DepsMut,
         new_ratio: Decimal,
@@ -1129,6 +2442,7 @@ mod sudo {
         let mut cfg = CONFIG.load(deps.storage)?;
         cfg.collateral_ratio = new_ratio;
         CONFIG.save(deps.storage, &cfg)?;
+        COLLATERAL_RATIO_RAMP.remove(deps.storage);
         Ok(Response::new())
     }
 
@@ -1167,8 +2481,8 @@ mod sudo {
         env: Env,
         new_interest_rates: Interest,
     ) -> Result<Response, ContractError> {
-        let mut cfg = CONFIG.load(deps.storage)?;
         let charge_msgs = execute::helpers::charge_interest(deps.branch(), env)?;
+        let mut cfg = CONFIG.load(deps.storage)?;
         let mut response = Response::new();
         if !charge_msgs.is_empty() {
             response = response.add_submessages(charge_msgs);
@@ -1176,8 +2490,245 @@ mod sudo {
         let interest_rates = new_interest_rates.validate()?;
         cfg.rates = interest_rates;
         CONFIG.save(deps.storage, &cfg)?;
+        // A new adaptive curve starts from its own initial rate at target
+        RATE_AT_TARGET.remove(deps.storage);
+        INTEREST_RATES_RAMP.remove(deps.storage);
         Ok(response)
     }
+
+    pub fn adjust_liquidity_haircut(
+        deps: DepsMut,
+        new_haircut: Option<LiquidityHaircut>,
+    ) -> Result<Response, ContractError> {
+        if let Some(haircut) = &new_haircut {
+            haircut.validate()?;
+        }
+
+        let mut cfg = CONFIG.load(deps.storage)?;
+        cfg.liquidity_haircut = new_haircut;
+        CONFIG.save(deps.storage, &cfg)?;
+        Ok(Response::new())
+    }
+
+    pub fn adjust_accrual_reward(
+        deps: DepsMut,
+        new_reward: Decimal,
+    ) -> Result<Response, ContractError> {
+        if new_reward > Decimal::one() {
+            return Err(ContractError::InvalidAccrualReward {});
+        }
+
+        let mut cfg = CONFIG.load(deps.storage)?;
+        cfg.accrual_reward = new_reward;
+        CONFIG.save(deps.storage, &cfg)?;
+        Ok(Response::new())
+    }
+
+    pub fn adjust_distribution_reserve_share(
+        deps: DepsMut,
+        new_share: Decimal,
+    ) -> Result<Response, ContractError> {
+        if new_share > Decimal::one() {
+            return Err(ContractError::InvalidDistributionReserveShare {});
+        }
+
+        let mut cfg = CONFIG.load(deps.storage)?;
+        cfg.distribution_reserve_share = new_share;
+        CONFIG.save(deps.storage, &cfg)?;
+        Ok(Response::new())
+    }
+
+    pub fn adjust_origination_fee(
+        deps: DepsMut,
+        new_fee: Decimal,
+    ) -> Result<Response, ContractError> {
+        if new_fee >= Decimal::one() {
+            return Err(ContractError::InvalidOriginationFee {});
+        }
+
+        let mut cfg = CONFIG.load(deps.storage)?;
+        cfg.origination_fee = new_fee;
+        CONFIG.save(deps.storage, &cfg)?;
+        Ok(Response::new())
+    }
+
+    pub fn adjust_market_flags(
+        deps: DepsMut,
+        borrowing_enabled: Option<bool>,
+        collateral_enabled: Option<bool>,
+    ) -> Result<Response, ContractError> {
+        let mut cfg = CONFIG.load(deps.storage)?;
+        if let Some(borrowing_enabled) = borrowing_enabled {
+            cfg.borrowing_enabled = borrowing_enabled;
+        }
+        if let Some(collateral_enabled) = collateral_enabled {
+            cfg.collateral_enabled = collateral_enabled;
+        }
+        CONFIG.save(deps.storage, &cfg)?;
+        Ok(Response::new())
+    }
+
+    pub fn ramp_collateral_ratio(
+        mut deps: DepsMut,
+        env: Env,
+        target: Decimal,
+        end: Expiration,
+    ) -> Result<Response, ContractError> {
+        // Make sure the config is not affected by a ramp which has already finished
+        let charge_msgs = execute::helpers::charge_interest(deps.branch(), env.clone())?;
+
+        if end.is_expired(&env.block) {
+            return Err(ContractError::InvalidRampEnd {});
+        }
+        if COLLATERAL_RATIO_RAMP.may_load(deps.storage)?.is_some() {
+            return Err(ContractError::RampInProgress {});
+        }
+
+        let cfg = CONFIG.load(deps.storage)?;
+        let ramp = Ramp {
+            from: cfg.collateral_ratio,
+            to: target,
+            start: env.block.time,
+            end,
+        };
+        COLLATERAL_RATIO_RAMP.save(deps.storage, &ramp)?;
+
+        Ok(Response::new().add_submessages(charge_msgs))
+    }
+
+    pub fn ramp_interest_rates(
+        mut deps: DepsMut,
+        env: Env,
+        target: Interest,
+        end: Expiration,
+    ) -> Result<Response, ContractError> {
+        // Interest up to now is charged with the current curve
+        let charge_msgs = execute::helpers::charge_interest(deps.branch(), env.clone())?;
+
+        if end.is_expired(&env.block) {
+            return Err(ContractError::InvalidRampEnd {});
+        }
+        if INTEREST_RATES_RAMP.may_load(deps.storage)?.is_some() {
+            return Err(ContractError::RampInProgress {});
+        }
+
+        let cfg = CONFIG.load(deps.storage)?;
+        let ramp = Ramp {
+            from: cfg.rates,
+            to: target.validate()?,
+            start: env.block.time,
+            end,
+        };
+        INTEREST_RATES_RAMP.save(deps.storage, &ramp)?;
+
+        Ok(Response::new().add_submessages(charge_msgs))
+    }
+
+    pub fn cancel_ramp(
+        mut deps: DepsMut,
+        env: Env,
+        parameter: RampParameter,
+    ) -> Result<Response, ContractError> {
+        // Interest up to now is charged with the ramp in place
+        let charge_msgs = execute::helpers::charge_interest(deps.branch(), env.clone())?;
+
+        match parameter {
+            RampParameter::CollateralRatio => {
+                if let Some(ramp) = COLLATERAL_RATIO_RAMP.may_load(deps.storage)? {
+                    let mut cfg = CONFIG.load(deps.storage)?;
+                    cfg.collateral_ratio = ramp.value_at(env.block.time);
+                    CONFIG.save(deps.storage, &cfg)?;
+                    COLLATERAL_RATIO_RAMP.remove(deps.storage);
+                }
+            }
+            // `Config::rates` still holds the curve the ramp started from
+            RampParameter::InterestRates => INTEREST_RATES_RAMP.remove(deps.storage),
+        }
+
+        Ok(Response::new().add_submessages(charge_msgs))
+    }
+
+    pub fn adjust_stable_borrowing(
+        deps: DepsMut,
+        new_config: Option<StableBorrowing>,
+    ) -> Result<Response, ContractError> {
+        if let Some(stable_borrowing) = &new_config {
+            stable_borrowing.validate()?;
+        }
+
+        let mut cfg = CONFIG.load(deps.storage)?;
+        cfg.stable_borrowing = new_config;
+        CONFIG.save(deps.storage, &cfg)?;
+        Ok(Response::new())
+    }
+
+    pub fn rebalance_stable_rate(
+        mut deps: DepsMut,
+        env: Env,
+        account: Addr,
+    ) -> Result<Response, ContractError> {
+        let charge_msgs = execute::helpers::charge_interest(deps.branch(), env.clone())?;
+
+        let cfg = CONFIG.load(deps.storage)?;
+        let params = stable::params(&cfg)?;
+        let (utilisation, rate) = stable::quote(deps.as_ref(), &env, &cfg, Uint128::zero())?;
+        let debt = stable::debt(deps.storage, &account)?;
+
+        let raise = utilisation >= params.rebalance_up_utilisation && debt.rate < rate;
+        let lower = debt.rate > rate + params.rebalance_down_delta;
+        if debt.amount.is_zero() || !(raise || lower) {
+            return Err(ContractError::RebalanceConditionsNotMet(
+                account.to_string(),
+            ));
+        }
+
+        stable::change_rate(deps.storage, &cfg, env.block.time.seconds(), &account, rate)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "rebalance_stable_rate")
+            .add_attribute("account", account)
+            .add_attribute("rate", rate.to_string())
+            .add_submessages(charge_msgs))
+    }
+
+    pub fn adjust_term_lending(
+        deps: DepsMut,
+        new_config: Option<TermLending>,
+    ) -> Result<Response, ContractError> {
+        if let Some(term_lending) = &new_config {
+            term_lending.validate()?;
+        }
+
+        let mut cfg = CONFIG.load(deps.storage)?;
+        cfg.term_lending = new_config;
+        CONFIG.save(deps.storage, &cfg)?;
+        Ok(Response::new())
+    }
+
+    pub fn adjust_reserve_split(
+        mut deps: DepsMut,
+        env: Env,
+        buckets: Vec<ReserveBucket>,
+    ) -> Result<Response, ContractError> {
+        reserve::validate(deps.api, &buckets)?;
+
+        // Reserves accrued so far are allocated with the old split
+        let charge_msgs = execute::helpers::charge_interest(deps.branch(), env)?;
+        let mut cfg = CONFIG.load(deps.storage)?;
+        let total = RESERVE.load(deps.storage)?;
+        reserve::allocate(deps.storage, &cfg, total)?;
+        for removed in cfg
+            .reserve_split
+            .iter()
+            .filter(|old| !buckets.iter().any(|new| new.name == old.name))
+        {
+            RESERVE_BUCKETS.remove(deps.storage, &removed.name);
+        }
+
+        cfg.reserve_split = buckets;
+        CONFIG.save(deps.storage, &cfg)?;
+        Ok(Response::new().add_submessages(charge_msgs))
+    }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
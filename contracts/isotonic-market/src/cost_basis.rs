@@ -0,0 +1,84 @@
+use cosmwasm_std::{Addr, Uint128};
+
+use crate::{
+    contract::{query, Deps, DepsMut},
+    state::{Config, CostBasis, COST_BASES},
+    ContractError,
+};
+
+/// Applies `action` to the cost basis of `account`. An account seen for the first time starts
+/// with its current balances as the principal, so positions opened before the cost basis was
+/// tracked don't count as interest.
+pub fn update(
+    deps: DepsMut,
+    cfg: &Config,
+    account: &Addr,
+    action: impl FnOnce(&mut CostBasis),
+) -> Result<(), ContractError> {
+    let mut basis = load(deps.as_ref(), cfg, account)?;
+    action(&mut basis);
+    COST_BASES.save(deps.storage, account, &basis)?;
+    Ok(())
+}
+
+/// Stored cost basis of `account`, or its current balances if it has none yet
+fn load(deps: Deps, cfg: &Config, account: &Addr) -> Result<CostBasis, ContractError> {
+    let basis = match COST_BASES.may_load(deps.storage, account)? {
+        Some(basis) => basis,
+        None => CostBasis {
+            deposited: query::ltoken_balance(deps, cfg, account)?.amount,
+            borrowed: query::btoken_balance(deps, cfg, account)?.amount,
+            ..CostBasis::default()
+        },
+    };
+    Ok(basis)
+}
+
+/// Moves the principal of `amount` L Tokens transferred from `sender` to `recipient`. Called
+/// after the transfer, so accounts seen for the first time start from their balances from
+/// before it.
+pub fn transfer(
+    deps: DepsMut,
+    cfg: &Config,
+    sender: &Addr,
+    recipient: &Addr,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    if sender == recipient {
+        return Ok(());
+    }
+
+    let mut basis = load(deps.as_ref(), cfg, sender)?;
+    if !COST_BASES.has(deps.storage, sender) {
+        basis.deposited += amount;
+    }
+    basis.withdrawn += amount;
+    COST_BASES.save(deps.storage, sender, &basis)?;
+
+    let mut basis = load(deps.as_ref(), cfg, recipient)?;
+    if !COST_BASES.has(deps.storage, recipient) {
+        basis.deposited = basis.deposited.saturating_sub(amount);
+    }
+    basis.deposited += amount;
+    COST_BASES.save(deps.storage, recipient, &basis)?;
+    Ok(())
+}
+
+/// Interest earned on `ltokens` and paid on `btokens` held by `account`. Zero if the market
+/// has no cost basis for it.
+pub fn interest(
+    deps: Deps,
+    account: &Addr,
+    ltokens: Uint128,
+    btokens: Uint128,
+) -> Result<(Uint128, Uint128), ContractError> {
+    let basis = match COST_BASES.may_load(deps.storage, account)? {
+        Some(basis) => basis,
+        None => return Ok((Uint128::zero(), Uint128::zero())),
+    };
+
+    // Rounding of the token multipliers can leave the balances a unit below the principal
+    let earned = (ltokens + basis.withdrawn).saturating_sub(basis.deposited);
+    let paid = (btokens + basis.repaid).saturating_sub(basis.borrowed);
+    Ok((earned, paid))
+}
@@ -1,4 +1,4 @@
-use cosmwasm_std::{OverflowError, StdError, Uint128};
+use cosmwasm_std::{Decimal, OverflowError, StdError, Uint128};
 use thiserror::Error;
 use utils::interest::InterestError;
 
@@ -19,6 +19,9 @@ pub enum ContractError {
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("Cannot compound interest over {0} charge periods")]
+    TooManyChargePeriods(u64),
+
     #[error("Unrecognised reply id: {0}")]
     UnrecognisedReply(u64),
 
@@ -58,6 +61,12 @@ pub enum ContractError {
     #[error("Unauthorized - requires sender to be a Market's Credit Agency")]
     RequiresCreditAgency {},
 
+    #[error("Market is deprecated and takes no new deposits or borrows")]
+    MarketDeprecated {},
+
+    #[error("Borrowing is disabled on this market")]
+    BorrowingDisabled {},
+
     #[error("{0}")]
     InvalidCommonTokenDenom(#[from] InvalidCommonTokenDenom),
 
@@ -78,4 +87,61 @@ pub enum ContractError {
         "Osmosis returned SwapAmount::Out in response for estimate - something went wrong, abort"
     )]
     IncorrectSwapAmountResponse {},
+
+    #[error("Price oracle returned an empty swap route")]
+    EmptyRoute {},
+
+    #[error("Depth curve steps must have increasing amounts and non-decreasing discounts of at most 100%")]
+    InvalidDepthCurve {},
+
+    #[error("Accrual reward must be at most 100%")]
+    InvalidAccrualReward {},
+
+    #[error("Origination fee must be lower than 100%")]
+    InvalidOriginationFee {},
+
+    #[error("Distribution reserve share must be at most 100%")]
+    InvalidDistributionReserveShare {},
+
+    #[error("Ramp must end in the future")]
+    InvalidRampEnd {},
+
+    #[error("Another ramp of this parameter is in progress")]
+    RampInProgress {},
+
+    #[error("Stable-rate borrowing is disabled on this market")]
+    StableBorrowingDisabled {},
+
+    #[error("Stable rate {rate} exceeds the limit of {max_rate}")]
+    StableRateTooHigh { rate: Decimal, max_rate: Decimal },
+
+    #[error("Stable rate of {0} can't be rebalanced under current market conditions")]
+    RebalanceConditionsNotMet(String),
+
+    #[error("Invalid stable borrowing config, utilisation threshold must be at most 100%")]
+    InvalidStableBorrowing {},
+
+    #[error("Term lending is disabled on this market")]
+    TermLendingDisabled {},
+
+    #[error("Term loan rate {rate} exceeds the limit of {max_rate}")]
+    TermRateTooHigh { rate: Decimal, max_rate: Decimal },
+
+    #[error("Term loan duration must be between 1 and {max} seconds")]
+    InvalidTermDuration { max: u64 },
+
+    #[error("Term loan {id} of {account} not found")]
+    TermLoanNotFound { account: String, id: u64 },
+
+    #[error("Reserve split buckets must have unique, non-empty names and positive weights")]
+    InvalidReserveSplit {},
+
+    #[error("Unknown reserve bucket {0}")]
+    UnknownReserveBucket(String),
+
+    #[error("Nothing to withdraw from reserve bucket {0}")]
+    NothingToWithdraw(String),
+
+    #[error("Invalid term lending config, max duration must be positive and early repayment fee at most 100%")]
+    InvalidTermLending {},
 }
@@ -0,0 +1,70 @@
+use cosmwasm_std::{Decimal, Order, Storage, Timestamp};
+use cw_storage_plus::Bound;
+
+use crate::{
+    interest::InterestUpdate,
+    msg::RateSnapshot,
+    state::{
+        NEXT_RATE_SNAPSHOT_ID, RATE_HISTORY, RATE_HISTORY_CAPACITY, RATE_HISTORY_MIN_INTERVAL,
+        SECONDS_IN_YEAR,
+    },
+    ContractError,
+};
+
+/// Records the rates and indexes of an interest charge over the last `seconds_passed` at
+/// `now`, dropping the oldest snapshot once there are more than `RATE_HISTORY_CAPACITY` of
+/// them. Charges less than `RATE_HISTORY_MIN_INTERVAL` after the last snapshot are skipped.
+pub fn record(
+    storage: &mut dyn Storage,
+    now: Timestamp,
+    seconds_passed: u64,
+    update: &InterestUpdate,
+) -> Result<(), ContractError> {
+    let last = RATE_HISTORY
+        .range(storage, None, None, Order::Descending)
+        .next()
+        .transpose()?;
+    if let Some((_, last)) = last {
+        if now < last.timestamp.plus_seconds(RATE_HISTORY_MIN_INTERVAL) {
+            return Ok(());
+        }
+    }
+
+    let id = NEXT_RATE_SNAPSHOT_ID.may_load(storage)?.unwrap_or(1);
+    NEXT_RATE_SNAPSHOT_ID.save(storage, &(id + 1))?;
+
+    // The L-Token growth of this charge covers stable-rate and term debt and the reserve
+    // factor, so it is the rate lenders actually earned
+    let supply_rate = update.ltoken_ratio * Decimal::from_ratio(SECONDS_IN_YEAR, seconds_passed);
+    let snapshot = RateSnapshot {
+        id,
+        timestamp: now,
+        utilisation: update.utilisation,
+        borrow_rate: update.rate,
+        supply_rate,
+        borrow_index: update.borrow_index,
+        supply_index: update.supply_index,
+    };
+    RATE_HISTORY.save(storage, id, &snapshot)?;
+
+    if id > RATE_HISTORY_CAPACITY {
+        RATE_HISTORY.remove(storage, id - RATE_HISTORY_CAPACITY);
+    }
+
+    Ok(())
+}
+
+/// Up to `limit` snapshots recorded after the one with id `start_after`, oldest first
+pub fn snapshots(
+    storage: &dyn Storage,
+    start_after: Option<u64>,
+    limit: usize,
+) -> Result<Vec<RateSnapshot>, ContractError> {
+    let start = start_after.map(Bound::exclusive);
+
+    RATE_HISTORY
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|snapshot| Ok(snapshot?.1))
+        .collect()
+}
@@ -3,11 +3,15 @@ use isotonic_token::msg::TokenInfoResponse;
 
 use crate::{
     contract::Deps,
-    state::{Config, TokensInfo, CONFIG, RESERVE, SECONDS_IN_YEAR},
+    ramp, stable,
+    state::{
+        Config, StableDebt, TokensInfo, BORROW_INDEX, CONFIG, RATE_AT_TARGET, RESERVE,
+        SECONDS_IN_YEAR, SUPPLY_INDEX,
+    },
     ContractError,
 };
 
-/// Values that should be updated when interest is charged for all pending charge periods
+/// Values that should be updated when interest is charged for the time elapsed since the last charge
 pub struct InterestUpdate {
     /// The new RESERVE value
     pub reserve: Uint128,
@@ -15,35 +19,70 @@ pub struct InterestUpdate {
     pub ltoken_ratio: Decimal,
     /// The ratio to rebase BTokens by
     pub btoken_ratio: Decimal,
+    /// The new BORROW_INDEX value
+    pub borrow_index: Decimal,
+    /// The new SUPPLY_INDEX value
+    pub supply_index: Decimal,
+    /// Utilisation the interest was charged with
+    pub utilisation: Decimal,
+    /// Yearly interest rate the variable-rate debt was charged with
+    pub rate: Decimal,
+    /// The new RATE_AT_TARGET value, for adaptive interest rate curves
+    pub rate_at_target: Option<Decimal>,
+    /// The new STABLE_TOTAL value
+    pub stable_total: StableDebt,
 }
 
-pub fn epochs_passed(cfg: &Config, env: Env) -> Result<u64, ContractError> {
-    Ok((env.block.time.seconds() - cfg.last_charged) / cfg.interest_charge_period)
+/// Seconds elapsed since interest was last charged
+pub fn seconds_passed(cfg: &Config, env: &Env) -> u64 {
+    env.block.time.seconds().saturating_sub(cfg.last_charged)
 }
 
-/// Calculates new values after applying all pending interest charges
+/// Growth of debt over `seconds`, with the interest rate compounded every `charge_period`.
+/// Whole charge periods are compounded exactly, by exponentiation by squaring. The remaining
+/// fraction `f` of a period uses the binomial expansion of `(1 + i)^f` up to the cubic term,
+/// which is precise for the per-period rates used in practice.
+pub fn compounded_growth(
+    interest_rate: Decimal,
+    charge_period: u64,
+    seconds: u64,
+) -> Result<Decimal, ContractError> {
+    // The interest rate per charge period
+    let i = Decimal::from_ratio(
+        Uint128::from(charge_period) * interest_rate.numerator(),
+        Uint128::from(SECONDS_IN_YEAR) * interest_rate.denominator(),
+    );
+
+    let periods = seconds / charge_period;
+    let periods =
+        u32::try_from(periods).map_err(|_| ContractError::TooManyChargePeriods(periods))?;
+    let whole = (Decimal::one() + i).checked_pow(periods)?;
+
+    let remainder = seconds % charge_period;
+    if remainder == 0 {
+        return Ok(whole);
+    }
+
+    // (1 + i)^f ~= 1 + f*i - f(1-f)/2 * i^2 + f(1-f)(2-f)/6 * i^3
+    let f = Decimal::from_ratio(remainder, charge_period);
+    let one_minus_f = Decimal::one() - f;
+    let i2 = i.checked_mul(i)?;
+    let i3 = i2.checked_mul(i)?;
+    let first = f * i;
+    let second = f * one_minus_f * i2 / Uint128::new(2);
+    let third = f * one_minus_f * (Decimal::one() + one_minus_f) * i3 / Uint128::new(6);
+    let partial = Decimal::one() + first + third - second;
+
+    Ok(whole.checked_mul(partial)?)
+}
+
+/// Calculates new values after applying interest for all the seconds since the last charge
 pub fn calculate_interest(
     deps: Deps,
-    epochs_passed: u64,
+    env: &Env,
+    seconds_passed: u64,
 ) -> Result<Option<InterestUpdate>, ContractError> {
-    // Adapted from the compound interest formula: https://en.wikipedia.org/wiki/Compound_interest
-    fn compounded_interest_rate(
-        interest_rate: Decimal,
-        charge_period: u64,
-        epochs_passed: u64,
-    ) -> Result<Decimal, ContractError> {
-        // The interest rate per charge period
-        let scaled_interest_rate = Decimal::from_ratio(
-            Uint128::from(charge_period) * interest_rate.numerator(),
-            Uint128::from(SECONDS_IN_YEAR) * interest_rate.denominator(),
-        );
-        Ok(
-            (Decimal::one() + scaled_interest_rate).checked_pow(epochs_passed as u32)?
-                - Decimal::one(),
-        )
-    }
-
-    if epochs_passed == 0 {
+    if seconds_passed == 0 {
         return Ok(None);
     }
 
@@ -59,37 +98,66 @@ pub fn calculate_interest(
         return Ok(None);
     }
 
-    let interest = cfg.rates.calculate_interest_rate(utilisation(&tokens_info));
-    let btoken_ratio =
-        compounded_interest_rate(interest, cfg.interest_charge_period, epochs_passed)?;
+    // The rate halfway through the charged time, which is exact for linear ramps. Adaptive
+    // curves drift only after the charge.
+    let mut stable_total = stable::total(deps.storage)?;
+    let stable_borrowed = stable_total.amount;
+    let utilisation = utilisation(&tokens_info, stable_borrowed);
+    let halfway = env.block.time.minus_seconds(seconds_passed / 2);
+    let interest = ramp::interest_rate(deps, &cfg, utilisation, halfway)?;
+    let old_rate_at_target = RATE_AT_TARGET.may_load(deps.storage)?;
+    let rate_at_target =
+        cfg.rates
+            .drift_rate_at_target(old_rate_at_target, utilisation, seconds_passed);
+    let growth = compounded_growth(interest, cfg.interest_charge_period, seconds_passed)?;
+    let borrow_index = BORROW_INDEX
+        .may_load(deps.storage)?
+        .unwrap_or_else(Decimal::one)
+        .checked_mul(growth)?;
+    let btoken_ratio = growth - Decimal::one();
 
     let old_reserve = RESERVE.load(deps.storage)?;
     // Add to reserve only portion of money charged here
-    let charged_interest = btoken_ratio * borrowed;
+    // Stable-rate debt accrues with its own rates, but the interest goes to lenders as well
+    let now = env.block.time.seconds();
+    let stable_interest = stable_total.amount_at(&cfg, now)? - stable_borrowed;
+    stable_total.amount += stable_interest;
+    stable_total.last_update = now;
+    let charged_interest = btoken_ratio * borrowed + stable_interest;
     let reserve = old_reserve + cfg.reserve_factor * charged_interest;
 
     // remember to add old reserve balance into supplied tokens
-    let base_asset_balance = supplied + old_reserve - borrowed;
+    let base_asset_balance = supplied + old_reserve - borrowed - stable_borrowed;
 
-    let l_supply = borrowed + base_asset_balance - reserve;
+    let l_supply = borrowed + stable_borrowed + base_asset_balance - reserve;
 
-    // lMul = b_supply() * ratio / l_supply
-    let ltoken_ratio: Decimal = Decimal::from_ratio(borrowed * btoken_ratio, l_supply);
+    // lMul = charged interest / l_supply
+    let ltoken_ratio: Decimal = Decimal::from_ratio(charged_interest, l_supply);
+    let supply_index = SUPPLY_INDEX
+        .may_load(deps.storage)?
+        .unwrap_or_else(Decimal::one)
+        .checked_mul(ltoken_ratio + Decimal::one())?;
 
     Ok(Some(InterestUpdate {
         reserve,
         ltoken_ratio,
         btoken_ratio,
+        borrow_index,
+        supply_index,
+        utilisation,
+        rate: interest,
+        rate_at_target,
+        stable_total,
     }))
 }
 
-/// Figure out the current utilisation
-pub fn utilisation(tokens_info: &TokensInfo) -> Decimal {
+/// Figure out the current utilisation, counting both variable and `stable_debt`
+pub fn utilisation(tokens_info: &TokensInfo, stable_debt: Uint128) -> Decimal {
     if tokens_info.ltoken.total_supply.is_zero() {
         Decimal::zero()
     } else {
         Decimal::from_ratio(
-            tokens_info.btoken.total_supply.display_amount(),
+            tokens_info.btoken.total_supply.display_amount() + stable_debt,
             tokens_info.ltoken.total_supply.display_amount(),
         )
     }
@@ -108,3 +176,21 @@ pub fn token_supply(deps: Deps, config: &Config) -> Result<TokensInfo, ContractE
     )?;
     Ok(TokensInfo { ltoken, btoken })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn growth_over_too_many_periods() {
+        let periods = u64::from(u32::MAX) + 1;
+        assert_eq!(
+            compounded_growth(Decimal::zero(), 1, periods).unwrap_err(),
+            ContractError::TooManyChargePeriods(periods)
+        );
+        assert_eq!(
+            compounded_growth(Decimal::zero(), 1, periods - 1).unwrap(),
+            Decimal::one()
+        );
+    }
+}
@@ -1,10 +1,17 @@
 pub mod contract;
+mod cost_basis;
 mod error;
+mod history;
 mod interest;
-mod math;
+mod liquidity;
 pub mod msg;
 #[cfg(test)]
 mod multitest;
+mod oracle;
+mod ramp;
+mod reserve;
+mod stable;
 pub mod state;
+mod term;
 
 pub use crate::error::ContractError;
@@ -0,0 +1,175 @@
+use cosmwasm_std::{Coin, Decimal, Env, QueryRequest, Uint128};
+use osmo_bindings::{OsmosisQuery, SwapAmount, SwapResponse};
+use utils::price::{coin_times_price_rate, PriceRate};
+
+use crate::{
+    contract::Deps,
+    msg::{DepthStep, LiquidityHaircut},
+    oracle::{osmosis_route, query_route},
+    state::Config,
+    ContractError,
+};
+
+impl LiquidityHaircut {
+    pub fn validate(&self) -> Result<(), ContractError> {
+        match self {
+            LiquidityHaircut::EstimateSwap { .. } => Ok(()),
+            LiquidityHaircut::DepthCurve { steps } => {
+                let ordered = steps
+                    .windows(2)
+                    .all(|w| w[0].amount < w[1].amount && w[0].discount <= w[1].discount);
+                let bounded = steps.iter().all(|step| step.discount <= Decimal::one());
+
+                if ordered && bounded {
+                    Ok(())
+                } else {
+                    Err(ContractError::InvalidDepthCurve {})
+                }
+            }
+        }
+    }
+}
+
+/// Values `collateral` (in market tokens) in common tokens, applying the liquidity haircut
+/// configured for the market, if any
+pub fn collateral_value(
+    deps: Deps,
+    env: &Env,
+    cfg: &Config,
+    collateral: &Coin,
+    price_rate: &PriceRate,
+) -> Result<Uint128, ContractError> {
+    let spot_value = coin_times_price_rate(collateral, price_rate)?.amount;
+
+    match &cfg.liquidity_haircut {
+        None => Ok(spot_value),
+        Some(LiquidityHaircut::EstimateSwap { threshold }) => {
+            // Nothing to sell if the market token is the common token already
+            if collateral.amount <= *threshold || cfg.market_token == cfg.common_token {
+                return Ok(spot_value);
+            }
+
+            let swap_value = estimate_sale(deps, env, cfg, collateral.amount)?;
+            Ok(std::cmp::min(spot_value, swap_value))
+        }
+        Some(LiquidityHaircut::DepthCurve { steps }) => {
+            let discounted = Coin {
+                denom: collateral.denom.clone(),
+                amount: apply_depth_curve(collateral.amount, steps),
+            };
+            Ok(coin_times_price_rate(&discounted, price_rate)?.amount)
+        }
+    }
+}
+
+/// Amount of common tokens which selling `amount` of market tokens would yield
+fn estimate_sale(
+    deps: Deps,
+    env: &Env,
+    cfg: &Config,
+    amount: Uint128,
+) -> Result<Uint128, ContractError> {
+    let route = query_route(deps, cfg, &cfg.market_token, &cfg.common_token)?;
+    let (first, route) = osmosis_route(route)?;
+
+    let estimate: SwapResponse =
+        deps.querier
+            .query(&QueryRequest::Custom(OsmosisQuery::EstimateSwap {
+                sender: env.contract.address.to_string(),
+                first,
+                route,
+                amount: SwapAmount::In(amount),
+            }))?;
+
+    match estimate.amount {
+        SwapAmount::Out(out) => Ok(out),
+        SwapAmount::In(_) => Err(ContractError::IncorrectSwapAmountResponse {}),
+    }
+}
+
+/// Reduces `amount` according to the depth curve, tranche by tranche
+fn apply_depth_curve(amount: Uint128, steps: &[DepthStep]) -> Uint128 {
+    let mut result = std::cmp::min(
+        amount,
+        steps.first().map(|step| step.amount).unwrap_or(amount),
+    );
+
+    for (idx, step) in steps.iter().enumerate() {
+        if amount <= step.amount {
+            break;
+        }
+
+        let tranche_end = steps
+            .get(idx + 1)
+            .map(|next| std::cmp::min(next.amount, amount))
+            .unwrap_or(amount);
+        let tranche = tranche_end - step.amount;
+        result += tranche * (Decimal::one() - step.discount);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(amount: u128, discount: u64) -> DepthStep {
+        DepthStep {
+            amount: Uint128::new(amount),
+            discount: Decimal::percent(discount),
+        }
+    }
+
+    #[test]
+    fn depth_curve_tranches() {
+        let steps = [step(500, 10), step(800, 50)];
+
+        assert_eq!(
+            apply_depth_curve(Uint128::new(400), &steps),
+            Uint128::new(400)
+        );
+        assert_eq!(
+            apply_depth_curve(Uint128::new(600), &steps),
+            Uint128::new(590)
+        );
+        // 500 + 300 * 0.9 + 200 * 0.5
+        assert_eq!(
+            apply_depth_curve(Uint128::new(1000), &steps),
+            Uint128::new(870)
+        );
+        assert_eq!(
+            apply_depth_curve(Uint128::new(1000), &[]),
+            Uint128::new(1000)
+        );
+    }
+
+    #[test]
+    fn depth_curve_validation() {
+        let valid = LiquidityHaircut::DepthCurve {
+            steps: vec![step(500, 10), step(800, 50)],
+        };
+        valid.validate().unwrap();
+
+        let unordered = LiquidityHaircut::DepthCurve {
+            steps: vec![step(800, 10), step(500, 50)],
+        };
+        assert_eq!(
+            unordered.validate(),
+            Err(ContractError::InvalidDepthCurve {})
+        );
+
+        let decreasing = LiquidityHaircut::DepthCurve {
+            steps: vec![step(500, 50), step(800, 10)],
+        };
+        assert_eq!(
+            decreasing.validate(),
+            Err(ContractError::InvalidDepthCurve {})
+        );
+
+        let too_big = LiquidityHaircut::DepthCurve {
+            steps: vec![step(500, 150)],
+        };
+        assert_eq!(too_big.validate(), Err(ContractError::InvalidDepthCurve {}));
+    }
+}
@@ -2,8 +2,10 @@ use cosmwasm_std::{Decimal, Timestamp, Uint128};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use utils::interest::Interest;
-use utils::{coin::Coin, token::Token};
+use utils::interest::{Interest, ValidatedInterest};
+use utils::{coin::Coin, credit_line::CreditLineResponse, time::Expiration, token::Token};
+
+use crate::state::Ramp;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
@@ -33,6 +35,19 @@ pub struct InstantiateMsg {
     pub price_oracle: String,
     /// Defines the portion of borrower interest that is converted into reserves (0 <= x <= 1)
     pub reserve_factor: Decimal,
+    /// Portion of every borrowed amount added to the debt and converted into reserves (0 <= x < 1)
+    #[serde(default)]
+    pub origination_fee: Decimal,
+    /// If not set, the market token can be supplied but never borrowed
+    #[serde(default = "default_enabled")]
+    pub borrowing_enabled: bool,
+    /// If not set, deposits into this market never count towards the credit line
+    #[serde(default = "default_enabled")]
+    pub collateral_enabled: bool,
+}
+
+pub(crate) fn default_enabled() -> bool {
+    true
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -58,11 +73,36 @@ pub enum ExecuteMsg {
         amount: Uint128,
     },
     /// Helper to allow repay of debt on given account. Transfers and burns btokens.
+    /// Repays up to `amount` of the funds sent and returns the rest to the sender.
     /// Sender must be a Credit Agency
     RepayTo {
         account: String,
         amount: Uint128,
     },
+    /// Same as `Deposit`, but the L Tokens are minted to `account`.
+    /// Only callable by the credit agency, as part of a batch.
+    DepositFor {
+        account: String,
+    },
+    /// Same as `Withdraw`, but for `account`, which receives the base asset.
+    /// Only callable by the credit agency, as part of a batch. Skips the credit line check,
+    /// the credit agency checks the account once the batch is done.
+    WithdrawFor {
+        account: String,
+        amount: Uint128,
+    },
+    /// Same as `Borrow`, but for `account`, which receives the base asset.
+    /// Only callable by the credit agency, as part of a batch. Skips the credit line check,
+    /// the credit agency checks the account once the batch is done.
+    BorrowFor {
+        account: String,
+        amount: Uint128,
+    },
+    /// Same as `Repay`, but repays the debt of `account`, which receives any surplus.
+    /// Only callable by the credit agency, as part of a batch.
+    RepayFor {
+        account: String,
+    },
     AdjustCommonToken {
         new_token: Token,
     },
@@ -75,8 +115,60 @@ pub enum ExecuteMsg {
         buy: Coin,
     },
     /// Deposits the market currency sent with this message and distributes the L Tokens to all existing lenders.
+    /// `distribution_reserve_share` of the funds goes to reserves instead.
     /// Only callable by the credit agency.
     DistributeAsLTokens {},
+    /// Moves the cost basis of `amount` transferred L Tokens from `sender` to `recipient`.
+    /// Only callable by the L Token contract, which sends it after every transfer.
+    AfterTransfer {
+        sender: String,
+        recipient: String,
+        amount: Uint128,
+    },
+    /// Charges interest accrued since the last charge. Callable by anyone, so quiet markets
+    /// can be kept up to date. The sender receives `accrual_reward` of the reserves accrued
+    /// by this charge.
+    AccrueInterest {},
+    /// Same as `AccrueInterest`, but the reward is sent to `recipient`.
+    /// Only callable by the credit agency.
+    AccrueInterestFor {
+        recipient: String,
+    },
+    /// Borrows `amount` with the interest rate locked at the current stable rate, which
+    /// must not exceed `max_rate` if given. Stable-rate debt is tracked separately from
+    /// B Tokens.
+    BorrowStable {
+        amount: Uint128,
+        max_rate: Option<Decimal>,
+    },
+    /// Repays the sender's stable-rate debt with the market tokens sent with this message.
+    /// Surplus is returned.
+    RepayStable {},
+    /// Takes a term loan of `amount`, due `duration` seconds from now. The rate is quoted at
+    /// origination and must not exceed `max_rate` if given.
+    BorrowTerm {
+        amount: Uint128,
+        duration: u64,
+        max_rate: Option<Decimal>,
+    },
+    /// Repays the sender's term loan with the market tokens sent with this message. Repaying
+    /// before maturity is charged the early repayment fee. Surplus is returned.
+    RepayTerm {
+        loan_id: u64,
+    },
+    /// Sends the reserves allocated to `bucket` to its recipient, as far as the market has
+    /// enough liquidity. Callable by anyone.
+    WithdrawReserve {
+        bucket: String,
+    },
+    /// Winds the market down: no new deposits or borrows are accepted. Optionally ramps the
+    /// collateral ratio to zero, reaching it at `collateral_ratio_ramp_end`, and switches to
+    /// `interest_rates` to push borrowers to repay.
+    /// Only callable by the credit agency.
+    Deprecate {
+        collateral_ratio_ramp_end: Option<Expiration>,
+        interest_rates: Option<Interest>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -107,16 +199,192 @@ pub enum QueryMsg {
     Reserve {},
     /// APY Query
     Apy {},
+    /// Returns PendingRampsResponse
+    PendingRamps {},
+    /// Returns StableDebtResponse
+    StableDebt { account: String },
+    /// Returns StableRateResponse with the rate new stable-rate loans would get
+    StableRate {},
+    /// Returns TermLoansResponse with the term loans of an account, ordered by id
+    TermLoans {
+        account: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns TermRateResponse with the rate a new term loan of `amount` would get
+    TermRate { amount: Uint128 },
+    /// Returns OverdueResponse
+    Overdue { account: String },
+    /// Returns ReserveBucketsResponse
+    ReserveBuckets {},
+    /// Lists accounts holding L-tokens or B-tokens or having fixed-rate debt, with balances
+    /// and debts including pending interest.
+    /// Pagination by account address.
+    /// Returns ListPositionsResponse
+    ListPositions {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Interest the account earned on its L-Tokens and paid on its B-Tokens so far, including
+    /// pending interest. Only covers what happened since the market started tracking the
+    /// principal of the account.
+    /// Returns AccountInterestResponse
+    AccountInterest { account: String },
+    /// Rates and cumulative indexes recorded when interest was charged, at most once an hour,
+    /// oldest first. Only the most recent snapshots are kept. Pagination by snapshot id.
+    /// Returns RateHistoryResponse
+    RateHistory {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Total credit line and health of the account after borrowing `amount`, and whether
+    /// the borrow would succeed.
+    /// Returns SimulationResponse
+    SimulateBorrow { account: String, amount: Uint128 },
+    /// Total credit line and health of the account after withdrawing `amount`, and whether
+    /// the withdrawal would succeed.
+    /// Returns SimulationResponse
+    SimulateWithdraw { account: String, amount: Uint128 },
+    /// Total credit line and health of the account after repaying `amount`, and whether
+    /// the repayment would succeed.
+    /// Returns SimulationResponse
+    SimulateRepay { account: String, amount: Uint128 },
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 #[serde(rename_all = "snake_case")]
 pub enum SudoMsg {
-    AdjustCollateralRatio { new_ratio: Decimal },
-    AdjustReserveFactor { new_factor: Decimal },
-    AdjustPriceOracle { new_oracle: String },
-    AdjustMarketCap { new_cap: Option<Uint128> },
-    AdjustInterestRates { new_interest_rates: Interest },
+    AdjustCollateralRatio {
+        new_ratio: Decimal,
+    },
+    AdjustReserveFactor {
+        new_factor: Decimal,
+    },
+    AdjustPriceOracle {
+        new_oracle: String,
+    },
+    AdjustMarketCap {
+        new_cap: Option<Uint128>,
+    },
+    AdjustInterestRates {
+        new_interest_rates: Interest,
+    },
+    AdjustLiquidityHaircut {
+        new_haircut: Option<LiquidityHaircut>,
+    },
+    AdjustAccrualReward {
+        new_reward: Decimal,
+    },
+    AdjustOriginationFee {
+        new_fee: Decimal,
+    },
+    /// Sets the portion of the funds distributed with `DistributeAsLTokens` which goes to
+    /// reserves instead of lenders
+    AdjustDistributionReserveShare {
+        new_share: Decimal,
+    },
+    /// Turns borrowing from the market and using its deposits as collateral on or off.
+    /// Flags which are not set stay unchanged.
+    AdjustMarketFlags {
+        borrowing_enabled: Option<bool>,
+        collateral_enabled: Option<bool>,
+    },
+    /// Moves the collateral ratio linearly from its current value to `target`, reaching it
+    /// at `end`. `AdjustCollateralRatio` cancels the ramp.
+    RampCollateralRatio {
+        target: Decimal,
+        end: Expiration,
+    },
+    /// Moves the interest rates linearly from the current curve to `target`, reaching it
+    /// at `end`. `AdjustInterestRates` cancels the ramp.
+    RampInterestRates {
+        target: Interest,
+        end: Expiration,
+    },
+    /// Stops a ramp in progress. The collateral ratio stays at its current value, while the
+    /// interest rates go back to the curve the ramp started from.
+    CancelRamp {
+        parameter: RampParameter,
+    },
+    AdjustStableBorrowing {
+        new_config: Option<StableBorrowing>,
+    },
+    /// Moves the stable rate of `account` to the current stable rate, if the conditions set in
+    /// `StableBorrowing` are met
+    RebalanceStableRate {
+        account: String,
+    },
+    AdjustTermLending {
+        new_config: Option<TermLending>,
+    },
+    /// Sets the buckets reserves are split between. Reserves accrued so far are allocated
+    /// with the previous split first, then the balances of removed buckets are released to be
+    /// split with the new one.
+    AdjustReserveSplit {
+        buckets: Vec<ReserveBucket>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct StableBorrowing {
+    /// Stable rate is the variable rate at the utilisation after borrowing plus this premium
+    pub premium: Decimal,
+    /// Stable rates can be raised to the current stable rate once utilisation reaches this
+    pub rebalance_up_utilisation: Decimal,
+    /// Stable rates can be lowered to the current stable rate once they exceed it by this much
+    pub rebalance_down_delta: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct TermLending {
+    /// Term loan rate is the variable rate at the utilisation after borrowing plus this premium
+    pub premium: Decimal,
+    /// Rate additionally charged on overdue loans. Penalty interest goes to the reserve.
+    pub penalty_rate: Decimal,
+    /// Longest term (in seconds) a loan can be taken for
+    pub max_duration: u64,
+    /// Portion of the principal repaid before maturity charged on top of it, goes to the
+    /// reserve
+    pub early_repayment_fee: Decimal,
+}
+
+/// Named destination of a share of the reserves, such as treasury, insurance or stakers
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ReserveBucket {
+    pub name: String,
+    /// Address the bucket is withdrawn to
+    pub recipient: String,
+    /// Share of the reserves relative to the other buckets
+    pub weight: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum RampParameter {
+    CollateralRatio,
+    InterestRates,
+}
+
+/// Discount applied when valuing large collateral positions, so they are not worth more
+/// than what could actually be realized by selling them during a liquidation
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum LiquidityHaircut {
+    /// Collateral above `threshold` market tokens is valued at the effective price of selling
+    /// it for the common token, as estimated by Osmosis (including slippage and fees)
+    EstimateSwap { threshold: Uint128 },
+    /// Governance-defined depth curve. Collateral between the `amount` of a step and the
+    /// `amount` of the next one is discounted by the step's `discount`. Collateral below the
+    /// first step is valued at the spot price.
+    DepthCurve { steps: Vec<DepthStep> },
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct DepthStep {
+    /// Amount of market tokens above which the discount applies
+    pub amount: Uint128,
+    /// Portion of the value that is cut off, 0 <= x <= 1
+    pub discount: Decimal,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
@@ -136,6 +404,14 @@ pub struct InterestResponse {
     pub interest: Decimal,
     pub utilisation: Decimal,
     pub charge_period: Timestamp,
+    /// Stable-rate debt and term loans including interest, without penalties
+    pub fixed_rate_debt: Uint128,
+    /// Cumulative growth of variable-rate debt since the market was created, including the
+    /// pending interest
+    pub borrow_index: Decimal,
+    /// Cumulative growth of L-Tokens since the market was created, including the pending
+    /// interest
+    pub supply_index: Decimal,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -175,3 +451,124 @@ pub struct ApyResponse {
     /// How much % interest will a lender earn
     pub lender: Decimal,
 }
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct PendingRampsResponse {
+    pub collateral_ratio: Option<Ramp<Decimal>>,
+    pub interest_rates: Option<Ramp<ValidatedInterest>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct StableDebtResponse {
+    /// Debt including interest accrued until now
+    pub amount: Uint128,
+    /// Rate locked for the debt
+    pub rate: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct StableRateResponse {
+    pub rate: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct TermLoanResponse {
+    pub id: u64,
+    /// Debt including interest and penalty accrued until now
+    pub amount: Uint128,
+    /// Rate quoted at origination
+    pub rate: Decimal,
+    pub maturity: Timestamp,
+    /// Overdue loans accrue penalty interest and can be liquidated
+    pub overdue: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct TermLoansResponse {
+    pub loans: Vec<TermLoanResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct TermRateResponse {
+    pub rate: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct OverdueResponse {
+    /// True if any term loan of the account is past its maturity
+    pub overdue: bool,
+    /// Debt of the loans past their maturity, including penalties
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ReserveBucketResponse {
+    pub name: String,
+    pub recipient: String,
+    pub weight: u64,
+    /// Reserves allocated to the bucket and not withdrawn yet
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct SimulationResponse {
+    /// Total credit line of the account across all markets after the action
+    pub credit_line: CreditLineResponse,
+    /// Credit line divided by debt after the action, not set if there is no debt left
+    pub health: Option<Decimal>,
+    /// If the action would succeed right now
+    pub allowed: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct PositionResponse {
+    pub account: String,
+    pub ltokens: Uint128,
+    pub btokens: Uint128,
+    /// Stable-rate debt including interest
+    pub stable_debt: Uint128,
+    /// Debt of all term loans including interest and penalties
+    pub term_debt: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ListPositionsResponse {
+    pub positions: Vec<PositionResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct AccountInterestResponse {
+    /// Growth of the L-Tokens on top of the deposits, including distributed rewards
+    pub earned: Uint128,
+    /// Growth of the B-Tokens on top of the borrowed amounts, including origination fees
+    pub paid: Uint128,
+}
+
+/// Rates and indexes of the market right after interest was charged
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct RateSnapshot {
+    pub id: u64,
+    pub timestamp: Timestamp,
+    pub utilisation: Decimal,
+    /// Yearly interest rate of variable-rate debt
+    pub borrow_rate: Decimal,
+    /// Yearly interest rate lenders earned with the charge, including interest paid on
+    /// fixed-rate debt
+    pub supply_rate: Decimal,
+    /// Cumulative growth of B-Tokens since the market was created
+    pub borrow_index: Decimal,
+    /// Cumulative growth of L-Tokens since the market was created
+    pub supply_index: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct RateHistoryResponse {
+    pub snapshots: Vec<RateSnapshot>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ReserveBucketsResponse {
+    pub buckets: Vec<ReserveBucketResponse>,
+    /// Reserves not allocated to any bucket, as there is no split set or due to rounding
+    pub unallocated: Uint128,
+}
@@ -1,3 +1,5 @@
+mod account_interest;
+mod accrue_interest;
 mod apy;
 mod borrow_repay;
 mod ca_mock;
@@ -5,10 +7,20 @@ mod common;
 mod credit_line;
 mod deposit;
 mod distribute_as_ltokens;
+mod events;
+mod for_account;
 mod interest;
+mod liquidity_haircut;
+mod market_flags;
 mod migration;
+mod positions;
+mod ramp;
+mod rate_history;
 mod reserve;
+mod simulate;
+mod stable;
 mod sudo;
 pub mod suite;
 mod swap_withdraw_from;
+mod term;
 mod withdraw;
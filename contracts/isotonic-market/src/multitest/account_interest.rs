@@ -0,0 +1,145 @@
+use super::suite::{SuiteBuilder, COMMON};
+
+use cosmwasm_std::{coin, Coin, Uint128};
+use utils::coin::coin_native;
+
+use crate::msg::AccountInterestResponse;
+use crate::state::SECONDS_IN_YEAR;
+
+const YEAR: u64 = SECONDS_IN_YEAR as u64;
+
+#[test]
+fn interest_excludes_principal() {
+    let lender = "lender";
+    let borrower = "borrower";
+    let market_token = "atom";
+    let mut suite = SuiteBuilder::new()
+        .with_charge_period(YEAR)
+        .with_funds(lender, &[coin(2000, market_token)])
+        .with_funds(borrower, &[coin(500, market_token)])
+        .with_interest(4, 20)
+        .with_reserve_factor(10)
+        .with_market_token(market_token)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+
+    suite.set_high_credit_line(lender).unwrap();
+    suite.set_high_credit_line(borrower).unwrap();
+    suite
+        .deposit(lender, &[Coin::new(2000, market_token)])
+        .unwrap();
+    suite.borrow(borrower, 1600).unwrap();
+
+    let no_interest = AccountInterestResponse {
+        earned: Uint128::zero(),
+        paid: Uint128::zero(),
+    };
+    assert_eq!(suite.query_account_interest(lender).unwrap(), no_interest);
+    assert_eq!(suite.query_account_interest(borrower).unwrap(), no_interest);
+
+    // 20% interest on 1600 borrowed, pending until charged
+    suite.advance_seconds(YEAR);
+    let lender_interest = suite.query_account_interest(lender).unwrap();
+    assert_eq!(lender_interest.paid, Uint128::zero());
+    assert_eq!(
+        suite.query_account_interest(borrower).unwrap(),
+        AccountInterestResponse {
+            earned: Uint128::zero(),
+            paid: Uint128::new(320),
+        }
+    );
+
+    // Moving principal around doesn't change the interest
+    suite.repay(borrower, coin(500, market_token)).unwrap();
+    suite.withdraw(lender, 800).unwrap();
+    suite.assert_ltoken_balance(lender, lender_interest.earned + Uint128::new(1200));
+    suite.assert_btoken_balance(borrower, 1420u128);
+    assert_eq!(
+        suite.query_account_interest(lender).unwrap(),
+        lender_interest
+    );
+    assert_eq!(
+        suite.query_account_interest(borrower).unwrap().paid,
+        Uint128::new(320)
+    );
+}
+
+#[test]
+fn liquidated_collateral_counts_as_withdrawn() {
+    let user = "user";
+    let atom = "ATOM";
+    let mut suite = SuiteBuilder::new()
+        .with_market_token(atom)
+        .with_funds(user, &[coin(5_000, atom)])
+        .with_pool(1, (coin(100_000, COMMON), coin(100_000, atom)))
+        .build();
+
+    suite.deposit(user, &[coin(5_000, atom)]).unwrap();
+
+    let ca = suite.credit_agency();
+    suite
+        .swap_withdraw_from(ca, user, Uint128::new(2_000), coin_native(2_000, atom))
+        .unwrap();
+
+    suite.assert_ltoken_balance(user, 3_000u128);
+    assert_eq!(
+        suite.query_account_interest(user).unwrap(),
+        AccountInterestResponse {
+            earned: Uint128::zero(),
+            paid: Uint128::zero(),
+        }
+    );
+}
+
+#[test]
+fn transferred_ltokens_move_principal() {
+    let lender = "lender";
+    let borrower = "borrower";
+    let friend = "friend";
+    let market_token = "atom";
+    let mut suite = SuiteBuilder::new()
+        .with_charge_period(YEAR)
+        .with_funds(lender, &[coin(2000, market_token)])
+        .with_interest(4, 20)
+        .with_market_token(market_token)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+
+    suite.set_high_credit_line(lender).unwrap();
+    suite.set_high_credit_line(borrower).unwrap();
+    suite.set_high_credit_line(friend).unwrap();
+    suite
+        .deposit(lender, &[Coin::new(2000, market_token)])
+        .unwrap();
+    suite.borrow(borrower, 1600).unwrap();
+
+    suite.advance_seconds(YEAR);
+    suite.accrue_interest(borrower).unwrap();
+    let lender_interest = suite.query_account_interest(lender).unwrap();
+    assert_ne!(lender_interest.earned, Uint128::zero());
+
+    // The friend receives principal, not interest, and the lender keeps what it earned
+    suite.transfer_ltokens(lender, friend, 1000).unwrap();
+    assert_eq!(
+        suite.query_account_interest(lender).unwrap(),
+        lender_interest
+    );
+    assert_eq!(
+        suite.query_account_interest(friend).unwrap(),
+        AccountInterestResponse {
+            earned: Uint128::zero(),
+            paid: Uint128::zero(),
+        }
+    );
+
+    // Transfers between tracked accounts move the principal too
+    suite.transfer_ltokens(friend, lender, 580).unwrap();
+    assert_eq!(
+        suite.query_account_interest(lender).unwrap(),
+        lender_interest
+    );
+    assert_eq!(
+        suite.query_account_interest(friend).unwrap().earned,
+        Uint128::zero()
+    );
+}
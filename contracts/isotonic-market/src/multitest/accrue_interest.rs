@@ -0,0 +1,129 @@
+use super::suite::{SuiteBuilder, COMMON};
+
+use cosmwasm_std::{coin, Coin, Decimal, Uint128};
+
+use crate::error::ContractError;
+use crate::state::SECONDS_IN_YEAR;
+
+const YEAR: u64 = SECONDS_IN_YEAR as u64;
+
+#[test]
+fn charges_interest_without_reward() {
+    let lender = "lender";
+    let borrower = "borrower";
+    let market_token = "atom";
+    let mut suite = SuiteBuilder::new()
+        .with_charge_period(YEAR)
+        .with_funds(lender, &[coin(2000, market_token)])
+        .with_interest(4, 20)
+        .with_reserve_factor(10)
+        .with_market_token(market_token)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+
+    suite.set_high_credit_line(borrower).unwrap();
+    suite
+        .deposit(lender, &[Coin::new(2000, market_token)])
+        .unwrap();
+    suite.borrow(borrower, 1600).unwrap();
+
+    suite.advance_seconds(YEAR);
+    suite.accrue_interest("keeper").unwrap();
+
+    // 20% interest on 1600 borrowed, 10% of it goes to reserves
+    suite.assert_btoken_balance(borrower, 1920u128);
+    assert_eq!(suite.query_reserve().unwrap(), Uint128::new(32));
+    assert_eq!(
+        suite.query_config().unwrap().last_charged,
+        suite.app().block_info().time.seconds()
+    );
+    assert_eq!(suite.query_asset_balance("keeper").unwrap(), 0);
+}
+
+#[test]
+fn caller_receives_reward_from_reserves() {
+    let lender = "lender";
+    let borrower = "borrower";
+    let market_token = "atom";
+    let mut suite = SuiteBuilder::new()
+        .with_charge_period(YEAR)
+        .with_funds(lender, &[coin(2000, market_token)])
+        .with_interest(4, 20)
+        .with_reserve_factor(10)
+        .with_market_token(market_token)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+
+    suite.sudo_adjust_accrual_reward(25).unwrap();
+    suite.set_high_credit_line(borrower).unwrap();
+    suite
+        .deposit(lender, &[Coin::new(2000, market_token)])
+        .unwrap();
+    suite.borrow(borrower, 1600).unwrap();
+
+    suite.advance_seconds(YEAR);
+    suite.accrue_interest("keeper").unwrap();
+
+    // 25% of the 32 tokens accrued into reserves
+    assert_eq!(suite.query_asset_balance("keeper").unwrap(), 8);
+    assert_eq!(suite.query_reserve().unwrap(), Uint128::new(24));
+    assert_eq!(suite.query_contract_asset_balance().unwrap(), 392);
+
+    // Nothing accrued since, so nothing to reward
+    suite.accrue_interest("keeper").unwrap();
+    assert_eq!(suite.query_asset_balance("keeper").unwrap(), 8);
+    assert_eq!(suite.query_reserve().unwrap(), Uint128::new(24));
+}
+
+#[test]
+fn credit_agency_can_redirect_reward() {
+    let lender = "lender";
+    let borrower = "borrower";
+    let market_token = "atom";
+    let mut suite = SuiteBuilder::new()
+        .with_charge_period(YEAR)
+        .with_funds(lender, &[coin(2000, market_token)])
+        .with_interest(4, 20)
+        .with_reserve_factor(10)
+        .with_market_token(market_token)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+
+    suite.sudo_adjust_accrual_reward(50).unwrap();
+    suite.set_high_credit_line(borrower).unwrap();
+    suite
+        .deposit(lender, &[Coin::new(2000, market_token)])
+        .unwrap();
+    suite.borrow(borrower, 1600).unwrap();
+
+    suite.advance_seconds(YEAR);
+
+    let err = suite.accrue_interest_for("keeper", "keeper").unwrap_err();
+    assert_eq!(
+        ContractError::RequiresCreditAgency {},
+        err.downcast().unwrap()
+    );
+
+    let ca = suite.credit_agency();
+    suite.accrue_interest_for(&ca, "keeper").unwrap();
+
+    assert_eq!(suite.query_asset_balance("keeper").unwrap(), 16);
+    assert_eq!(suite.query_reserve().unwrap(), Uint128::new(16));
+}
+
+#[test]
+fn accrual_reward_is_validated() {
+    let mut suite = SuiteBuilder::new().build();
+
+    let err = suite.sudo_adjust_accrual_reward(101).unwrap_err();
+    assert_eq!(
+        ContractError::InvalidAccrualReward {},
+        err.downcast().unwrap()
+    );
+
+    suite.sudo_adjust_accrual_reward(5).unwrap();
+    assert_eq!(
+        suite.query_config().unwrap().accrual_reward,
+        Decimal::percent(5)
+    );
+}
@@ -231,3 +231,102 @@ fn query_borrowable_with_limited_liquidity() {
     suite.assert_borrowable(borrower, 20);
     suite.attempt_borrow_max(borrower).unwrap();
 }
+
+#[test]
+fn borrow_charges_origination_fee() {
+    let lender = "lender";
+    let borrower = "borrower";
+    let market_token = "ATOM";
+    let mut suite = SuiteBuilder::new()
+        .with_funds(lender, &[coin(1000, market_token)])
+        .with_funds(borrower, &[coin(5, market_token)])
+        .with_origination_fee(1)
+        .with_market_token(market_token)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+
+    suite.set_high_credit_line(borrower).unwrap();
+    suite.deposit(lender, &[coin(1000, market_token)]).unwrap();
+
+    // The whole amount is paid out, the fee is added to the debt and goes to reserves
+    suite.borrow(borrower, 500).unwrap();
+    assert_eq!(suite.query_asset_balance(borrower).unwrap(), 505);
+    assert_eq!(suite.query_btoken_balance(borrower).unwrap().u128(), 505);
+    assert_eq!(suite.query_reserve().unwrap(), Uint128::new(5));
+    assert_eq!(suite.query_ltoken_balance(lender).unwrap().u128(), 1000);
+
+    suite.repay(borrower, coin(505, market_token)).unwrap();
+    assert_eq!(suite.query_btoken_balance(borrower).unwrap().u128(), 0);
+    assert_eq!(suite.query_contract_asset_balance().unwrap(), 1005);
+
+    let err = suite.sudo_adjust_origination_fee(100).unwrap_err();
+    assert_eq!(
+        ContractError::InvalidOriginationFee {},
+        err.downcast().unwrap()
+    );
+    suite.sudo_adjust_origination_fee(0).unwrap();
+    suite.borrow(borrower, 100).unwrap();
+    assert_eq!(suite.query_btoken_balance(borrower).unwrap().u128(), 100);
+}
+
+#[test]
+fn repay_to_returns_surplus_to_sender() {
+    let lender = "lender";
+    let borrower = "borrower";
+    let market_token = "ATOM";
+    let mut suite = SuiteBuilder::new()
+        .with_funds(lender, &[coin(1000, market_token)])
+        .with_ca_funds(&[coin(100, market_token)])
+        .with_market_token(market_token)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+    let ca = suite.credit_agency();
+
+    suite.set_high_credit_line(borrower).unwrap();
+    suite.deposit(lender, &[coin(1000, market_token)]).unwrap();
+    suite.borrow(borrower, 80).unwrap();
+
+    // Funds above the whole debt are rejected
+    suite
+        .repay_to(&ca, borrower, 60, coin(100, market_token))
+        .unwrap_err();
+
+    // Only `amount` is repaid, the rest of the funds goes back to the credit agency
+    suite
+        .repay_to(&ca, borrower, 60, coin(70, market_token))
+        .unwrap();
+    assert_eq!(suite.query_btoken_balance(borrower).unwrap().u128(), 20);
+    assert_eq!(suite.query_asset_balance(&ca).unwrap(), 40);
+}
+
+#[test]
+fn query_borrowable_with_origination_fee() {
+    let lender = "lender";
+    let borrower = "borrower";
+    let mut suite = SuiteBuilder::new()
+        .with_funds(lender, &[coin(2000, "ATOM")])
+        .with_origination_fee(1)
+        .with_pool(1, (coin(100, COMMON), coin(100, "ATOM")))
+        .with_market_token("ATOM")
+        .build();
+
+    suite.set_high_credit_line(lender).unwrap();
+    suite
+        .set_credit_line(
+            borrower,
+            CreditLineValues {
+                collateral: Uint128::new(2000),
+                credit_line: Uint128::new(1000),
+                debt: Uint128::zero(),
+            },
+        )
+        .unwrap();
+    suite.deposit(lender, &[coin(2000, "ATOM")]).unwrap();
+
+    // 990 borrowed and 1% fee on it fit in the remaining credit line of 1000. The fee is
+    // rounded down, so 991 fits as well.
+    suite.assert_borrowable(borrower, 991);
+    suite.borrow(borrower, 992).unwrap_err();
+    suite.borrow(borrower, 991).unwrap();
+    assert_eq!(suite.query_btoken_balance(borrower).unwrap().u128(), 1000);
+}
@@ -0,0 +1,130 @@
+use super::suite::{SuiteBuilder, COMMON};
+
+use cosmwasm_std::{coin, Coin, Decimal, Uint128};
+use utils::events::{
+    parse_all, Borrow, Burn, DebtKind, Deposit, Mint, ParameterChange, Rebase, Repay, Withdraw,
+};
+
+use crate::state::SECONDS_IN_YEAR;
+
+const YEAR: u64 = SECONDS_IN_YEAR as u64;
+
+#[test]
+fn actions_emit_typed_events() {
+    let lender = "lender";
+    let borrower = "borrower";
+    let market_token = "atom";
+    let mut suite = SuiteBuilder::new()
+        .with_charge_period(YEAR)
+        .with_funds(lender, &[coin(2000, market_token)])
+        .with_interest(4, 20)
+        .with_market_token(market_token)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+
+    suite.set_high_credit_line(lender).unwrap();
+    suite.set_high_credit_line(borrower).unwrap();
+
+    let response = suite
+        .deposit(lender, &[Coin::new(2000, market_token)])
+        .unwrap();
+    assert_eq!(
+        parse_all::<Deposit>(&response.events).unwrap(),
+        vec![Deposit {
+            sender: lender.to_owned(),
+            recipient: lender.to_owned(),
+            amount: Uint128::new(2000),
+            stored_amount: Uint128::new(200_000_000),
+        }]
+    );
+    assert_eq!(
+        parse_all::<Mint>(&response.events).unwrap(),
+        vec![Mint {
+            recipient: lender.to_owned(),
+            amount: Uint128::new(2000),
+            stored_amount: Uint128::new(200_000_000),
+        }]
+    );
+
+    let response = suite.borrow(borrower, 1000).unwrap();
+    assert_eq!(
+        parse_all::<Borrow>(&response.events).unwrap(),
+        vec![Borrow {
+            account: borrower.to_owned(),
+            kind: DebtKind::Variable,
+            amount: Uint128::new(1000),
+            fee: Uint128::zero(),
+            stored_amount: Uint128::new(100_000_000),
+        }]
+    );
+
+    // 14% interest on the debt, so 7% for the lenders. Tokens start with a multiplier of
+    // 1/100_000.
+    suite.advance_seconds(YEAR);
+    let initial = Decimal::from_ratio(1u128, 100_000u128);
+    let response = suite.repay(borrower, coin(570, market_token)).unwrap();
+    assert_eq!(
+        parse_all::<Rebase>(&response.events).unwrap(),
+        vec![
+            Rebase {
+                ratio: Decimal::percent(114),
+                multiplier: initial * Decimal::percent(114),
+            },
+            Rebase {
+                ratio: Decimal::percent(107),
+                multiplier: initial * Decimal::percent(107),
+            },
+        ]
+    );
+    assert_eq!(
+        parse_all::<Repay>(&response.events).unwrap(),
+        vec![Repay {
+            sender: borrower.to_owned(),
+            account: borrower.to_owned(),
+            kind: DebtKind::Variable,
+            amount: Uint128::new(570),
+            stored_amount: Uint128::new(50_000_000),
+        }]
+    );
+    assert_eq!(
+        parse_all::<Burn>(&response.events).unwrap(),
+        vec![Burn {
+            owner: borrower.to_owned(),
+            amount: Uint128::new(570),
+            stored_amount: Uint128::new(50_000_000),
+        }]
+    );
+
+    let response = suite.withdraw(lender, 1070).unwrap();
+    assert_eq!(
+        parse_all::<Withdraw>(&response.events).unwrap(),
+        vec![Withdraw {
+            account: lender.to_owned(),
+            recipient: lender.to_owned(),
+            amount: Uint128::new(1070),
+            stored_amount: Uint128::new(100_000_000),
+        }]
+    );
+    assert_eq!(
+        parse_all::<Burn>(&response.events).unwrap(),
+        vec![Burn {
+            owner: lender.to_owned(),
+            amount: Uint128::new(1070),
+            stored_amount: Uint128::new(100_000_000),
+        }]
+    );
+}
+
+#[test]
+fn sudo_emits_parameter_change() {
+    let mut suite = SuiteBuilder::new().build();
+
+    let response = suite.sudo_adjust_reserve_factor(20).unwrap();
+    assert_eq!(
+        parse_all::<ParameterChange>(&response.events).unwrap(),
+        vec![ParameterChange {
+            parameter: "adjust_reserve_factor".to_owned(),
+            value: r#"{"new_factor":"0.2"}"#.to_owned(),
+        }]
+    );
+}
@@ -0,0 +1,50 @@
+use cosmwasm_std::coin;
+
+use super::suite::{SuiteBuilder, COMMON};
+use crate::error::ContractError;
+
+#[test]
+fn sender_not_credit_agency() {
+    let mut suite = SuiteBuilder::new().build();
+
+    let err = suite.borrow_for("any sender", "account", 100).unwrap_err();
+    assert_eq!(
+        ContractError::RequiresCreditAgency {},
+        err.downcast().unwrap()
+    );
+
+    let err = suite
+        .withdraw_for("any sender", "account", 100)
+        .unwrap_err();
+    assert_eq!(
+        ContractError::RequiresCreditAgency {},
+        err.downcast().unwrap()
+    );
+}
+
+#[test]
+fn credit_line_not_checked() {
+    let borrower = "borrower";
+    let lender = "lender";
+    let market_token = "ATOM";
+    let mut suite = SuiteBuilder::new()
+        .with_funds(lender, &[coin(100, market_token)])
+        .with_contract_funds(coin(150, market_token))
+        .with_market_token(market_token)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+    let ca = suite.credit_agency();
+
+    suite.deposit(lender, &[coin(100, market_token)]).unwrap();
+
+    // Neither account has a credit line, the credit agency checks it after its batch
+    suite.borrow(borrower, 100).unwrap_err();
+    suite.borrow_for(&ca, borrower, 100).unwrap();
+    assert_eq!(suite.query_btoken_balance(borrower).unwrap().u128(), 100);
+    assert_eq!(suite.query_asset_balance(borrower).unwrap(), 100);
+
+    suite.withdraw(lender, 100).unwrap_err();
+    suite.withdraw_for(&ca, lender, 100).unwrap();
+    assert_eq!(suite.query_ltoken_balance(lender).unwrap().u128(), 0);
+    assert_eq!(suite.query_asset_balance(lender).unwrap(), 100);
+}
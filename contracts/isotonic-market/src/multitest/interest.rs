@@ -1,7 +1,8 @@
 use super::suite::{SuiteBuilder, COMMON};
 
-use cosmwasm_std::{coin, Coin, Decimal, Timestamp};
+use cosmwasm_std::{coin, Coin, Decimal, Timestamp, Uint128};
 use isotonic_token::DisplayAmount;
+use utils::interest::Interest;
 
 use crate::msg::InterestResponse;
 use crate::state::SECONDS_IN_YEAR;
@@ -33,6 +34,9 @@ fn query_interest() {
             interest: Decimal::percent(3),
             utilisation: Decimal::zero(),
             charge_period: Timestamp::from_seconds(300),
+            fixed_rate_debt: Uint128::zero(),
+            borrow_index: Decimal::one(),
+            supply_index: Decimal::one(),
         },
         resp
     );
@@ -53,6 +57,9 @@ fn query_interest() {
             interest: Decimal::percent(3),
             utilisation: Decimal::zero(),
             charge_period: Timestamp::from_seconds(300),
+            fixed_rate_debt: Uint128::zero(),
+            borrow_index: Decimal::one(),
+            supply_index: Decimal::one(),
         },
         resp
     );
@@ -68,6 +75,9 @@ fn query_interest() {
             utilisation: Decimal::percent(10),
             interest: Decimal::percent(3) + Decimal::percent(2),
             charge_period: Timestamp::from_seconds(300),
+            fixed_rate_debt: Uint128::zero(),
+            borrow_index: Decimal::one(),
+            supply_index: Decimal::one(),
         },
         resp
     );
@@ -83,6 +93,9 @@ fn query_interest() {
             utilisation: Decimal::percent(5),
             interest: Decimal::percent(3) + Decimal::percent(1),
             charge_period: Timestamp::from_seconds(300),
+            fixed_rate_debt: Uint128::zero(),
+            borrow_index: Decimal::one(),
+            supply_index: Decimal::one(),
         },
         resp
     );
@@ -100,6 +113,9 @@ fn query_interest() {
             utilisation: Decimal::from_ratio(1u8, 30u8),
             interest: Decimal::percent(3) + Decimal::from_ratio(1u8, 150u8),
             charge_period: Timestamp::from_seconds(300),
+            fixed_rate_debt: Uint128::zero(),
+            borrow_index: Decimal::one(),
+            supply_index: Decimal::one(),
         },
         resp
     );
@@ -135,6 +151,9 @@ fn charge_interest_borrow() {
             utilisation: Decimal::percent(80),
             interest: Decimal::percent(20),
             charge_period: Timestamp::from_seconds(YEAR),
+            fixed_rate_debt: Uint128::zero(),
+            borrow_index: Decimal::one(),
+            supply_index: Decimal::one(),
         },
         resp
     );
@@ -277,6 +296,9 @@ fn query_balance_with_uncharged_interest() {
             utilisation: Decimal::percent(25),
             interest: Decimal::percent(15),
             charge_period: Timestamp::from_seconds(YEAR),
+            fixed_rate_debt: Uint128::zero(),
+            borrow_index: Decimal::one(),
+            supply_index: Decimal::one(),
         },
         resp
     );
@@ -381,12 +403,130 @@ fn query_last_charged_with_uncharged_interest() {
 
     suite.borrow(borrower, 1000).unwrap();
 
-    let next_epoch = suite.query_config().unwrap().last_charged + YEAR;
+    let expected = suite.query_config().unwrap().last_charged + YEAR + 123;
 
     suite.advance_seconds(YEAR + 123);
 
-    // we want to make sure the query returns the timestamp as if interest was already charged for this epoch
-    // even if there was no call to `charge_interest`
+    // we want to make sure the query returns the timestamp as if interest was already charged
+    // up to now, even if there was no call to `charge_interest`
 
-    assert_eq!(next_epoch, suite.query_config().unwrap().last_charged);
+    assert_eq!(expected, suite.query_config().unwrap().last_charged);
+}
+
+#[test]
+fn interest_accrues_for_partial_periods() {
+    let lender = "lender";
+    let borrower = "borrower";
+    let market_token = "atom";
+    let build = || {
+        let mut suite = SuiteBuilder::new()
+            .with_funds(lender, &[coin(5000, market_token)])
+            .with_charge_period(YEAR)
+            .with_interest(10, 0)
+            .with_market_token(market_token)
+            .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+            .build();
+
+        suite.set_high_credit_line(borrower).unwrap();
+        suite.set_high_credit_line(lender).unwrap();
+
+        suite.deposit(lender, &[coin(2000, market_token)]).unwrap();
+        suite.borrow(borrower, 1000).unwrap();
+        suite
+    };
+
+    // Half a year in, interest is already there: 1000 * sqrt(110%) ~= 1048.8
+    let mut suite = build();
+    suite.advance_seconds(YEAR / 2);
+    suite.assert_btoken_balance(borrower, 1048u128);
+
+    // Charging in the middle of the period doesn't change the yearly result
+    suite.deposit(lender, &[coin(2, market_token)]).unwrap();
+    suite.advance_seconds(YEAR / 2);
+    suite.deposit(lender, &[coin(2, market_token)]).unwrap();
+    suite.assert_btoken_balance(borrower, 1100u128);
+
+    let mut suite = build();
+    suite.advance_seconds(YEAR);
+    suite.assert_btoken_balance(borrower, 1100u128);
+}
+
+#[test]
+fn adaptive_rate_drifts_with_utilisation() {
+    let lender = "lender";
+    let borrower = "borrower";
+    let market_token = "atom";
+    let mut suite = SuiteBuilder::new()
+        .with_funds(lender, &[coin(1000, market_token)])
+        .with_charge_period(YEAR)
+        .with_market_token(market_token)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+
+    let adaptive = Interest::Adaptive {
+        target_utilisation: Decimal::percent(50),
+        initial_rate_at_target: Decimal::percent(10),
+        min_rate_at_target: Decimal::percent(1),
+        max_rate_at_target: Decimal::percent(100),
+        adjustment_speed: Decimal::percent(1000),
+        steepness: Decimal::percent(400),
+    };
+    suite.sudo_adjust_interest_rates(adaptive.clone()).unwrap();
+    suite.set_high_credit_line(borrower).unwrap();
+
+    suite.deposit(lender, &[coin(1000, market_token)]).unwrap();
+    suite.borrow(borrower, 900).unwrap();
+
+    // 90% utilisation is 80% of the way from the target to the top of the curve:
+    // 10% * (1 + 3 * 80%)
+    assert_eq!(
+        suite.query_interest().unwrap().interest,
+        Decimal::percent(34)
+    );
+
+    // Rate at target drifts by 1000% * 80% * 1/10 year, up to 18%
+    suite.advance_seconds(YEAR / 10);
+    suite.accrue_interest("keeper").unwrap();
+    let interest = suite.query_interest().unwrap();
+    assert!(interest.utilisation > Decimal::percent(90));
+    assert!(interest.interest > Decimal::percent(61));
+    assert!(interest.interest < Decimal::percent(62));
+
+    // Setting the curve again starts it from the initial rate at target
+    suite.sudo_adjust_interest_rates(adaptive).unwrap();
+    let interest = suite.query_interest().unwrap();
+    assert!(interest.interest > Decimal::percent(34));
+    assert!(interest.interest < Decimal::percent(35));
+}
+
+#[test]
+fn indexes_include_pending_interest() {
+    let lender = "lender";
+    let borrower = "borrower";
+    let market_token = "atom";
+    let mut suite = SuiteBuilder::new()
+        .with_charge_period(YEAR)
+        .with_funds(lender, &[coin(2000, market_token)])
+        .with_interest(4, 20)
+        .with_market_token(market_token)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+
+    suite.set_high_credit_line(borrower).unwrap();
+    suite
+        .deposit(lender, &[Coin::new(2000, market_token)])
+        .unwrap();
+    suite.borrow(borrower, 1000).unwrap();
+
+    // 14% interest on the debt, so 7% for the lenders
+    suite.advance_seconds(YEAR);
+    let interest = suite.query_interest().unwrap();
+    assert_eq!(interest.borrow_index, Decimal::percent(114));
+    assert_eq!(interest.supply_index, Decimal::percent(107));
+
+    // Charging the interest on the next borrow doesn't change them
+    suite.borrow(borrower, 10).unwrap();
+    let charged = suite.query_interest().unwrap();
+    assert_eq!(charged.borrow_index, interest.borrow_index);
+    assert_eq!(charged.supply_index, interest.supply_index);
 }
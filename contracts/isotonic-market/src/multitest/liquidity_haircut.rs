@@ -0,0 +1,138 @@
+use cosmwasm_std::{coin, Decimal, Uint128};
+use utils::credit_line::CreditLineValues;
+
+use super::suite::{SuiteBuilder, COMMON};
+use crate::msg::{DepthStep, LiquidityHaircut};
+use crate::ContractError;
+
+#[test]
+fn small_collateral_valued_at_spot() {
+    let lender = "lender";
+    let market_token = "atom";
+    let mut suite = SuiteBuilder::new()
+        .with_funds(lender, &[coin(1000, market_token)])
+        .with_collateral_ratio(Decimal::percent(70))
+        .with_market_token(market_token)
+        .with_pool(1, (coin(2000, COMMON), coin(1000, market_token)))
+        .build();
+
+    suite
+        .sudo_adjust_liquidity_haircut(LiquidityHaircut::EstimateSwap {
+            threshold: Uint128::new(1000),
+        })
+        .unwrap();
+
+    suite.deposit(lender, &[coin(1000, market_token)]).unwrap();
+
+    let credit_line = suite.query_credit_line(lender).unwrap();
+    assert_eq!(
+        credit_line,
+        CreditLineValues {
+            collateral: Uint128::new(2000),
+            credit_line: Uint128::new(1400),
+            debt: Uint128::zero(),
+        }
+        .make_response(suite.common_token())
+    );
+}
+
+#[test]
+fn large_collateral_valued_at_swap_estimate() {
+    let lender = "lender";
+    let market_token = "atom";
+    let mut suite = SuiteBuilder::new()
+        .with_funds(lender, &[coin(1000, market_token)])
+        .with_collateral_ratio(Decimal::percent(70))
+        .with_market_token(market_token)
+        .with_pool(1, (coin(2000, COMMON), coin(1000, market_token)))
+        .build();
+
+    suite
+        .sudo_adjust_liquidity_haircut(LiquidityHaircut::EstimateSwap {
+            threshold: Uint128::new(500),
+        })
+        .unwrap();
+
+    suite.deposit(lender, &[coin(1000, market_token)]).unwrap();
+
+    // Selling 1000 atom (997 after 0.3% fee) into the 1000 atom / 2000 COMMON pool
+    // leaves 2000 * 1000 / 1997 = 1001 COMMON in it, so only 999 COMMON could be realized
+    let credit_line = suite.query_credit_line(lender).unwrap();
+    assert_eq!(
+        credit_line,
+        CreditLineValues {
+            collateral: Uint128::new(999),
+            credit_line: Uint128::new(699),
+            debt: Uint128::zero(),
+        }
+        .make_response(suite.common_token())
+    );
+
+    // Removing the haircut goes back to spot valuation
+    suite.sudo_adjust_liquidity_haircut(None).unwrap();
+    let credit_line = suite.query_credit_line(lender).unwrap();
+    assert_eq!(credit_line.collateral.amount, Uint128::new(2000));
+}
+
+#[test]
+fn collateral_valued_with_depth_curve() {
+    let lender = "lender";
+    let market_token = "atom";
+    let mut suite = SuiteBuilder::new()
+        .with_funds(lender, &[coin(1000, market_token)])
+        .with_collateral_ratio(Decimal::percent(70))
+        .with_market_token(market_token)
+        .with_pool(1, (coin(200, COMMON), coin(100, market_token)))
+        .build();
+
+    suite
+        .sudo_adjust_liquidity_haircut(LiquidityHaircut::DepthCurve {
+            steps: vec![
+                DepthStep {
+                    amount: Uint128::new(500),
+                    discount: Decimal::percent(10),
+                },
+                DepthStep {
+                    amount: Uint128::new(800),
+                    discount: Decimal::percent(50),
+                },
+            ],
+        })
+        .unwrap();
+
+    suite.deposit(lender, &[coin(1000, market_token)]).unwrap();
+
+    // (500 + 300 * 0.9 + 200 * 0.5) * 2.0 price
+    let credit_line = suite.query_credit_line(lender).unwrap();
+    assert_eq!(
+        credit_line,
+        CreditLineValues {
+            collateral: Uint128::new(1740),
+            credit_line: Uint128::new(1218),
+            debt: Uint128::zero(),
+        }
+        .make_response(suite.common_token())
+    );
+}
+
+#[test]
+fn invalid_depth_curve_rejected() {
+    let mut suite = SuiteBuilder::new().build();
+
+    let err = suite
+        .sudo_adjust_liquidity_haircut(LiquidityHaircut::DepthCurve {
+            steps: vec![
+                DepthStep {
+                    amount: Uint128::new(800),
+                    discount: Decimal::percent(10),
+                },
+                DepthStep {
+                    amount: Uint128::new(500),
+                    discount: Decimal::percent(50),
+                },
+            ],
+        })
+        .unwrap_err();
+    assert_eq!(ContractError::InvalidDepthCurve {}, err.downcast().unwrap());
+    assert_eq!(suite.query_config().unwrap().liquidity_haircut, None);
+}
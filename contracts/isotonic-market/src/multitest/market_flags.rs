@@ -0,0 +1,95 @@
+use cosmwasm_std::{coin, Decimal, Uint128};
+use utils::credit_line::CreditLineValues;
+
+use super::suite::{SuiteBuilder, COMMON};
+use crate::error::ContractError;
+
+#[test]
+fn borrowing_disabled() {
+    let borrower = "borrower";
+    let market_token = "ATOM";
+    let mut suite = SuiteBuilder::new()
+        .with_contract_funds(coin(150, market_token))
+        .with_market_token(market_token)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+
+    suite.set_high_credit_line(borrower).unwrap();
+    suite.sudo_adjust_market_flags(false, None).unwrap();
+    assert!(!suite.query_config().unwrap().borrowing_enabled);
+    assert!(suite.query_config().unwrap().collateral_enabled);
+
+    let err = suite.borrow(borrower, 100).unwrap_err();
+    assert_eq!(ContractError::BorrowingDisabled {}, err.downcast().unwrap());
+    let err = suite.borrow_stable(borrower, 100, None).unwrap_err();
+    assert_eq!(ContractError::BorrowingDisabled {}, err.downcast().unwrap());
+    assert_eq!(
+        suite.query_borrowable(borrower).unwrap(),
+        coin(0, market_token)
+    );
+
+    suite.sudo_adjust_market_flags(true, None).unwrap();
+    suite.borrow(borrower, 100).unwrap();
+    assert_eq!(suite.query_btoken_balance(borrower).unwrap().u128(), 100);
+}
+
+#[test]
+fn collateral_disabled() {
+    let lender = "lender";
+    let market_token = "ATOM";
+    let mut suite = SuiteBuilder::new()
+        .with_funds(lender, &[coin(1000, market_token)])
+        .with_collateral_ratio(Decimal::percent(70))
+        .with_market_token(market_token)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+
+    suite.deposit(lender, &[coin(1000, market_token)]).unwrap();
+    suite.sudo_adjust_market_flags(None, false).unwrap();
+
+    // Deposits are still reported, but they don't give any credit
+    let credit_line = suite.query_credit_line(lender).unwrap();
+    assert_eq!(
+        credit_line,
+        CreditLineValues {
+            collateral: Uint128::new(1000),
+            credit_line: Uint128::zero(),
+            debt: Uint128::zero(),
+        }
+        .make_response(suite.common_token())
+    );
+}
+
+#[test]
+fn non_collateral_deposits_withdrawable_with_debt_elsewhere() {
+    let lender = "lender";
+    let market_token = "ATOM";
+    let mut suite = SuiteBuilder::new()
+        .with_funds(lender, &[coin(1000, market_token)])
+        .with_collateral_ratio(Decimal::zero())
+        .with_market_token(market_token)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+
+    suite.deposit(lender, &[coin(1000, market_token)]).unwrap();
+    suite.sudo_adjust_market_flags(None, false).unwrap();
+
+    // The lender has debt on some other market, which is fully covered elsewhere
+    suite
+        .set_credit_line(
+            lender,
+            CreditLineValues {
+                collateral: Uint128::new(2000),
+                credit_line: Uint128::new(1000),
+                debt: Uint128::new(1000),
+            },
+        )
+        .unwrap();
+
+    // A zero collateral ratio doesn't get in the way, as these deposits back nothing
+    assert_eq!(
+        suite.query_withdrawable(lender).unwrap(),
+        coin(1000, market_token)
+    );
+    suite.withdraw(lender, 1000).unwrap();
+}
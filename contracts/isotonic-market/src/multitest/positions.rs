@@ -0,0 +1,140 @@
+use super::suite::{SuiteBuilder, COMMON};
+
+use cosmwasm_std::{coin, Decimal, Uint128};
+
+use crate::msg::{PositionResponse, StableBorrowing, TermLending};
+use crate::state::SECONDS_IN_YEAR;
+
+const YEAR: u64 = (SECONDS_IN_YEAR) as u64;
+
+fn position(account: &str, ltokens: u128, btokens: u128) -> PositionResponse {
+    PositionResponse {
+        account: account.to_owned(),
+        ltokens: Uint128::new(ltokens),
+        btokens: Uint128::new(btokens),
+        stable_debt: Uint128::zero(),
+        term_debt: Uint128::zero(),
+    }
+}
+
+#[test]
+fn list_positions_paginated() {
+    let market_token = "atom";
+    let mut suite = SuiteBuilder::new()
+        .with_funds("alice", &[coin(1000, market_token)])
+        .with_funds("carol", &[coin(100, market_token)])
+        .with_market_token(market_token)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+
+    suite.deposit("carol", &[coin(100, market_token)]).unwrap();
+    suite.deposit("alice", &[coin(1000, market_token)]).unwrap();
+    suite.set_high_credit_line("bob").unwrap();
+    suite.borrow("bob", 500).unwrap();
+
+    let page = suite.query_positions(None, 2).unwrap();
+    assert_eq!(
+        page,
+        vec![position("alice", 1000, 0), position("bob", 0, 500)]
+    );
+    let page = suite.query_positions("bob".to_owned(), 2).unwrap();
+    assert_eq!(page, vec![position("carol", 100, 0)]);
+}
+
+#[test]
+fn list_positions_with_pending_interest() {
+    let market_token = "atom";
+    let mut suite = SuiteBuilder::new()
+        .with_funds("alice", &[coin(1000, market_token)])
+        .with_market_token(market_token)
+        .with_charge_period(YEAR)
+        .with_interest(10, 0)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+
+    suite.deposit("alice", &[coin(1000, market_token)]).unwrap();
+    suite.set_high_credit_line("bob").unwrap();
+    suite.borrow("bob", 500).unwrap();
+
+    suite.advance_seconds(YEAR);
+
+    // 10% interest on 500 borrowed is paid to the only lender
+    assert_eq!(
+        suite.query_positions(None, None).unwrap(),
+        vec![position("alice", 1050, 0), position("bob", 0, 550)]
+    );
+}
+
+#[test]
+fn list_positions_with_fixed_rate_debt() {
+    let market_token = "atom";
+    let mut suite = SuiteBuilder::new()
+        .with_funds("alice", &[coin(1000, market_token)])
+        .with_funds("bob", &[coin(20, market_token)])
+        .with_market_token(market_token)
+        .with_charge_period(YEAR)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+
+    suite
+        .sudo_adjust_stable_borrowing(Some(StableBorrowing {
+            premium: Decimal::percent(2),
+            rebalance_up_utilisation: Decimal::percent(90),
+            rebalance_down_delta: Decimal::percent(5),
+        }))
+        .unwrap();
+    suite
+        .sudo_adjust_term_lending(Some(TermLending {
+            premium: Decimal::percent(2),
+            penalty_rate: Decimal::percent(10),
+            max_duration: 2 * YEAR,
+            early_repayment_fee: Decimal::percent(1),
+        }))
+        .unwrap();
+    suite.deposit("alice", &[coin(1000, market_token)]).unwrap();
+    suite.set_high_credit_line("bob").unwrap();
+    suite.set_high_credit_line("carol").unwrap();
+    // 3% base + 20% slope * 20% utilisation + 2% premium
+    suite.borrow_stable("carol", 200, None).unwrap();
+    // 3% base + 20% slope * 50% utilisation + 2% premium
+    suite.borrow_term("bob", 300, 2 * YEAR, None).unwrap();
+    suite.borrow_term("bob", 100, 2 * YEAR, None).unwrap();
+
+    // Fixed-rate debtors hold no tokens, but are listed with their debt
+    let page = suite.query_positions(None, 2).unwrap();
+    assert_eq!(
+        page,
+        vec![
+            position("alice", 1000, 0),
+            PositionResponse {
+                term_debt: Uint128::new(400),
+                ..position("bob", 0, 0)
+            },
+        ]
+    );
+    let page = suite.query_positions("bob".to_owned(), 2).unwrap();
+    assert_eq!(
+        page,
+        vec![PositionResponse {
+            stable_debt: Uint128::new(200),
+            ..position("carol", 0, 0)
+        }]
+    );
+
+    // Accounts are listed until their last term loan is repaid
+    suite.repay_term("bob", 2, coin(110, market_token)).unwrap();
+    assert_eq!(suite.query_positions(None, 2).unwrap()[1].account, "bob");
+    suite.repay_term("bob", 1, coin(310, market_token)).unwrap();
+
+    // 9% stable rate on 200
+    suite.advance_seconds(YEAR);
+    let page = suite.query_positions(None, 2).unwrap();
+    assert_eq!(page[0].account, "alice");
+    assert_eq!(
+        page[1],
+        PositionResponse {
+            stable_debt: Uint128::new(218),
+            ..position("carol", 0, 0)
+        }
+    );
+}
@@ -0,0 +1,183 @@
+use super::suite::{SuiteBuilder, COMMON};
+
+use cosmwasm_std::{coin, Decimal, Uint128};
+use utils::interest::{Interest, ValidatedInterest};
+
+use crate::error::ContractError;
+use crate::msg::RampParameter;
+
+#[test]
+fn collateral_ratio_ramps_down() {
+    let lender = "lender";
+    let market_token = "atom";
+    let mut suite = SuiteBuilder::new()
+        .with_funds(lender, &[coin(1000, market_token)])
+        .with_collateral_ratio(Decimal::percent(80))
+        .with_market_token(market_token)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+
+    suite.deposit(lender, &[coin(1000, market_token)]).unwrap();
+    suite.sudo_ramp_collateral_ratio(40, 1000).unwrap();
+
+    // Nothing changes at once
+    let credit_line = suite.query_credit_line(lender).unwrap();
+    assert_eq!(credit_line.credit_line.amount, Uint128::new(800));
+
+    suite.advance_seconds(500);
+    let credit_line = suite.query_credit_line(lender).unwrap();
+    assert_eq!(credit_line.credit_line.amount, Uint128::new(600));
+    assert_eq!(
+        suite.query_config().unwrap().collateral_ratio,
+        Decimal::percent(60)
+    );
+
+    let ramp = suite
+        .query_pending_ramps()
+        .unwrap()
+        .collateral_ratio
+        .unwrap();
+    assert_eq!(ramp.from, Decimal::percent(80));
+    assert_eq!(ramp.to, Decimal::percent(40));
+
+    suite.advance_seconds(600);
+    let credit_line = suite.query_credit_line(lender).unwrap();
+    assert_eq!(credit_line.credit_line.amount, Uint128::new(400));
+    assert_eq!(suite.query_pending_ramps().unwrap().collateral_ratio, None);
+
+    // The final value ends up in the config once the market is touched
+    suite.accrue_interest(lender).unwrap();
+    suite.sudo_ramp_collateral_ratio(50, 1000).unwrap();
+    let ramp = suite
+        .query_pending_ramps()
+        .unwrap()
+        .collateral_ratio
+        .unwrap();
+    assert_eq!(ramp.from, Decimal::percent(40));
+}
+
+#[test]
+fn cancelled_collateral_ratio_ramp_stays_at_current_value() {
+    let lender = "lender";
+    let market_token = "atom";
+    let mut suite = SuiteBuilder::new()
+        .with_funds(lender, &[coin(1000, market_token)])
+        .with_collateral_ratio(Decimal::percent(80))
+        .with_market_token(market_token)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+
+    suite.deposit(lender, &[coin(1000, market_token)]).unwrap();
+    suite.sudo_ramp_collateral_ratio(40, 1000).unwrap();
+
+    let err = suite.sudo_ramp_collateral_ratio(20, 1000).unwrap_err();
+    assert_eq!(ContractError::RampInProgress {}, err.downcast().unwrap());
+
+    suite.advance_seconds(250);
+    suite
+        .sudo_cancel_ramp(RampParameter::CollateralRatio)
+        .unwrap();
+    suite.advance_seconds(750);
+
+    let credit_line = suite.query_credit_line(lender).unwrap();
+    assert_eq!(credit_line.credit_line.amount, Uint128::new(700));
+    assert_eq!(suite.query_pending_ramps().unwrap().collateral_ratio, None);
+
+    // Instant adjustment replaces a ramp as well
+    suite.sudo_ramp_collateral_ratio(40, 1000).unwrap();
+    suite.sudo_adjust_collateral_ratio(50).unwrap();
+    suite.advance_seconds(500);
+    let credit_line = suite.query_credit_line(lender).unwrap();
+    assert_eq!(credit_line.credit_line.amount, Uint128::new(500));
+}
+
+#[test]
+fn interest_rates_ramp() {
+    let lender = "lender";
+    let borrower = "borrower";
+    let market_token = "atom";
+    let mut suite = SuiteBuilder::new()
+        .with_funds(lender, &[coin(1000, market_token)])
+        .with_interest(10, 0)
+        .with_market_token(market_token)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+
+    suite.set_high_credit_line(borrower).unwrap();
+    suite.deposit(lender, &[coin(1000, market_token)]).unwrap();
+    suite.borrow(borrower, 500).unwrap();
+
+    let target = Interest::Linear {
+        base: Decimal::percent(30),
+        slope: Decimal::zero(),
+    };
+    suite
+        .sudo_ramp_interest_rates(target.clone(), 1000)
+        .unwrap();
+
+    suite.advance_seconds(500);
+    assert_eq!(
+        suite.query_interest().unwrap().interest,
+        Decimal::percent(20)
+    );
+    assert_eq!(
+        suite
+            .query_pending_ramps()
+            .unwrap()
+            .interest_rates
+            .unwrap()
+            .to,
+        ValidatedInterest::unchecked(target.clone())
+    );
+
+    suite.advance_seconds(500);
+    assert_eq!(
+        suite.query_interest().unwrap().interest,
+        Decimal::percent(30)
+    );
+    assert_eq!(
+        suite.query_config().unwrap().rates,
+        ValidatedInterest::unchecked(target)
+    );
+
+    // Cancelling goes back to the original curve
+    suite
+        .sudo_ramp_interest_rates(
+            Interest::Linear {
+                base: Decimal::percent(50),
+                slope: Decimal::zero(),
+            },
+            1000,
+        )
+        .unwrap();
+    suite.advance_seconds(500);
+    assert_eq!(
+        suite.query_interest().unwrap().interest,
+        Decimal::percent(40)
+    );
+    suite
+        .sudo_cancel_ramp(RampParameter::InterestRates)
+        .unwrap();
+    assert_eq!(
+        suite.query_interest().unwrap().interest,
+        Decimal::percent(30)
+    );
+}
+
+#[test]
+fn ramp_must_end_in_future() {
+    let mut suite = SuiteBuilder::new().build();
+
+    let err = suite.sudo_ramp_collateral_ratio(40, 0).unwrap_err();
+    assert_eq!(ContractError::InvalidRampEnd {}, err.downcast().unwrap());
+
+    let err = suite
+        .sudo_ramp_interest_rates(
+            Interest::Fixed {
+                rate: Decimal::percent(5),
+            },
+            0,
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::InvalidRampEnd {}, err.downcast().unwrap());
+}
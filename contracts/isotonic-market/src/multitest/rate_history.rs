@@ -0,0 +1,155 @@
+use super::suite::{SuiteBuilder, COMMON};
+
+use cosmwasm_std::{coin, Coin, Decimal};
+
+use crate::msg::{RateSnapshot, StableBorrowing};
+use crate::state::{RATE_HISTORY_MIN_INTERVAL, SECONDS_IN_YEAR};
+
+const YEAR: u64 = SECONDS_IN_YEAR as u64;
+
+#[test]
+fn snapshot_recorded_on_interest_charge() {
+    let lender = "lender";
+    let borrower = "borrower";
+    let market_token = "atom";
+    let mut suite = SuiteBuilder::new()
+        .with_charge_period(YEAR)
+        .with_funds(lender, &[coin(2000, market_token)])
+        .with_interest(4, 20)
+        .with_reserve_factor(10)
+        .with_market_token(market_token)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+
+    suite.set_high_credit_line(borrower).unwrap();
+    suite
+        .deposit(lender, &[Coin::new(2000, market_token)])
+        .unwrap();
+    suite.borrow(borrower, 1600).unwrap();
+
+    // Nothing borrowed until now, so there was no interest to charge
+    assert_eq!(suite.query_rate_history(None, None).unwrap(), vec![]);
+
+    suite.advance_seconds(YEAR);
+    suite.accrue_interest("keeper").unwrap();
+
+    // 20% interest on 1600 borrowed, 10% of it goes to reserves and L-Tokens grow by it over
+    // the 1968 L-Tokens left after the reserves
+    let snapshots = suite.query_rate_history(None, None).unwrap();
+    assert_eq!(
+        snapshots,
+        vec![RateSnapshot {
+            id: 1,
+            timestamp: suite.app().block_info().time,
+            utilisation: Decimal::percent(80),
+            borrow_rate: Decimal::percent(20),
+            supply_rate: Decimal::from_ratio(320u128, 1968u128),
+            borrow_index: Decimal::percent(120),
+            supply_index: Decimal::one() + Decimal::from_ratio(320u128, 1968u128),
+        }]
+    );
+}
+
+#[test]
+fn history_is_paginated() {
+    let lender = "lender";
+    let borrower = "borrower";
+    let market_token = "atom";
+    let mut suite = SuiteBuilder::new()
+        .with_charge_period(YEAR)
+        .with_funds(lender, &[coin(2000, market_token)])
+        .with_interest(4, 20)
+        .with_market_token(market_token)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+
+    suite.set_high_credit_line(borrower).unwrap();
+    suite
+        .deposit(lender, &[Coin::new(2000, market_token)])
+        .unwrap();
+    suite.borrow(borrower, 1000).unwrap();
+
+    for _ in 0..3 {
+        suite.advance_seconds(YEAR);
+        suite.accrue_interest("keeper").unwrap();
+    }
+
+    let first = suite.query_rate_history(None, 2).unwrap();
+    assert_eq!(first.iter().map(|s| s.id).collect::<Vec<_>>(), vec![1, 2]);
+    let rest = suite.query_rate_history(2, None).unwrap();
+    assert_eq!(rest.iter().map(|s| s.id).collect::<Vec<_>>(), vec![3]);
+
+    // Indexes only grow while there is debt
+    assert!(first[0].borrow_index < first[1].borrow_index);
+    assert!(first[1].borrow_index < rest[0].borrow_index);
+    assert!(first[0].supply_index < first[1].supply_index);
+    assert!(first[1].supply_index < rest[0].supply_index);
+}
+
+#[test]
+fn snapshots_are_rate_limited() {
+    let lender = "lender";
+    let borrower = "borrower";
+    let market_token = "atom";
+    let mut suite = SuiteBuilder::new()
+        .with_funds(lender, &[coin(2000, market_token)])
+        .with_interest(4, 20)
+        .with_market_token(market_token)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+
+    suite.set_high_credit_line(borrower).unwrap();
+    suite
+        .deposit(lender, &[Coin::new(2000, market_token)])
+        .unwrap();
+    suite.borrow(borrower, 1000).unwrap();
+
+    // Interest is charged on every call, but only one snapshot is taken per hour
+    for _ in 0..12 {
+        suite.advance_seconds(RATE_HISTORY_MIN_INTERVAL / 4);
+        suite.accrue_interest("keeper").unwrap();
+    }
+    let snapshots = suite.query_rate_history(None, None).unwrap();
+    assert_eq!(snapshots.len(), 3);
+    assert_eq!(
+        snapshots[1].timestamp,
+        snapshots[0]
+            .timestamp
+            .plus_seconds(RATE_HISTORY_MIN_INTERVAL)
+    );
+}
+
+#[test]
+fn supply_rate_includes_stable_interest() {
+    let lender = "lender";
+    let borrower = "borrower";
+    let market_token = "atom";
+    let mut suite = SuiteBuilder::new()
+        .with_charge_period(YEAR)
+        .with_funds(lender, &[coin(1000, market_token)])
+        .with_market_token(market_token)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+
+    suite
+        .sudo_adjust_stable_borrowing(Some(StableBorrowing {
+            premium: Decimal::percent(2),
+            rebalance_up_utilisation: Decimal::percent(90),
+            rebalance_down_delta: Decimal::percent(5),
+        }))
+        .unwrap();
+    suite.set_high_credit_line(borrower).unwrap();
+    suite
+        .deposit(lender, &[Coin::new(1000, market_token)])
+        .unwrap();
+    // 3% base + 20% slope * 50% utilisation + 2% premium
+    suite.borrow_stable(borrower, 500, None).unwrap();
+
+    suite.advance_seconds(YEAR);
+    suite.accrue_interest("keeper").unwrap();
+
+    // 15% on 500 borrowed at a stable rate goes to 1000 L-Tokens
+    let snapshots = suite.query_rate_history(None, None).unwrap();
+    assert_eq!(snapshots[0].borrow_rate, Decimal::percent(13));
+    assert_eq!(snapshots[0].supply_rate, Decimal::permille(75));
+}
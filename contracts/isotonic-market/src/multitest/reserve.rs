@@ -3,6 +3,7 @@ use super::suite::{SuiteBuilder, COMMON};
 use cosmwasm_std::{coin, Coin, Decimal, Uint128};
 use isotonic_token::DisplayAmount;
 
+use crate::error::ContractError;
 use crate::state::SECONDS_IN_YEAR;
 use utils::assert_approx_eq;
 
@@ -229,3 +230,131 @@ fn query_reserve_with_uncharged_interest() {
 
     assert_eq!(15, suite.query_reserve().unwrap().u128());
 }
+
+#[test]
+fn reserve_split_between_buckets() {
+    let lender = "lender";
+    let borrower = "borrower";
+    let market_token = "atom";
+    let mut suite = SuiteBuilder::new()
+        .with_charge_period(SECONDS_IN_YEAR as u64)
+        .with_funds(lender, &[coin(2000, market_token)])
+        .with_interest(4, 20)
+        .with_reserve_factor(10)
+        .with_market_token(market_token)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+
+    let err = suite
+        .sudo_adjust_reserve_split(&[("treasury", "treasury", 3), ("treasury", "insurance", 1)])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidReserveSplit {},
+        err.downcast().unwrap()
+    );
+    suite
+        .sudo_adjust_reserve_split(&[("treasury", "treasury", 3), ("insurance", "insurance", 1)])
+        .unwrap();
+
+    suite.set_high_credit_line(borrower).unwrap();
+    suite
+        .deposit(lender, &[Coin::new(2000, market_token)])
+        .unwrap();
+    suite.borrow(borrower, 1600).unwrap();
+    suite.advance_seconds(SECONDS_IN_YEAR as u64);
+
+    // 20% interest on 1600 borrowed, 10% of it goes to reserves
+    let buckets = suite.query_reserve_buckets().unwrap();
+    assert_eq!(buckets.buckets[0].name, "treasury");
+    assert_eq!(buckets.buckets[0].amount, Uint128::new(24));
+    assert_eq!(buckets.buckets[1].name, "insurance");
+    assert_eq!(buckets.buckets[1].amount, Uint128::new(8));
+    assert_eq!(buckets.unallocated, Uint128::zero());
+
+    suite.withdraw_reserve("anyone", "treasury").unwrap();
+    assert_eq!(suite.query_asset_balance("treasury").unwrap(), 24);
+    assert_eq!(suite.query_reserve().unwrap(), Uint128::new(8));
+
+    let err = suite.withdraw_reserve("anyone", "treasury").unwrap_err();
+    assert_eq!(
+        ContractError::NothingToWithdraw("treasury".to_owned()),
+        err.downcast().unwrap()
+    );
+    let err = suite.withdraw_reserve("anyone", "stakers").unwrap_err();
+    assert_eq!(
+        ContractError::UnknownReserveBucket("stakers".to_owned()),
+        err.downcast().unwrap()
+    );
+
+    // Reserves accrued until the split changes are allocated with the previous one, the
+    // share of the removed bucket is split with the new one
+    suite.advance_seconds(SECONDS_IN_YEAR as u64);
+    let growth = suite.query_reserve().unwrap() - Uint128::new(8);
+    suite
+        .sudo_adjust_reserve_split(&[("insurance", "insurance", 1), ("stakers", "stakers", 1)])
+        .unwrap();
+    let buckets = suite.query_reserve_buckets().unwrap();
+    let removed = growth - growth.multiply_ratio(1u128, 4u128);
+    assert_eq!(buckets.buckets[1].name, "stakers");
+    assert_eq!(
+        buckets.buckets[1].amount,
+        removed.multiply_ratio(1u128, 2u128)
+    );
+    assert_eq!(
+        buckets.buckets[0].amount,
+        Uint128::new(8) + (growth - removed) + removed.multiply_ratio(1u128, 2u128)
+    );
+    let err = suite.withdraw_reserve("anyone", "treasury").unwrap_err();
+    assert_eq!(
+        ContractError::UnknownReserveBucket("treasury".to_owned()),
+        err.downcast().unwrap()
+    );
+
+    suite.withdraw_reserve("anyone", "insurance").unwrap();
+    assert_eq!(
+        suite.query_asset_balance("insurance").unwrap(),
+        buckets.buckets[0].amount.u128()
+    );
+}
+
+#[test]
+fn distributed_funds_split_with_reserves() {
+    let lender = "lender";
+    let market_token = "atom";
+    let mut suite = SuiteBuilder::new()
+        .with_funds(lender, &[coin(800, market_token)])
+        .with_ca_funds(&[coin(300, market_token)])
+        .with_reserve_factor(10)
+        .with_market_token(market_token)
+        .build();
+
+    suite
+        .sudo_adjust_reserve_split(&[("treasury", "treasury", 1)])
+        .unwrap();
+    suite.deposit(lender, &[coin(800, market_token)]).unwrap();
+
+    // The reserve factor only applies to interest, by default lenders get everything
+    suite
+        .distribute_as_ltokens(&suite.credit_agency(), coin(200, market_token))
+        .unwrap();
+    assert_eq!(suite.query_ltoken_balance(lender).unwrap().u128(), 1000);
+    assert_eq!(suite.query_reserve().unwrap(), Uint128::zero());
+
+    let err = suite
+        .sudo_adjust_distribution_reserve_share(101)
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidDistributionReserveShare {},
+        err.downcast().unwrap()
+    );
+    suite.sudo_adjust_distribution_reserve_share(20).unwrap();
+    suite
+        .distribute_as_ltokens(&suite.credit_agency(), coin(100, market_token))
+        .unwrap();
+    assert_eq!(suite.query_ltoken_balance(lender).unwrap().u128(), 1080);
+    assert_eq!(suite.query_reserve().unwrap(), Uint128::new(20));
+
+    suite.withdraw_reserve("anyone", "treasury").unwrap();
+    assert_eq!(suite.query_asset_balance("treasury").unwrap(), 20);
+    assert_eq!(suite.query_contract_asset_balance().unwrap(), 1080);
+}
@@ -0,0 +1,95 @@
+use cosmwasm_std::{coin, Decimal, Uint128};
+use utils::credit_line::CreditLineValues;
+
+use super::suite::{SuiteBuilder, COMMON};
+
+#[test]
+fn simulate_borrow() {
+    let borrower = "borrower";
+    let market_token = "atom";
+    let mut suite = SuiteBuilder::new()
+        .with_funds(borrower, &[coin(1000, market_token)])
+        .with_market_token(market_token)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+
+    suite
+        .deposit(borrower, &[coin(1000, market_token)])
+        .unwrap();
+    suite
+        .set_credit_line(
+            borrower,
+            CreditLineValues {
+                collateral: Uint128::new(1000),
+                // 1000 * 0.5 collateral ratio
+                credit_line: Uint128::new(500),
+                debt: Uint128::zero(),
+            },
+        )
+        .unwrap();
+
+    let simulation = suite.query_simulate_borrow(borrower, 200).unwrap();
+    assert!(simulation.allowed);
+    assert_eq!(
+        simulation.credit_line,
+        CreditLineValues::new(1000u128, 500u128, 200u128).make_response(suite.common_token())
+    );
+    assert_eq!(simulation.health, Some(Decimal::percent(250)));
+
+    // Borrowing over the credit line would fail
+    let simulation = suite.query_simulate_borrow(borrower, 600).unwrap();
+    assert!(!simulation.allowed);
+    assert_eq!(
+        simulation.health,
+        Some(Decimal::from_ratio(500u128, 600u128))
+    );
+}
+
+#[test]
+fn simulate_withdraw_and_repay() {
+    let borrower = "borrower";
+    let market_token = "atom";
+    let mut suite = SuiteBuilder::new()
+        .with_funds(borrower, &[coin(1000, market_token)])
+        .with_market_token(market_token)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+
+    suite
+        .deposit(borrower, &[coin(1000, market_token)])
+        .unwrap();
+    suite.set_high_credit_line(borrower).unwrap();
+    suite.borrow(borrower, 200).unwrap();
+    suite
+        .set_credit_line(
+            borrower,
+            CreditLineValues {
+                collateral: Uint128::new(1000),
+                credit_line: Uint128::new(500),
+                debt: Uint128::new(200),
+            },
+        )
+        .unwrap();
+
+    // The remaining 300 of credit line is backed by 600 tokens at 0.5 collateral ratio
+    let simulation = suite.query_simulate_withdraw(borrower, 600).unwrap();
+    assert!(simulation.allowed);
+    assert_eq!(
+        simulation.credit_line,
+        CreditLineValues::new(400u128, 200u128, 200u128).make_response(suite.common_token())
+    );
+    assert_eq!(simulation.health, Some(Decimal::one()));
+
+    let simulation = suite.query_simulate_withdraw(borrower, 700).unwrap();
+    assert!(!simulation.allowed);
+    assert_eq!(simulation.health, Some(Decimal::percent(75)));
+
+    // Repaying more than the debt clears it
+    let simulation = suite.query_simulate_repay(borrower, 500).unwrap();
+    assert!(simulation.allowed);
+    assert_eq!(
+        simulation.credit_line,
+        CreditLineValues::new(1000u128, 500u128, 0u128).make_response(suite.common_token())
+    );
+    assert_eq!(simulation.health, None);
+}
@@ -0,0 +1,287 @@
+use cosmwasm_std::{coin, Decimal, Uint128};
+
+use super::suite::{SuiteBuilder, COMMON};
+use crate::error::ContractError;
+use crate::msg::StableBorrowing;
+
+const DAY: u64 = 24 * 3600;
+const YEAR: u64 = 365 * DAY;
+
+fn stable_borrowing() -> StableBorrowing {
+    StableBorrowing {
+        premium: Decimal::percent(2),
+        rebalance_up_utilisation: Decimal::percent(90),
+        rebalance_down_delta: Decimal::percent(5),
+    }
+}
+
+#[test]
+fn stable_borrowing_disabled_by_default() {
+    let borrower = "borrower";
+    let market_token = "atom";
+    let mut suite = SuiteBuilder::new()
+        .with_contract_funds(coin(1000, market_token))
+        .with_market_token(market_token)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+
+    suite.set_high_credit_line(borrower).unwrap();
+
+    let err = suite.borrow_stable(borrower, 100, None).unwrap_err();
+    assert_eq!(
+        ContractError::StableBorrowingDisabled {},
+        err.downcast().unwrap()
+    );
+
+    let err = suite
+        .sudo_adjust_stable_borrowing(Some(StableBorrowing {
+            rebalance_up_utilisation: Decimal::percent(101),
+            ..stable_borrowing()
+        }))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidStableBorrowing {},
+        err.downcast().unwrap()
+    );
+}
+
+#[test]
+fn borrow_stable_locks_rate() {
+    let lender = "lender";
+    let borrower = "borrower";
+    let market_token = "atom";
+    let mut suite = SuiteBuilder::new()
+        .with_funds(lender, &[coin(1000, market_token)])
+        .with_market_token(market_token)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+
+    suite
+        .sudo_adjust_stable_borrowing(Some(stable_borrowing()))
+        .unwrap();
+    suite.deposit(lender, &[coin(1000, market_token)]).unwrap();
+    suite.set_high_credit_line(borrower).unwrap();
+
+    // 3% base + 20% slope * 50% utilisation after borrowing + 2% premium
+    suite.borrow_stable(borrower, 500, None).unwrap();
+    assert_eq!(suite.query_asset_balance(borrower).unwrap(), 500);
+    assert_eq!(suite.query_btoken_balance(borrower).unwrap().u128(), 0);
+
+    let debt = suite.query_stable_debt(borrower).unwrap();
+    assert_eq!(debt.amount, Uint128::new(500));
+    assert_eq!(debt.rate, Decimal::percent(15));
+
+    // Stable debt counts towards utilisation and the credit line
+    let interest = suite.query_interest().unwrap();
+    assert_eq!(interest.utilisation, Decimal::percent(50));
+    assert_eq!(interest.interest, Decimal::percent(13));
+    assert_eq!(suite.query_stable_rate().unwrap(), Decimal::percent(15));
+    let credit_line = suite.query_credit_line(borrower).unwrap();
+    assert_eq!(credit_line.debt.amount, Uint128::new(500));
+
+    // Changing the curve does not affect the locked rate
+    suite
+        .sudo_adjust_interest_rates(utils::interest::Interest::Linear {
+            base: Decimal::percent(10),
+            slope: Decimal::percent(20),
+        })
+        .unwrap();
+    assert_eq!(
+        suite.query_stable_debt(borrower).unwrap().rate,
+        Decimal::percent(15)
+    );
+}
+
+#[test]
+fn stable_debt_accrues_at_locked_rate() {
+    let lender = "lender";
+    let borrower = "borrower";
+    let market_token = "atom";
+    let mut suite = SuiteBuilder::new()
+        .with_funds(lender, &[coin(1000, market_token)])
+        .with_funds(borrower, &[coin(200, market_token)])
+        .with_charge_period(YEAR)
+        .with_market_token(market_token)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+
+    suite
+        .sudo_adjust_stable_borrowing(Some(stable_borrowing()))
+        .unwrap();
+    suite.deposit(lender, &[coin(1000, market_token)]).unwrap();
+    suite.set_high_credit_line(borrower).unwrap();
+    suite.borrow_stable(borrower, 500, None).unwrap();
+
+    suite.advance_seconds(YEAR);
+    // 500 * 15%
+    let debt = suite.query_stable_debt(borrower).unwrap();
+    assert_eq!(debt.amount, Uint128::new(575));
+
+    // Interest paid by stable borrowers goes to lenders
+    suite.accrue_interest(lender).unwrap();
+    assert_eq!(
+        suite.query_ltoken_balance(lender).unwrap(),
+        Uint128::new(1075)
+    );
+
+    // Surplus is returned: 200 + 500 borrowed - 575 repaid
+    suite
+        .repay_stable(borrower, coin(600, market_token))
+        .unwrap();
+    assert_eq!(suite.query_asset_balance(borrower).unwrap(), 125);
+    assert_eq!(
+        suite.query_stable_debt(borrower).unwrap().amount,
+        Uint128::zero()
+    );
+    assert_eq!(suite.query_interest().unwrap().utilisation, Decimal::zero());
+}
+
+#[test]
+fn borrow_stable_respects_max_rate() {
+    let lender = "lender";
+    let borrower = "borrower";
+    let market_token = "atom";
+    let mut suite = SuiteBuilder::new()
+        .with_funds(lender, &[coin(1000, market_token)])
+        .with_market_token(market_token)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+
+    suite
+        .sudo_adjust_stable_borrowing(Some(stable_borrowing()))
+        .unwrap();
+    suite.deposit(lender, &[coin(1000, market_token)]).unwrap();
+    suite.set_high_credit_line(borrower).unwrap();
+
+    let err = suite
+        .borrow_stable(borrower, 500, Decimal::percent(14))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::StableRateTooHigh {
+            rate: Decimal::percent(15),
+            max_rate: Decimal::percent(14),
+        },
+        err.downcast().unwrap()
+    );
+
+    suite
+        .borrow_stable(borrower, 500, Decimal::percent(15))
+        .unwrap();
+}
+
+#[test]
+fn rebalance_stable_rate() {
+    let lender = "lender";
+    let borrower = "borrower";
+    let market_token = "atom";
+    let mut suite = SuiteBuilder::new()
+        .with_funds(lender, &[coin(1000, market_token)])
+        .with_market_token(market_token)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+
+    suite
+        .sudo_adjust_stable_borrowing(Some(stable_borrowing()))
+        .unwrap();
+    suite.deposit(lender, &[coin(1000, market_token)]).unwrap();
+    suite.set_high_credit_line(borrower).unwrap();
+    suite.borrow_stable(borrower, 500, None).unwrap();
+
+    let err = suite.sudo_rebalance_stable_rate(borrower).unwrap_err();
+    assert_eq!(
+        ContractError::RebalanceConditionsNotMet(borrower.to_owned()),
+        err.downcast().unwrap()
+    );
+
+    // Utilisation goes up to 95%, the stable rate is raised:
+    // 3% base + 20% slope * 95% + 2% premium
+    suite.borrow(borrower, 450).unwrap();
+    suite.sudo_rebalance_stable_rate(borrower).unwrap();
+    let debt = suite.query_stable_debt(borrower).unwrap();
+    assert_eq!(debt.rate, Decimal::percent(24));
+
+    // Variable debt is repaid, the stable rate is lowered back:
+    // 3% base + 20% slope * 50% + 2% premium
+    suite.repay(borrower, coin(450, market_token)).unwrap();
+    suite.sudo_rebalance_stable_rate(borrower).unwrap();
+    let debt = suite.query_stable_debt(borrower).unwrap();
+    assert_eq!(debt.rate, Decimal::percent(15));
+}
+
+#[test]
+fn stable_total_cleared_after_full_repayments() {
+    let lender = "lender";
+    let cheap = "cheap";
+    let pricey = "pricey";
+    let market_token = "atom";
+    let mut suite = SuiteBuilder::new()
+        .with_funds(lender, &[coin(1000, market_token)])
+        .with_funds(cheap, &[coin(200, market_token)])
+        .with_funds(pricey, &[coin(200, market_token)])
+        .with_market_token(market_token)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+
+    suite
+        .sudo_adjust_stable_borrowing(Some(stable_borrowing()))
+        .unwrap();
+    suite.deposit(lender, &[coin(1000, market_token)]).unwrap();
+    suite.set_high_credit_line(cheap).unwrap();
+    suite.set_high_credit_line(pricey).unwrap();
+
+    // 3% base + 20% slope * 30% utilisation + 2% premium
+    suite.borrow_stable(cheap, 300, None).unwrap();
+    // 3% base + 20% slope * 60% utilisation + 2% premium
+    suite.borrow_stable(pricey, 300, None).unwrap();
+    assert_eq!(
+        suite.query_stable_debt(cheap).unwrap().rate,
+        Decimal::percent(11)
+    );
+    assert_eq!(
+        suite.query_stable_debt(pricey).unwrap().rate,
+        Decimal::percent(17)
+    );
+
+    // Interest of both debts is rounded down separately, but their sum accrues as a whole
+    suite.advance_seconds(5 * DAY);
+    suite.repay_stable(cheap, coin(500, market_token)).unwrap();
+    suite.repay_stable(pricey, coin(500, market_token)).unwrap();
+
+    assert_eq!(
+        suite.query_stable_debt(cheap).unwrap().amount,
+        Uint128::zero()
+    );
+    assert_eq!(
+        suite.query_stable_debt(pricey).unwrap().amount,
+        Uint128::zero()
+    );
+    assert_eq!(suite.query_interest().unwrap().utilisation, Decimal::zero());
+}
+
+#[test]
+fn borrow_stable_charges_origination_fee() {
+    let lender = "lender";
+    let borrower = "borrower";
+    let market_token = "atom";
+    let mut suite = SuiteBuilder::new()
+        .with_funds(lender, &[coin(1000, market_token)])
+        .with_origination_fee(1)
+        .with_market_token(market_token)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+
+    suite
+        .sudo_adjust_stable_borrowing(Some(stable_borrowing()))
+        .unwrap();
+    suite.deposit(lender, &[coin(1000, market_token)]).unwrap();
+    suite.set_high_credit_line(borrower).unwrap();
+
+    // The whole amount is paid out, the fee is added to the debt and goes to reserves
+    suite.borrow_stable(borrower, 500, None).unwrap();
+    assert_eq!(suite.query_asset_balance(borrower).unwrap(), 500);
+    assert_eq!(
+        suite.query_stable_debt(borrower).unwrap().amount,
+        Uint128::new(505)
+    );
+    assert_eq!(suite.query_reserve().unwrap(), Uint128::new(5));
+}
@@ -4,7 +4,9 @@ use crate::state::SECONDS_IN_YEAR;
 use cosmwasm_std::{coin, Coin, Decimal, Uint128};
 use isotonic_token::DisplayAmount;
 
-use utils::interest::{Interest, ValidatedInterest};
+use utils::interest::{Interest, InterestError, Kink, ValidatedInterest};
+
+use crate::error::ContractError;
 
 #[test]
 fn adjust_collateral_ratio() {
@@ -154,3 +156,45 @@ fn adjust_interest_rates() {
         DisplayAmount::raw(3299u128)
     );
 }
+
+#[test]
+fn adjust_interest_rates_multi_kink() {
+    let mut suite = SuiteBuilder::new().build();
+
+    let err = suite
+        .sudo_adjust_interest_rates(Interest::MultiKink {
+            base: Decimal::percent(2),
+            kinks: vec![],
+        })
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InterestError(InterestError::InvalidKinks {}),
+        err.downcast().unwrap()
+    );
+
+    let new_interests = Interest::MultiKink {
+        base: Decimal::percent(2),
+        kinks: vec![
+            Kink {
+                utilisation: Decimal::percent(80),
+                rate: Decimal::percent(10),
+            },
+            Kink {
+                utilisation: Decimal::percent(95),
+                rate: Decimal::percent(150),
+            },
+        ],
+    };
+    suite
+        .sudo_adjust_interest_rates(new_interests.clone())
+        .unwrap();
+
+    assert_eq!(
+        ValidatedInterest::unchecked(new_interests),
+        suite.query_config().unwrap().rates
+    );
+    assert_eq!(
+        suite.query_interest().unwrap().interest,
+        Decimal::percent(2)
+    );
+}
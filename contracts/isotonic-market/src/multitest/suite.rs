@@ -13,6 +13,7 @@ use osmo_bindings_test::{OsmosisApp, Pool};
 use utils::{
     credit_line::{CreditLineResponse, CreditLineValues},
     interest::Interest,
+    time::Duration,
     token::Token,
 };
 
@@ -21,8 +22,12 @@ use super::ca_mock::{
     InstantiateMsg as CAInstantiateMsg,
 };
 use crate::msg::{
-    ApyResponse, ExecuteMsg, InstantiateMsg, InterestResponse, MigrateMsg, QueryMsg,
-    ReserveResponse, SudoMsg, TokensBalanceResponse, TransferableAmountResponse,
+    AccountInterestResponse, ApyResponse, ExecuteMsg, InstantiateMsg, InterestResponse,
+    LiquidityHaircut, ListPositionsResponse, MigrateMsg, OverdueResponse, PendingRampsResponse,
+    PositionResponse, QueryMsg, RampParameter, RateHistoryResponse, RateSnapshot, ReserveBucket,
+    ReserveBucketsResponse, ReserveResponse, SimulationResponse, StableBorrowing,
+    StableDebtResponse, StableRateResponse, SudoMsg, TermLending, TermLoanResponse,
+    TermLoansResponse, TermRateResponse, TokensBalanceResponse, TransferableAmountResponse,
 };
 use crate::state::Config;
 
@@ -92,6 +97,7 @@ pub struct SuiteBuilder {
     collateral_ratio: Decimal,
     /// Defines the portion of borrower interest that is converted into reserves (0 <= x <= 1)
     reserve_factor: Decimal,
+    origination_fee: Decimal,
     pools: HashMap<u64, (Coin, Coin)>,
 }
 
@@ -112,6 +118,7 @@ impl SuiteBuilder {
             common_token: COMMON.to_owned(),
             collateral_ratio: Decimal::percent(50),
             reserve_factor: Decimal::percent(0),
+            origination_fee: Decimal::zero(),
             pools: HashMap::new(),
         }
     }
@@ -172,6 +179,11 @@ impl SuiteBuilder {
         self
     }
 
+    pub fn with_origination_fee(mut self, origination_fee: u64) -> Self {
+        self.origination_fee = Decimal::percent(origination_fee);
+        self
+    }
+
     pub fn with_pool(mut self, id: u64, pool: (Coin, Coin)) -> Self {
         self.pools.insert(id, pool);
         self
@@ -260,6 +272,9 @@ impl SuiteBuilder {
                     collateral_ratio: self.collateral_ratio,
                     price_oracle: oracle_contract.to_string(),
                     reserve_factor: self.reserve_factor,
+                    origination_fee: self.origination_fee,
+                    borrowing_enabled: true,
+                    collateral_enabled: true,
                 },
                 &[],
                 "market",
@@ -381,6 +396,23 @@ impl Suite {
         )
     }
 
+    pub fn transfer_ltokens(
+        &mut self,
+        sender: &str,
+        recipient: &str,
+        amount: u128,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            self.ltoken_contract.clone(),
+            &isotonic_token::msg::ExecuteMsg::Transfer {
+                recipient: recipient.to_owned(),
+                amount: isotonic_token::DisplayAmount::raw(amount),
+            },
+            &[],
+        )
+    }
+
     /// Attempts to withdraw the full "withdrawable" amount (as determined by the withdrawable query),
     /// then performs a couple checks to make sure nothing more than that could be withdrawn.
     pub fn attempt_withdraw_max(&mut self, sender: &str) -> AnyResult<()> {
@@ -450,6 +482,81 @@ impl Suite {
         )
     }
 
+    /// Borrow base asset from the lending pool at a stable rate
+    pub fn borrow_stable(
+        &mut self,
+        sender: &str,
+        amount: u128,
+        max_rate: impl Into<Option<Decimal>>,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            self.contract.clone(),
+            &ExecuteMsg::BorrowStable {
+                amount: Uint128::from(amount),
+                max_rate: max_rate.into(),
+            },
+            &[],
+        )
+    }
+
+    /// Repay stable-rate debt
+    pub fn repay_stable(&mut self, sender: &str, funds: Coin) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            self.contract.clone(),
+            &ExecuteMsg::RepayStable {},
+            &[funds],
+        )
+    }
+
+    /// Take a term loan of `amount`, due `duration` seconds from now
+    pub fn borrow_term(
+        &mut self,
+        sender: &str,
+        amount: u128,
+        duration: u64,
+        max_rate: impl Into<Option<Decimal>>,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            self.contract.clone(),
+            &ExecuteMsg::BorrowTerm {
+                amount: Uint128::from(amount),
+                duration,
+                max_rate: max_rate.into(),
+            },
+            &[],
+        )
+    }
+
+    /// Repay a term loan
+    pub fn repay_term(
+        &mut self,
+        sender: &str,
+        loan_id: u64,
+        funds: Coin,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            self.contract.clone(),
+            &ExecuteMsg::RepayTerm { loan_id },
+            &[funds],
+        )
+    }
+
+    /// Withdraw reserves allocated to `bucket` to its recipient
+    pub fn withdraw_reserve(&mut self, sender: &str, bucket: &str) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            self.contract.clone(),
+            &ExecuteMsg::WithdrawReserve {
+                bucket: bucket.to_owned(),
+            },
+            &[],
+        )
+    }
+
     pub fn swap_withdraw_from(
         &mut self,
         sender: impl Into<String>,
@@ -469,6 +576,59 @@ impl Suite {
         )
     }
 
+    pub fn withdraw_for(
+        &mut self,
+        sender: &str,
+        account: &str,
+        amount: u128,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            self.contract.clone(),
+            &ExecuteMsg::WithdrawFor {
+                account: account.to_owned(),
+                amount: Uint128::from(amount),
+            },
+            &[],
+        )
+    }
+
+    /// Repay up to `amount` of the debt of `account` with `funds`
+    pub fn repay_to(
+        &mut self,
+        sender: &str,
+        account: &str,
+        amount: u128,
+        funds: Coin,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            self.contract.clone(),
+            &ExecuteMsg::RepayTo {
+                account: account.to_owned(),
+                amount: Uint128::from(amount),
+            },
+            &[funds],
+        )
+    }
+
+    pub fn borrow_for(
+        &mut self,
+        sender: &str,
+        account: &str,
+        amount: u128,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            self.contract.clone(),
+            &ExecuteMsg::BorrowFor {
+                account: account.to_owned(),
+                amount: Uint128::from(amount),
+            },
+            &[],
+        )
+    }
+
     pub fn distribute_as_ltokens(&mut self, sender: &str, funds: Coin) -> AnyResult<AppResponse> {
         self.app.execute_contract(
             Addr::unchecked(sender),
@@ -489,6 +649,26 @@ impl Suite {
         )
     }
 
+    pub fn accrue_interest(&mut self, sender: &str) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            self.contract.clone(),
+            &ExecuteMsg::AccrueInterest {},
+            &[],
+        )
+    }
+
+    pub fn accrue_interest_for(&mut self, sender: &str, recipient: &str) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            self.contract.clone(),
+            &ExecuteMsg::AccrueInterestFor {
+                recipient: recipient.to_owned(),
+            },
+            &[],
+        )
+    }
+
     /// Shortcut for querying base asset balance in the market contract
     pub fn query_asset_balance(&self, owner: &str) -> StdResult<u128> {
         let amount = self
@@ -649,6 +829,161 @@ impl Suite {
         Ok(response.reserve)
     }
 
+    pub fn query_reserve_buckets(&self) -> AnyResult<ReserveBucketsResponse> {
+        let response: ReserveBucketsResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(self.contract.clone(), &QueryMsg::ReserveBuckets {})?;
+        Ok(response)
+    }
+
+    pub fn query_positions(
+        &self,
+        start_after: impl Into<Option<String>>,
+        limit: impl Into<Option<u32>>,
+    ) -> AnyResult<Vec<PositionResponse>> {
+        let response: ListPositionsResponse = self.app.wrap().query_wasm_smart(
+            self.contract.clone(),
+            &QueryMsg::ListPositions {
+                start_after: start_after.into(),
+                limit: limit.into(),
+            },
+        )?;
+        Ok(response.positions)
+    }
+
+    pub fn query_account_interest(
+        &self,
+        account: impl ToString,
+    ) -> AnyResult<AccountInterestResponse> {
+        let response = self.app.wrap().query_wasm_smart(
+            self.contract.clone(),
+            &QueryMsg::AccountInterest {
+                account: account.to_string(),
+            },
+        )?;
+        Ok(response)
+    }
+
+    pub fn query_rate_history(
+        &self,
+        start_after: impl Into<Option<u64>>,
+        limit: impl Into<Option<u32>>,
+    ) -> AnyResult<Vec<RateSnapshot>> {
+        let response: RateHistoryResponse = self.app.wrap().query_wasm_smart(
+            self.contract.clone(),
+            &QueryMsg::RateHistory {
+                start_after: start_after.into(),
+                limit: limit.into(),
+            },
+        )?;
+        Ok(response.snapshots)
+    }
+
+    pub fn query_simulate_borrow(
+        &self,
+        account: &str,
+        amount: u128,
+    ) -> AnyResult<SimulationResponse> {
+        self.query_simulation(QueryMsg::SimulateBorrow {
+            account: account.to_owned(),
+            amount: amount.into(),
+        })
+    }
+
+    pub fn query_simulate_withdraw(
+        &self,
+        account: &str,
+        amount: u128,
+    ) -> AnyResult<SimulationResponse> {
+        self.query_simulation(QueryMsg::SimulateWithdraw {
+            account: account.to_owned(),
+            amount: amount.into(),
+        })
+    }
+
+    pub fn query_simulate_repay(
+        &self,
+        account: &str,
+        amount: u128,
+    ) -> AnyResult<SimulationResponse> {
+        self.query_simulation(QueryMsg::SimulateRepay {
+            account: account.to_owned(),
+            amount: amount.into(),
+        })
+    }
+
+    fn query_simulation(&self, msg: QueryMsg) -> AnyResult<SimulationResponse> {
+        let response: SimulationResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(self.contract.clone(), &msg)?;
+        Ok(response)
+    }
+
+    pub fn query_pending_ramps(&self) -> AnyResult<PendingRampsResponse> {
+        let response: PendingRampsResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(self.contract.clone(), &QueryMsg::PendingRamps {})?;
+        Ok(response)
+    }
+
+    pub fn query_stable_debt(&self, account: impl ToString) -> AnyResult<StableDebtResponse> {
+        let response: StableDebtResponse = self.app.wrap().query_wasm_smart(
+            self.contract.clone(),
+            &QueryMsg::StableDebt {
+                account: account.to_string(),
+            },
+        )?;
+        Ok(response)
+    }
+
+    pub fn query_stable_rate(&self) -> AnyResult<Decimal> {
+        let response: StableRateResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(self.contract.clone(), &QueryMsg::StableRate {})?;
+        Ok(response.rate)
+    }
+
+    pub fn query_term_loans(
+        &self,
+        account: impl ToString,
+        start_after: impl Into<Option<u64>>,
+        limit: impl Into<Option<u32>>,
+    ) -> AnyResult<Vec<TermLoanResponse>> {
+        let response: TermLoansResponse = self.app.wrap().query_wasm_smart(
+            self.contract.clone(),
+            &QueryMsg::TermLoans {
+                account: account.to_string(),
+                start_after: start_after.into(),
+                limit: limit.into(),
+            },
+        )?;
+        Ok(response.loans)
+    }
+
+    pub fn query_term_rate(&self, amount: u128) -> AnyResult<Decimal> {
+        let response: TermRateResponse = self.app.wrap().query_wasm_smart(
+            self.contract.clone(),
+            &QueryMsg::TermRate {
+                amount: amount.into(),
+            },
+        )?;
+        Ok(response.rate)
+    }
+
+    pub fn query_overdue(&self, account: impl ToString) -> AnyResult<OverdueResponse> {
+        let response: OverdueResponse = self.app.wrap().query_wasm_smart(
+            self.contract.clone(),
+            &QueryMsg::Overdue {
+                account: account.to_string(),
+            },
+        )?;
+        Ok(response)
+    }
+
     pub fn query_config(&self) -> AnyResult<Config> {
         let response: Config = self
             .app
@@ -749,6 +1084,152 @@ impl Suite {
         )
     }
 
+    pub fn sudo_adjust_liquidity_haircut(
+        &mut self,
+        new_haircut: impl Into<Option<LiquidityHaircut>>,
+    ) -> AnyResult<AppResponse> {
+        let contract = self.contract.clone();
+        self.app.wasm_sudo(
+            contract,
+            &SudoMsg::AdjustLiquidityHaircut {
+                new_haircut: new_haircut.into(),
+            },
+        )
+    }
+
+    /// Changes the accrual reward through sudo. Pass new reward as percentage.
+    pub fn sudo_adjust_accrual_reward(&mut self, new_reward: u64) -> AnyResult<AppResponse> {
+        let contract = self.contract.clone();
+        self.app.wasm_sudo(
+            contract,
+            &SudoMsg::AdjustAccrualReward {
+                new_reward: Decimal::percent(new_reward),
+            },
+        )
+    }
+
+    /// Changes the origination fee through sudo. Pass new fee as percentage.
+    pub fn sudo_adjust_origination_fee(&mut self, new_fee: u64) -> AnyResult<AppResponse> {
+        let contract = self.contract.clone();
+        self.app.wasm_sudo(
+            contract,
+            &SudoMsg::AdjustOriginationFee {
+                new_fee: Decimal::percent(new_fee),
+            },
+        )
+    }
+
+    /// Changes the reserve share of distributed funds through sudo. Pass new share as
+    /// percentage.
+    pub fn sudo_adjust_distribution_reserve_share(
+        &mut self,
+        new_share: u64,
+    ) -> AnyResult<AppResponse> {
+        let contract = self.contract.clone();
+        self.app.wasm_sudo(
+            contract,
+            &SudoMsg::AdjustDistributionReserveShare {
+                new_share: Decimal::percent(new_share),
+            },
+        )
+    }
+
+    pub fn sudo_adjust_market_flags(
+        &mut self,
+        borrowing_enabled: impl Into<Option<bool>>,
+        collateral_enabled: impl Into<Option<bool>>,
+    ) -> AnyResult<AppResponse> {
+        let contract = self.contract.clone();
+        self.app.wasm_sudo(
+            contract,
+            &SudoMsg::AdjustMarketFlags {
+                borrowing_enabled: borrowing_enabled.into(),
+                collateral_enabled: collateral_enabled.into(),
+            },
+        )
+    }
+
+    /// Ramps the collateral ratio through sudo, ending `seconds` from now. Pass target as percentage.
+    pub fn sudo_ramp_collateral_ratio(
+        &mut self,
+        target: u64,
+        seconds: u64,
+    ) -> AnyResult<AppResponse> {
+        let contract = self.contract.clone();
+        let end = Duration::new(seconds).after(&self.app.block_info());
+        self.app.wasm_sudo(
+            contract,
+            &SudoMsg::RampCollateralRatio {
+                target: Decimal::percent(target),
+                end,
+            },
+        )
+    }
+
+    /// Ramps the interest rates through sudo, ending `seconds` from now
+    pub fn sudo_ramp_interest_rates(
+        &mut self,
+        target: Interest,
+        seconds: u64,
+    ) -> AnyResult<AppResponse> {
+        let contract = self.contract.clone();
+        let end = Duration::new(seconds).after(&self.app.block_info());
+        self.app
+            .wasm_sudo(contract, &SudoMsg::RampInterestRates { target, end })
+    }
+
+    pub fn sudo_cancel_ramp(&mut self, parameter: RampParameter) -> AnyResult<AppResponse> {
+        let contract = self.contract.clone();
+        self.app
+            .wasm_sudo(contract, &SudoMsg::CancelRamp { parameter })
+    }
+
+    pub fn sudo_adjust_stable_borrowing(
+        &mut self,
+        new_config: Option<StableBorrowing>,
+    ) -> AnyResult<AppResponse> {
+        let contract = self.contract.clone();
+        self.app
+            .wasm_sudo(contract, &SudoMsg::AdjustStableBorrowing { new_config })
+    }
+
+    pub fn sudo_rebalance_stable_rate(&mut self, account: &str) -> AnyResult<AppResponse> {
+        let contract = self.contract.clone();
+        self.app.wasm_sudo(
+            contract,
+            &SudoMsg::RebalanceStableRate {
+                account: account.to_owned(),
+            },
+        )
+    }
+
+    pub fn sudo_adjust_term_lending(
+        &mut self,
+        new_config: Option<TermLending>,
+    ) -> AnyResult<AppResponse> {
+        let contract = self.contract.clone();
+        self.app
+            .wasm_sudo(contract, &SudoMsg::AdjustTermLending { new_config })
+    }
+
+    /// Sets the reserve split through sudo. Pass buckets as (name, recipient, weight).
+    pub fn sudo_adjust_reserve_split(
+        &mut self,
+        buckets: &[(&str, &str, u64)],
+    ) -> AnyResult<AppResponse> {
+        let contract = self.contract.clone();
+        let buckets = buckets
+            .iter()
+            .map(|(name, recipient, weight)| ReserveBucket {
+                name: name.to_string(),
+                recipient: recipient.to_string(),
+                weight: *weight,
+            })
+            .collect();
+        self.app
+            .wasm_sudo(contract, &SudoMsg::AdjustReserveSplit { buckets })
+    }
+
     pub fn assert_ltoken_balance(&self, account: impl ToString, amount: impl Into<Uint128>) {
         let balance = self.query_tokens_balance(account).unwrap();
         assert_eq!(balance.ltokens, amount.into());
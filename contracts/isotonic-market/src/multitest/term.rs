@@ -0,0 +1,248 @@
+use cosmwasm_std::{coin, Decimal, Timestamp, Uint128};
+
+use super::suite::{SuiteBuilder, COMMON};
+use crate::error::ContractError;
+use crate::msg::TermLending;
+
+const YEAR: u64 = 365 * 24 * 3600;
+
+fn term_lending() -> TermLending {
+    TermLending {
+        premium: Decimal::percent(2),
+        penalty_rate: Decimal::percent(10),
+        max_duration: 2 * YEAR,
+        early_repayment_fee: Decimal::percent(1),
+    }
+}
+
+#[test]
+fn term_lending_disabled_by_default() {
+    let borrower = "borrower";
+    let market_token = "atom";
+    let mut suite = SuiteBuilder::new()
+        .with_contract_funds(coin(1000, market_token))
+        .with_market_token(market_token)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+
+    suite.set_high_credit_line(borrower).unwrap();
+
+    let err = suite.borrow_term(borrower, 100, YEAR, None).unwrap_err();
+    assert_eq!(
+        ContractError::TermLendingDisabled {},
+        err.downcast().unwrap()
+    );
+
+    let err = suite
+        .sudo_adjust_term_lending(Some(TermLending {
+            max_duration: 0,
+            ..term_lending()
+        }))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidTermLending {},
+        err.downcast().unwrap()
+    );
+
+    suite
+        .sudo_adjust_term_lending(Some(term_lending()))
+        .unwrap();
+    let err = suite
+        .borrow_term(borrower, 100, 2 * YEAR + 1, None)
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidTermDuration { max: 2 * YEAR },
+        err.downcast().unwrap()
+    );
+}
+
+#[test]
+fn borrow_term_loans() {
+    let lender = "lender";
+    let borrower = "borrower";
+    let market_token = "atom";
+    let mut suite = SuiteBuilder::new()
+        .with_funds(lender, &[coin(1000, market_token)])
+        .with_market_token(market_token)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+
+    suite
+        .sudo_adjust_term_lending(Some(term_lending()))
+        .unwrap();
+    suite.deposit(lender, &[coin(1000, market_token)]).unwrap();
+    suite.set_high_credit_line(borrower).unwrap();
+
+    // 3% base + 20% slope * 50% utilisation after borrowing + 2% premium
+    assert_eq!(suite.query_term_rate(500).unwrap(), Decimal::percent(15));
+    let err = suite
+        .borrow_term(borrower, 500, YEAR, Decimal::percent(14))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::TermRateTooHigh {
+            rate: Decimal::percent(15),
+            max_rate: Decimal::percent(14),
+        },
+        err.downcast().unwrap()
+    );
+
+    suite.borrow_term(borrower, 400, YEAR, None).unwrap();
+    suite.borrow_term(borrower, 100, 2 * YEAR, None).unwrap();
+    assert_eq!(suite.query_asset_balance(borrower).unwrap(), 500);
+
+    let start = suite.app().block_info().time.seconds();
+    let loans = suite.query_term_loans(borrower, None, None).unwrap();
+    assert_eq!(loans.len(), 2);
+    assert_eq!(loans[0].id, 1);
+    assert_eq!(loans[0].amount, Uint128::new(400));
+    // 3% base + 20% slope * 40% + 2% premium
+    assert_eq!(loans[0].rate, Decimal::percent(13));
+    assert_eq!(loans[0].maturity.seconds(), start + YEAR);
+    assert!(!loans[0].overdue);
+    assert_eq!(loans[1].id, 2);
+    assert_eq!(loans[1].rate, Decimal::percent(15));
+    assert_eq!(loans[1].maturity.seconds(), start + 2 * YEAR);
+
+    let loans = suite.query_term_loans(borrower, 1, None).unwrap();
+    assert_eq!(loans.len(), 1);
+    assert_eq!(loans[0].id, 2);
+
+    // Term loans count towards utilisation and the credit line
+    assert_eq!(
+        suite.query_interest().unwrap().utilisation,
+        Decimal::percent(50)
+    );
+    let credit_line = suite.query_credit_line(borrower).unwrap();
+    assert_eq!(credit_line.debt.amount, Uint128::new(500));
+}
+
+#[test]
+fn early_repayment_is_charged_fee() {
+    let lender = "lender";
+    let borrower = "borrower";
+    let market_token = "atom";
+    let mut suite = SuiteBuilder::new()
+        .with_funds(lender, &[coin(1000, market_token)])
+        .with_funds(borrower, &[coin(100, market_token)])
+        .with_market_token(market_token)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+
+    suite
+        .sudo_adjust_term_lending(Some(term_lending()))
+        .unwrap();
+    suite.deposit(lender, &[coin(1000, market_token)]).unwrap();
+    suite.set_high_credit_line(borrower).unwrap();
+    suite.borrow_term(borrower, 500, YEAR, None).unwrap();
+
+    let err = suite
+        .repay_term(borrower, 2, coin(100, market_token))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::TermLoanNotFound {
+            account: borrower.to_owned(),
+            id: 2
+        },
+        err.downcast().unwrap()
+    );
+
+    // Partial repayment: 200 of principal and 1% fee
+    suite
+        .repay_term(borrower, 1, coin(202, market_token))
+        .unwrap();
+    assert_eq!(
+        suite.query_term_loans(borrower, None, None).unwrap()[0].amount,
+        Uint128::new(300)
+    );
+    assert_eq!(suite.query_reserve().unwrap(), Uint128::new(2));
+
+    // Full repayment: 300 of principal and 1% fee, surplus returned
+    suite
+        .repay_term(borrower, 1, coin(398, market_token))
+        .unwrap();
+    assert_eq!(
+        suite.query_term_loans(borrower, None, None).unwrap(),
+        vec![]
+    );
+    assert_eq!(suite.query_reserve().unwrap(), Uint128::new(5));
+    assert_eq!(suite.query_asset_balance(borrower).unwrap(), 95);
+}
+
+#[test]
+fn overdue_loan_accrues_penalty() {
+    let lender = "lender";
+    let borrower = "borrower";
+    let market_token = "atom";
+    let mut suite = SuiteBuilder::new()
+        .with_funds(lender, &[coin(1000, market_token)])
+        .with_funds(borrower, &[coin(300, market_token)])
+        .with_charge_period(YEAR)
+        .with_market_token(market_token)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+
+    suite
+        .sudo_adjust_term_lending(Some(term_lending()))
+        .unwrap();
+    suite.deposit(lender, &[coin(1000, market_token)]).unwrap();
+    suite.set_high_credit_line(borrower).unwrap();
+    suite.borrow_term(borrower, 500, YEAR, None).unwrap();
+
+    suite.advance_seconds(YEAR);
+    // 500 * 15%, due right now
+    let loan = &suite.query_term_loans(borrower, None, None).unwrap()[0];
+    assert_eq!(loan.amount, Uint128::new(575));
+    assert!(!loan.overdue);
+    assert!(!suite.query_overdue(borrower).unwrap().overdue);
+
+    suite.advance_seconds(YEAR);
+    // 500 * 1.15^2 = 661 and 10% penalty on it
+    let loan = &suite.query_term_loans(borrower, None, None).unwrap()[0];
+    assert_eq!(loan.amount, Uint128::new(727));
+    assert!(loan.overdue);
+    let overdue = suite.query_overdue(borrower).unwrap();
+    assert!(overdue.overdue);
+    assert_eq!(overdue.amount, Uint128::new(727));
+    assert_eq!(
+        loan.maturity,
+        Timestamp::from_seconds(suite.app().block_info().time.seconds() - YEAR)
+    );
+
+    // No early repayment fee after maturity, the penalty goes to the reserve
+    suite
+        .repay_term(borrower, 1, coin(727, market_token))
+        .unwrap();
+    assert_eq!(
+        suite.query_term_loans(borrower, None, None).unwrap(),
+        vec![]
+    );
+    assert_eq!(suite.query_reserve().unwrap(), Uint128::new(66));
+    assert_eq!(suite.query_asset_balance(borrower).unwrap(), 73);
+    assert_eq!(suite.query_interest().unwrap().utilisation, Decimal::zero());
+}
+
+#[test]
+fn borrow_term_charges_origination_fee() {
+    let lender = "lender";
+    let borrower = "borrower";
+    let market_token = "atom";
+    let mut suite = SuiteBuilder::new()
+        .with_funds(lender, &[coin(1000, market_token)])
+        .with_origination_fee(1)
+        .with_market_token(market_token)
+        .with_pool(1, (coin(100, COMMON), coin(100, market_token)))
+        .build();
+
+    suite
+        .sudo_adjust_term_lending(Some(term_lending()))
+        .unwrap();
+    suite.deposit(lender, &[coin(1000, market_token)]).unwrap();
+    suite.set_high_credit_line(borrower).unwrap();
+
+    // The whole amount is paid out, the fee is added to the loan and goes to reserves
+    suite.borrow_term(borrower, 500, YEAR, None).unwrap();
+    assert_eq!(suite.query_asset_balance(borrower).unwrap(), 500);
+    let loan = &suite.query_term_loans(borrower, None, None).unwrap()[0];
+    assert_eq!(loan.amount, Uint128::new(505));
+    assert_eq!(suite.query_reserve().unwrap(), Uint128::new(5));
+}
@@ -0,0 +1,51 @@
+use cosmwasm_std::Decimal;
+use osmo_bindings::{Step, Swap};
+use utils::oracle::{OracleQueryMsg, PriceResponse, RouteResponse, RouteStep};
+
+use crate::{contract::Deps, state::Config, ContractError};
+
+/// Queries the configured oracle for the `sell` / `buy` exchange rate
+pub fn query_price(
+    deps: Deps,
+    cfg: &Config,
+    sell: impl Into<String>,
+    buy: impl Into<String>,
+) -> Result<Decimal, ContractError> {
+    let price: PriceResponse = deps.querier.query_wasm_smart(
+        &cfg.price_oracle,
+        &OracleQueryMsg::Price {
+            sell: sell.into(),
+            buy: buy.into(),
+        },
+    )?;
+    Ok(price.rate)
+}
+
+/// Queries the configured oracle for the route to follow when swapping `sell` for `buy`
+pub fn query_route(
+    deps: Deps,
+    cfg: &Config,
+    sell: impl Into<String>,
+    buy: impl Into<String>,
+) -> Result<Vec<RouteStep>, ContractError> {
+    let route: RouteResponse = deps.querier.query_wasm_smart(
+        &cfg.price_oracle,
+        &OracleQueryMsg::Route {
+            sell: sell.into(),
+            buy: buy.into(),
+        },
+    )?;
+    Ok(route.route)
+}
+
+/// Converts the route into the first swap and the following steps, as expected by Osmosis
+pub fn osmosis_route(route: Vec<RouteStep>) -> Result<(Swap, Vec<Step>), ContractError> {
+    let mut route = route.into_iter();
+    let first = route.next().ok_or(ContractError::EmptyRoute {})?;
+    let swap = Swap::new(first.pool_id, first.denom_in, first.denom_out);
+    let steps = route
+        .map(|step| Step::new(step.pool_id, step.denom_out))
+        .collect();
+
+    Ok((swap, steps))
+}
@@ -0,0 +1,143 @@
+use cosmwasm_std::{Decimal, Storage, Timestamp};
+
+use crate::{
+    contract::Deps,
+    state::{Config, Ramp, COLLATERAL_RATIO_RAMP, CONFIG, INTEREST_RATES_RAMP, RATE_AT_TARGET},
+    ContractError,
+};
+
+impl<T> Ramp<T> {
+    /// How far along the ramp is at `time`, from 0 at the start to 1 at the end
+    fn progress(&self, time: Timestamp) -> Decimal {
+        if self.end.is_expired_time(time) {
+            return Decimal::one();
+        }
+
+        let start = self.start.seconds();
+        let elapsed = time.seconds().saturating_sub(start);
+        Decimal::from_ratio(elapsed, self.end.time().seconds() - start)
+    }
+
+    pub fn is_finished(&self, time: Timestamp) -> bool {
+        self.end.is_expired_time(time)
+    }
+}
+
+/// Linear interpolation between `from` and `to`, `progress` of the way
+fn interpolate(from: Decimal, to: Decimal, progress: Decimal) -> Decimal {
+    if to >= from {
+        from + (to - from) * progress
+    } else {
+        from - (from - to) * progress
+    }
+}
+
+impl Ramp<Decimal> {
+    pub fn value_at(&self, time: Timestamp) -> Decimal {
+        interpolate(self.from, self.to, self.progress(time))
+    }
+}
+
+/// Collateral ratio in effect at `time`
+pub fn collateral_ratio(
+    deps: Deps,
+    cfg: &Config,
+    time: Timestamp,
+) -> Result<Decimal, ContractError> {
+    Ok(match COLLATERAL_RATIO_RAMP.may_load(deps.storage)? {
+        Some(ramp) => ramp.value_at(time),
+        None => cfg.collateral_ratio,
+    })
+}
+
+/// Interest rate charged at `utilisation` at `time`. During a ramp, it is interpolated between
+/// the rates of the curve the ramp started from and the target curve.
+pub fn interest_rate(
+    deps: Deps,
+    cfg: &Config,
+    utilisation: Decimal,
+    time: Timestamp,
+) -> Result<Decimal, ContractError> {
+    let rate_at_target = RATE_AT_TARGET.may_load(deps.storage)?;
+
+    Ok(match INTEREST_RATES_RAMP.may_load(deps.storage)? {
+        Some(ramp) => interpolate(
+            ramp.from
+                .calculate_interest_rate_with(utilisation, rate_at_target),
+            ramp.to
+                .calculate_interest_rate_with(utilisation, rate_at_target),
+            ramp.progress(time),
+        ),
+        None => cfg
+            .rates
+            .calculate_interest_rate_with(utilisation, rate_at_target),
+    })
+}
+
+/// Stores the final values of ramps which ended by `time` in the config
+pub fn finish_ramps(storage: &mut dyn Storage, time: Timestamp) -> Result<(), ContractError> {
+    let mut cfg = CONFIG.load(storage)?;
+
+    if let Some(ramp) = COLLATERAL_RATIO_RAMP.may_load(storage)? {
+        if ramp.is_finished(time) {
+            cfg.collateral_ratio = ramp.to;
+            COLLATERAL_RATIO_RAMP.remove(storage);
+        }
+    }
+
+    if let Some(ramp) = INTEREST_RATES_RAMP.may_load(storage)? {
+        if ramp.is_finished(time) {
+            cfg.rates = ramp.to;
+            INTEREST_RATES_RAMP.remove(storage);
+            RATE_AT_TARGET.remove(storage);
+        }
+    }
+
+    CONFIG.save(storage, &cfg)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use utils::time::Expiration;
+
+    fn ramp(from: u64, to: u64) -> Ramp<Decimal> {
+        Ramp {
+            from: Decimal::percent(from),
+            to: Decimal::percent(to),
+            start: Timestamp::from_seconds(100),
+            end: Expiration::at_timestamp(Timestamp::from_seconds(200)),
+        }
+    }
+
+    #[test]
+    fn interpolates_linearly() {
+        let down = ramp(80, 60);
+        assert_eq!(
+            down.value_at(Timestamp::from_seconds(100)),
+            Decimal::percent(80)
+        );
+        assert_eq!(
+            down.value_at(Timestamp::from_seconds(125)),
+            Decimal::percent(75)
+        );
+        assert_eq!(
+            down.value_at(Timestamp::from_seconds(200)),
+            Decimal::percent(60)
+        );
+        assert_eq!(
+            down.value_at(Timestamp::from_seconds(300)),
+            Decimal::percent(60)
+        );
+
+        let up = ramp(60, 80);
+        assert_eq!(
+            up.value_at(Timestamp::from_seconds(150)),
+            Decimal::percent(70)
+        );
+        assert!(!up.is_finished(Timestamp::from_seconds(199)));
+        assert!(up.is_finished(Timestamp::from_seconds(200)));
+    }
+}
@@ -0,0 +1,66 @@
+use std::collections::HashSet;
+
+use cosmwasm_std::{Api, Order, StdResult, Storage, Uint128};
+
+use crate::{
+    msg::ReserveBucket,
+    state::{Config, RESERVE_BUCKETS},
+    ContractError,
+};
+
+/// Amounts allocated to every bucket of the current split once `reserve` is allocated, and
+/// the amount left unallocated
+pub fn split(
+    storage: &dyn Storage,
+    cfg: &Config,
+    reserve: Uint128,
+) -> Result<(Vec<Uint128>, Uint128), ContractError> {
+    let allocated = RESERVE_BUCKETS
+        .range(storage, None, None, Order::Ascending)
+        .map(|bucket| bucket.map(|(_, amount)| amount))
+        .sum::<StdResult<Uint128>>()?;
+    let new = reserve.saturating_sub(allocated);
+    let mut unallocated = new;
+    let total_weight: u64 = cfg.reserve_split.iter().map(|bucket| bucket.weight).sum();
+
+    let amounts = cfg
+        .reserve_split
+        .iter()
+        .map(|bucket| {
+            let share = new.multiply_ratio(bucket.weight, total_weight);
+            unallocated -= share;
+            let current = RESERVE_BUCKETS
+                .may_load(storage, &bucket.name)?
+                .unwrap_or_default();
+            Ok(current + share)
+        })
+        .collect::<Result<_, ContractError>>()?;
+
+    Ok((amounts, unallocated))
+}
+
+/// Allocates reserves accrued since the last allocation to buckets of the current split.
+/// Rounding leftovers stay unallocated until the next allocation.
+pub fn allocate(
+    storage: &mut dyn Storage,
+    cfg: &Config,
+    reserve: Uint128,
+) -> Result<(), ContractError> {
+    let (amounts, _) = split(storage, cfg, reserve)?;
+    for (bucket, amount) in cfg.reserve_split.iter().zip(amounts) {
+        RESERVE_BUCKETS.save(storage, &bucket.name, &amount)?;
+    }
+    Ok(())
+}
+
+/// Validates a new split
+pub fn validate(api: &dyn Api, buckets: &[ReserveBucket]) -> Result<(), ContractError> {
+    let mut names = HashSet::new();
+    for bucket in buckets {
+        if bucket.name.is_empty() || bucket.weight == 0 || !names.insert(&bucket.name) {
+            return Err(ContractError::InvalidReserveSplit {});
+        }
+        api.addr_validate(&bucket.recipient)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,209 @@
+use cosmwasm_std::{Addr, Decimal, Env, Storage, Uint128};
+
+use crate::{
+    contract::Deps,
+    interest::{compounded_growth, token_supply, utilisation},
+    msg::StableBorrowing,
+    ramp,
+    state::{Config, StableDebt, STABLE_DEBTS, STABLE_TOTAL, TERM_LOANS},
+    ContractError,
+};
+
+impl StableDebt {
+    /// Debt including the interest accrued until `now` (in seconds)
+    pub fn amount_at(&self, cfg: &Config, now: u64) -> Result<Uint128, ContractError> {
+        let growth = compounded_growth(
+            self.rate,
+            cfg.interest_charge_period,
+            now.saturating_sub(self.last_update),
+        )?;
+        Ok(self.amount * growth)
+    }
+
+    /// Accrues interest until `now` and adds `amount` borrowed at `rate`. The rate of the
+    /// result is the average of both rates weighted by amounts.
+    pub fn add(
+        &mut self,
+        cfg: &Config,
+        now: u64,
+        amount: Uint128,
+        rate: Decimal,
+    ) -> Result<(), ContractError> {
+        let current = self.amount_at(cfg, now)?;
+        let total = current + amount;
+        if total.is_zero() {
+            self.last_update = now;
+            return Ok(());
+        }
+
+        self.rate = Decimal::from_ratio(current, total) * self.rate
+            + Decimal::from_ratio(amount, total) * rate;
+        self.amount = total;
+        self.last_update = now;
+        Ok(())
+    }
+
+    /// Accrues interest until `now` and removes `amount` which was borrowed at `rate`,
+    /// reverting its effect on the average rate
+    pub fn remove(
+        &mut self,
+        cfg: &Config,
+        now: u64,
+        amount: Uint128,
+        rate: Decimal,
+    ) -> Result<(), ContractError> {
+        let current = self.amount_at(cfg, now)?;
+        let remaining = current.saturating_sub(amount);
+
+        self.rate = if remaining.is_zero() {
+            Decimal::zero()
+        } else {
+            (Decimal::from_ratio(current, remaining) * self.rate)
+                .saturating_sub(Decimal::from_ratio(amount, remaining) * rate)
+        };
+        self.amount = remaining;
+        self.last_update = now;
+        Ok(())
+    }
+}
+
+/// Sum of all stable-rate debts, as of its last update
+pub fn total(storage: &dyn Storage) -> Result<StableDebt, ContractError> {
+    Ok(STABLE_TOTAL.may_load(storage)?.unwrap_or_default())
+}
+
+/// Saves the sum of all fixed-rate debts. Interest of every debt is rounded on its own, so
+/// removing them one by one can leave a remainder, which is dropped once no stable debt or
+/// term loan is left.
+pub fn save_total(storage: &mut dyn Storage, total_debt: &StableDebt) -> Result<(), ContractError> {
+    if STABLE_DEBTS.is_empty(storage) && TERM_LOANS.is_empty(storage) {
+        STABLE_TOTAL.save(
+            storage,
+            &StableDebt {
+                last_update: total_debt.last_update,
+                ..StableDebt::default()
+            },
+        )?;
+    } else {
+        STABLE_TOTAL.save(storage, total_debt)?;
+    }
+    Ok(())
+}
+
+/// Stable-rate debt of `account`, as of its last update
+pub fn debt(storage: &dyn Storage, account: &Addr) -> Result<StableDebt, ContractError> {
+    Ok(STABLE_DEBTS.may_load(storage, account)?.unwrap_or_default())
+}
+
+/// Records `amount` borrowed by `account` at `rate`
+pub fn borrow(
+    storage: &mut dyn Storage,
+    cfg: &Config,
+    now: u64,
+    account: &Addr,
+    amount: Uint128,
+    rate: Decimal,
+) -> Result<(), ContractError> {
+    let mut account_debt = debt(storage, account)?;
+    account_debt.add(cfg, now, amount, rate)?;
+    STABLE_DEBTS.save(storage, account, &account_debt)?;
+
+    let mut total_debt = total(storage)?;
+    total_debt.add(cfg, now, amount, rate)?;
+    STABLE_TOTAL.save(storage, &total_debt)?;
+    Ok(())
+}
+
+/// Repays up to `amount` of the stable-rate debt of `account`. Returns the amount repaid.
+pub fn repay(
+    storage: &mut dyn Storage,
+    cfg: &Config,
+    now: u64,
+    account: &Addr,
+    amount: Uint128,
+) -> Result<Uint128, ContractError> {
+    let mut account_debt = debt(storage, account)?;
+    let current = account_debt.amount_at(cfg, now)?;
+    let repaid = std::cmp::min(amount, current);
+    if repaid.is_zero() {
+        return Ok(repaid);
+    }
+
+    if repaid == current {
+        STABLE_DEBTS.remove(storage, account);
+    } else {
+        account_debt.amount = current - repaid;
+        account_debt.last_update = now;
+        STABLE_DEBTS.save(storage, account, &account_debt)?;
+    }
+
+    let mut total_debt = total(storage)?;
+    total_debt.remove(cfg, now, repaid, account_debt.rate)?;
+    save_total(storage, &total_debt)?;
+    Ok(repaid)
+}
+
+/// Moves the debt of `account` to `rate`
+pub fn change_rate(
+    storage: &mut dyn Storage,
+    cfg: &Config,
+    now: u64,
+    account: &Addr,
+    rate: Decimal,
+) -> Result<(), ContractError> {
+    let mut account_debt = debt(storage, account)?;
+    let current = account_debt.amount_at(cfg, now)?;
+
+    let mut total_debt = total(storage)?;
+    total_debt.remove(cfg, now, current, account_debt.rate)?;
+    total_debt.add(cfg, now, current, rate)?;
+    STABLE_TOTAL.save(storage, &total_debt)?;
+
+    account_debt.amount = current;
+    account_debt.rate = rate;
+    account_debt.last_update = now;
+    STABLE_DEBTS.save(storage, account, &account_debt)?;
+    Ok(())
+}
+
+/// Returns stable borrowing parameters, failing if it is disabled
+pub fn params(cfg: &Config) -> Result<&StableBorrowing, ContractError> {
+    cfg.stable_borrowing
+        .as_ref()
+        .ok_or(ContractError::StableBorrowingDisabled {})
+}
+
+/// Utilisation after additionally borrowing `amount` at a fixed rate, and the variable rate
+/// at this utilisation
+pub fn variable_rate_after(
+    deps: Deps,
+    env: &Env,
+    cfg: &Config,
+    amount: Uint128,
+) -> Result<(Decimal, Decimal), ContractError> {
+    let stable_total = total(deps.storage)?.amount_at(cfg, env.block.time.seconds())?;
+    let utilisation = utilisation(&token_supply(deps, cfg)?, stable_total + amount);
+    let variable = ramp::interest_rate(deps, cfg, utilisation, env.block.time)?;
+    Ok((utilisation, variable))
+}
+
+/// Utilisation after additionally borrowing `amount`, and the stable rate quoted for it
+pub fn quote(
+    deps: Deps,
+    env: &Env,
+    cfg: &Config,
+    amount: Uint128,
+) -> Result<(Decimal, Decimal), ContractError> {
+    let params = params(cfg)?;
+    let (utilisation, variable) = variable_rate_after(deps, env, cfg, amount)?;
+    Ok((utilisation, variable + params.premium))
+}
+
+impl StableBorrowing {
+    pub fn validate(&self) -> Result<(), ContractError> {
+        if self.rebalance_up_utilisation > Decimal::one() {
+            return Err(ContractError::InvalidStableBorrowing {});
+        }
+        Ok(())
+    }
+}
@@ -1,11 +1,15 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use utils::interest::ValidatedInterest;
+use utils::{interest::ValidatedInterest, time::Expiration};
 
-use cosmwasm_std::{Addr, Decimal, Uint128};
-use cw_storage_plus::Item;
+use cosmwasm_std::{Addr, Decimal, Timestamp, Uint128};
+use cw_storage_plus::{Item, Map};
 use isotonic_token::msg::TokenInfoResponse;
 
+use crate::msg::{
+    default_enabled, LiquidityHaircut, RateSnapshot, ReserveBucket, StableBorrowing, TermLending,
+};
+
 pub const SECONDS_IN_YEAR: u128 = 365 * 24 * 3600;
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
@@ -22,7 +26,9 @@ pub struct Config {
     pub market_cap: Option<Uint128>,
     /// Interest rate calculation
     pub rates: ValidatedInterest,
+    /// How often (in seconds) the interest rate compounds. Interest accrues every second.
     pub interest_charge_period: u64,
+    /// Time (in seconds) interest was last charged at
     pub last_charged: u64,
     /// Denom common amongst markets within same Credit Agency
     pub common_token: String,
@@ -32,13 +38,141 @@ pub struct Config {
     /// Address of Credit Agency
     pub credit_agency: Addr,
     pub reserve_factor: Decimal,
+    /// Portion of every borrowed amount added to the debt and converted into reserves
+    #[serde(default)]
+    pub origination_fee: Decimal,
+    /// Optional haircut applied when valuing large collateral positions
+    #[serde(default)]
+    pub liquidity_haircut: Option<LiquidityHaircut>,
+    /// Portion of the reserves accrued by an `AccrueInterest` call paid out to the caller
+    #[serde(default)]
+    pub accrual_reward: Decimal,
+    /// Portion of the funds distributed with `DistributeAsLTokens` converted into reserves
+    #[serde(default)]
+    pub distribution_reserve_share: Decimal,
+    /// Stable-rate borrowing parameters, disabled if not set
+    #[serde(default)]
+    pub stable_borrowing: Option<StableBorrowing>,
+    /// Term lending parameters, disabled if not set
+    #[serde(default)]
+    pub term_lending: Option<TermLending>,
+    /// Buckets the reserves are split between, reserves stay unallocated if empty
+    #[serde(default)]
+    pub reserve_split: Vec<ReserveBucket>,
+    /// Deprecated markets take no new deposits or borrows
+    #[serde(default)]
+    pub deprecated: bool,
+    /// If not set, the market token can be supplied but never borrowed
+    #[serde(default = "default_enabled")]
+    pub borrowing_enabled: bool,
+    /// If not set, deposits into this market never count towards the credit line
+    #[serde(default = "default_enabled")]
+    pub collateral_enabled: bool,
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
 pub const RESERVE: Item<Uint128> = Item::new("reserve");
+/// Part of `RESERVE` allocated to every bucket of the reserve split
+pub const RESERVE_BUCKETS: Map<&str, Uint128> = Map::new("reserve_buckets");
+/// Cumulative growth of debt since the market was created, starting from 1. Missing in
+/// markets created before it was introduced, which is equivalent to 1.
+pub const BORROW_INDEX: Item<Decimal> = Item::new("borrow_index");
+/// Cumulative growth of L-Tokens since the market was created, starting from 1. Missing in
+/// markets created before it was introduced, which is equivalent to 1.
+pub const SUPPLY_INDEX: Item<Decimal> = Item::new("supply_index");
+/// Current rate at target of an adaptive interest rate curve. Missing until the curve drifts
+/// for the first time, which is equivalent to its initial rate at target.
+pub const RATE_AT_TARGET: Item<Decimal> = Item::new("rate_at_target");
+
+/// Linear transition of a risk parameter from one value to another
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct Ramp<T> {
+    pub from: T,
+    pub to: T,
+    /// Time the ramp was scheduled at
+    pub start: Timestamp,
+    /// Time the parameter reaches `to`
+    pub end: Expiration,
+}
+
+/// Collateral ratio ramp in progress. `Config::collateral_ratio` is updated once it ends.
+pub const COLLATERAL_RATIO_RAMP: Item<Ramp<Decimal>> = Item::new("collateral_ratio_ramp");
+/// Interest rates ramp in progress. `Config::rates` is updated once it ends.
+pub const INTEREST_RATES_RAMP: Item<Ramp<ValidatedInterest>> = Item::new("interest_rates_ramp");
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct TokensInfo {
     pub ltoken: TokenInfoResponse,
     pub btoken: TokenInfoResponse,
 }
+
+/// Debt with a locked interest rate, accruing outside of the B Token supply
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, JsonSchema, Debug)]
+pub struct StableDebt {
+    /// Debt as of `last_update`
+    pub amount: Uint128,
+    /// Yearly interest rate the debt accrues with
+    pub rate: Decimal,
+    /// Time (in seconds) `amount` was last updated at
+    pub last_update: u64,
+}
+
+/// Stable-rate debt of every account
+pub const STABLE_DEBTS: Map<&Addr, StableDebt> = Map::new("stable_debts");
+/// Sum of all stable-rate debts and term loans (without penalties), accruing with their
+/// average rate. Updated by the exact amounts added to and removed from single debts.
+pub const STABLE_TOTAL: Item<StableDebt> = Item::new("stable_total");
+
+/// Loan with a rate fixed at origination, due at `maturity`
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct TermLoan {
+    pub id: u64,
+    /// Principal with interest as of `last_update`
+    pub amount: Uint128,
+    /// Yearly interest rate the loan accrues with
+    pub rate: Decimal,
+    /// Yearly rate additionally charged after maturity
+    pub penalty_rate: Decimal,
+    /// Penalty interest accrued as of `last_update`
+    pub penalty: Uint128,
+    /// Time (in seconds) the loan is due at
+    pub maturity: u64,
+    /// Time (in seconds) `amount` and `penalty` were last updated at
+    pub last_update: u64,
+}
+
+/// Term loans of every account, by loan id
+pub const TERM_LOANS: Map<(&Addr, u64), TermLoan> = Map::new("term_loans");
+/// Number of open term loans of every account. Unlike `TERM_LOANS`, iterates in address order.
+pub const TERM_BORROWERS: Map<&Addr, u32> = Map::new("term_borrowers");
+/// Id of the next term loan
+pub const NEXT_TERM_LOAN_ID: Item<u64> = Item::new("next_term_loan_id");
+
+/// Market tokens an account moved in and out of the market, the interest being the difference
+/// to its token balances
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, JsonSchema, Debug)]
+pub struct CostBasis {
+    /// Deposited for L-Tokens, including liquidation fees and L-Tokens received in transfers
+    pub deposited: Uint128,
+    /// Withdrawn by burning L-Tokens, including collateral seized in liquidations and L-Tokens
+    /// transferred away
+    pub withdrawn: Uint128,
+    /// Borrowed for B-Tokens, without the origination fee
+    pub borrowed: Uint128,
+    /// Repaid by burning B-Tokens, including repayments in liquidations
+    pub repaid: Uint128,
+}
+
+/// Cost basis of every account. Missing for accounts which did not interact with the market
+/// since it was introduced.
+pub const COST_BASES: Map<&Addr, CostBasis> = Map::new("cost_bases");
+
+/// Number of most recent rate snapshots kept in `RATE_HISTORY`
+pub const RATE_HISTORY_CAPACITY: u64 = 1000;
+/// Minimum time (in seconds) between two rate snapshots, so frequent interest charges can't
+/// push older snapshots out of `RATE_HISTORY`
+pub const RATE_HISTORY_MIN_INTERVAL: u64 = 3600;
+/// Rate snapshot recorded on interest charges, by id
+pub const RATE_HISTORY: Map<u64, RateSnapshot> = Map::new("rate_history");
+/// Id of the next rate snapshot
+pub const NEXT_RATE_SNAPSHOT_ID: Item<u64> = Item::new("next_rate_snapshot_id");
@@ -0,0 +1,215 @@
+use cosmwasm_std::{Addr, Decimal, Env, Order, StdResult, Storage, Uint128};
+
+use crate::{
+    contract::Deps,
+    interest::compounded_growth,
+    msg::TermLending,
+    stable,
+    state::{Config, TermLoan, NEXT_TERM_LOAN_ID, STABLE_TOTAL, TERM_BORROWERS, TERM_LOANS},
+    ContractError,
+};
+
+/// Part of a payment applied to term loans
+#[derive(Default)]
+pub struct Repayment {
+    /// Total amount used, including penalties and fees
+    pub repaid: Uint128,
+    /// Penalties and fees, which go to the reserve
+    pub to_reserve: Uint128,
+}
+
+impl TermLoan {
+    /// Principal with interest, and penalty accrued until `now` (in seconds)
+    pub fn amounts_at(&self, cfg: &Config, now: u64) -> Result<(Uint128, Uint128), ContractError> {
+        let growth = compounded_growth(
+            self.rate,
+            cfg.interest_charge_period,
+            now.saturating_sub(self.last_update),
+        )?;
+        let amount = self.amount * growth;
+
+        let overdue = now.saturating_sub(std::cmp::max(self.maturity, self.last_update));
+        let penalty_growth =
+            compounded_growth(self.penalty_rate, cfg.interest_charge_period, overdue)?;
+        let penalty = self.penalty + amount * (penalty_growth - Decimal::one());
+
+        Ok((amount, penalty))
+    }
+
+    /// Whole debt of the loan at `now`
+    pub fn debt_at(&self, cfg: &Config, now: u64) -> Result<Uint128, ContractError> {
+        let (amount, penalty) = self.amounts_at(cfg, now)?;
+        Ok(amount + penalty)
+    }
+
+    pub fn is_overdue(&self, now: u64) -> bool {
+        now > self.maturity
+    }
+}
+
+/// Returns term lending parameters, failing if it is disabled
+pub fn params(cfg: &Config) -> Result<&TermLending, ContractError> {
+    cfg.term_lending
+        .as_ref()
+        .ok_or(ContractError::TermLendingDisabled {})
+}
+
+/// Rate quoted for a term loan of `amount`
+pub fn quote(
+    deps: Deps,
+    env: &Env,
+    cfg: &Config,
+    amount: Uint128,
+) -> Result<Decimal, ContractError> {
+    let params = params(cfg)?;
+    let (_, variable) = stable::variable_rate_after(deps, env, cfg, amount)?;
+    Ok(variable + params.premium)
+}
+
+/// All term loans of `account`, ordered by id
+pub fn loans(storage: &dyn Storage, account: &Addr) -> Result<Vec<TermLoan>, ContractError> {
+    TERM_LOANS
+        .prefix(account)
+        .range(storage, None, None, Order::Ascending)
+        .map(|loan| Ok(loan?.1))
+        .collect()
+}
+
+pub fn load(storage: &dyn Storage, account: &Addr, id: u64) -> Result<TermLoan, ContractError> {
+    TERM_LOANS
+        .may_load(storage, (account, id))?
+        .ok_or_else(|| ContractError::TermLoanNotFound {
+            account: account.to_string(),
+            id,
+        })
+}
+
+/// Sum of all term loans of `account` at `now`
+pub fn debt(
+    storage: &dyn Storage,
+    cfg: &Config,
+    account: &Addr,
+    now: u64,
+) -> Result<Uint128, ContractError> {
+    loans(storage, account)?
+        .iter()
+        .try_fold(Uint128::zero(), |sum, loan| {
+            Ok(sum + loan.debt_at(cfg, now)?)
+        })
+}
+
+/// Records a loan of `amount` taken by `account` at `rate`, due at `maturity`
+pub fn borrow(
+    storage: &mut dyn Storage,
+    cfg: &Config,
+    now: u64,
+    account: &Addr,
+    amount: Uint128,
+    rate: Decimal,
+    maturity: u64,
+) -> Result<TermLoan, ContractError> {
+    let params = params(cfg)?;
+    let id = NEXT_TERM_LOAN_ID.may_load(storage)?.unwrap_or(1);
+    NEXT_TERM_LOAN_ID.save(storage, &(id + 1))?;
+
+    let loan = TermLoan {
+        id,
+        amount,
+        rate,
+        penalty_rate: params.penalty_rate,
+        penalty: Uint128::zero(),
+        maturity,
+        last_update: now,
+    };
+    TERM_LOANS.save(storage, (account, id), &loan)?;
+    TERM_BORROWERS.update(storage, account, |count| -> StdResult<_> {
+        Ok(count.unwrap_or_default() + 1)
+    })?;
+
+    let mut total_debt = stable::total(storage)?;
+    total_debt.add(cfg, now, amount, rate)?;
+    STABLE_TOTAL.save(storage, &total_debt)?;
+    Ok(loan)
+}
+
+/// Repays up to `amount` of the loan, penalty first. The principal repaid before maturity is
+/// charged `early_fee` on top of it.
+pub fn repay(
+    storage: &mut dyn Storage,
+    cfg: &Config,
+    now: u64,
+    account: &Addr,
+    mut loan: TermLoan,
+    amount: Uint128,
+    early_fee: Decimal,
+) -> Result<Repayment, ContractError> {
+    let (principal, penalty) = loan.amounts_at(cfg, now)?;
+
+    let penalty_paid = std::cmp::min(amount, penalty);
+    let fee_rate = if now < loan.maturity {
+        early_fee
+    } else {
+        Decimal::zero()
+    };
+    let principal_paid = std::cmp::min(
+        principal,
+        (amount - penalty_paid).multiply_ratio(
+            Decimal::one().atomics(),
+            (Decimal::one() + fee_rate).atomics(),
+        ),
+    );
+    let fee = principal_paid * fee_rate;
+
+    loan.amount = principal - principal_paid;
+    loan.penalty = penalty - penalty_paid;
+    loan.last_update = now;
+    if loan.amount.is_zero() && loan.penalty.is_zero() {
+        TERM_LOANS.remove(storage, (account, loan.id));
+        match TERM_BORROWERS.load(storage, account)? {
+            1 => TERM_BORROWERS.remove(storage, account),
+            count => TERM_BORROWERS.save(storage, account, &(count - 1))?,
+        }
+    } else {
+        TERM_LOANS.save(storage, (account, loan.id), &loan)?;
+    }
+
+    let mut total_debt = stable::total(storage)?;
+    total_debt.remove(cfg, now, principal_paid, loan.rate)?;
+    stable::save_total(storage, &total_debt)?;
+
+    Ok(Repayment {
+        repaid: penalty_paid + principal_paid + fee,
+        to_reserve: penalty_paid + fee,
+    })
+}
+
+/// Repays `loans` in order with up to `amount`, without early repayment fees
+pub fn repay_many(
+    storage: &mut dyn Storage,
+    cfg: &Config,
+    now: u64,
+    account: &Addr,
+    loans: Vec<TermLoan>,
+    amount: Uint128,
+) -> Result<Repayment, ContractError> {
+    let mut result = Repayment::default();
+    for loan in loans {
+        let left = amount - result.repaid;
+        if left.is_zero() {
+            break;
+        }
+        let repayment = repay(storage, cfg, now, account, loan, left, Decimal::zero())?;
+        result.repaid += repayment.repaid;
+        result.to_reserve += repayment.to_reserve;
+    }
+    Ok(result)
+}
+
+impl TermLending {
+    pub fn validate(&self) -> Result<(), ContractError> {
+        if self.max_duration == 0 || self.early_repayment_fee > Decimal::one() {
+            return Err(ContractError::InvalidTermLending {});
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,22 @@
+use std::env::current_dir;
+use std::fs::create_dir_all;
+
+use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
+
+use isotonic_mock_oracle::msg::{
+    ExecuteMsg, InstantiateMsg, PriceResponse, PriceWithTimestampResponse, QueryMsg, RouteResponse,
+};
+
+fn main() {
+    let mut out_dir = current_dir().unwrap();
+    out_dir.push("schema");
+    create_dir_all(&out_dir).unwrap();
+    remove_schemas(&out_dir).unwrap();
+
+    export_schema(&schema_for!(InstantiateMsg), &out_dir);
+    export_schema(&schema_for!(ExecuteMsg), &out_dir);
+    export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(PriceResponse), &out_dir);
+    export_schema(&schema_for!(PriceWithTimestampResponse), &out_dir);
+    export_schema(&schema_for!(RouteResponse), &out_dir);
+}
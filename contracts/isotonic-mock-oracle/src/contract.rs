@@ -0,0 +1,306 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{to_binary, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Response};
+use cw2::set_contract_version;
+
+use crate::error::ContractError;
+use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::state::{MockPrice, POOLS, PRICES};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:isotonic-mock-oracle";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    _msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "instantiate")
+        .add_attribute("owner", info.sender))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    use ExecuteMsg::*;
+    match msg {
+        SetPrice { sell, buy, rate } => exec::set_price(deps, env, &sell, &buy, rate),
+        SetPriceError {
+            denom1,
+            denom2,
+            error,
+        } => exec::set_price_error(deps, &denom1, &denom2, error),
+        SetPool {
+            pool_id,
+            denom1,
+            denom2,
+        } => exec::set_pool(deps, pool_id, &denom1, &denom2),
+    }
+}
+
+fn sorted_tuple<'a>(denom1: &'a str, denom2: &'a str) -> (&'a str, &'a str) {
+    if denom1 < denom2 {
+        (denom1, denom2)
+    } else {
+        (denom2, denom1)
+    }
+}
+
+mod exec {
+    use super::*;
+
+    pub fn set_price(
+        deps: DepsMut,
+        env: Env,
+        sell: &str,
+        buy: &str,
+        rate: Decimal,
+    ) -> Result<Response, ContractError> {
+        let updated_at = env.block.time;
+        PRICES.save(
+            deps.storage,
+            (sell, buy),
+            &MockPrice::Rate { rate, updated_at },
+        )?;
+
+        match Decimal::one().checked_div(rate) {
+            Ok(inverted) => PRICES.save(
+                deps.storage,
+                (buy, sell),
+                &MockPrice::Rate {
+                    rate: inverted,
+                    updated_at,
+                },
+            )?,
+            Err(_) => PRICES.remove(deps.storage, (buy, sell)),
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "set_price")
+            .add_attribute("sell", sell)
+            .add_attribute("buy", buy)
+            .add_attribute("rate", rate.to_string()))
+    }
+
+    pub fn set_price_error(
+        deps: DepsMut,
+        denom1: &str,
+        denom2: &str,
+        error: String,
+    ) -> Result<Response, ContractError> {
+        let price = MockPrice::Error { error };
+        PRICES.save(deps.storage, (denom1, denom2), &price)?;
+        PRICES.save(deps.storage, (denom2, denom1), &price)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "set_price_error")
+            .add_attribute("denom1", denom1)
+            .add_attribute("denom2", denom2))
+    }
+
+    pub fn set_pool(
+        deps: DepsMut,
+        pool_id: u64,
+        denom1: &str,
+        denom2: &str,
+    ) -> Result<Response, ContractError> {
+        POOLS.save(deps.storage, sorted_tuple(denom1, denom2), &pool_id)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "set_pool")
+            .add_attribute("pool_id", pool_id.to_string()))
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+    use QueryMsg::*;
+
+    let res = match msg {
+        Price { sell, buy } => to_binary(&query::price(deps, &sell, &buy)?)?,
+        PriceWithTimestamp { sell, buy } => {
+            to_binary(&query::price_with_timestamp(deps, &sell, &buy)?)?
+        }
+        Route { sell, buy } => to_binary(&query::route(deps, sell, buy)?)?,
+        PoolId { denom1, denom2 } => to_binary(&query::pool_id(deps, &denom1, &denom2)?)?,
+    };
+
+    Ok(res)
+}
+
+mod query {
+    use crate::msg::{PriceResponse, PriceWithTimestampResponse, RouteResponse, RouteStep};
+
+    use super::*;
+
+    pub fn price_with_timestamp(
+        deps: Deps,
+        sell: &str,
+        buy: &str,
+    ) -> Result<PriceWithTimestampResponse, ContractError> {
+        match PRICES.may_load(deps.storage, (sell, buy))? {
+            Some(MockPrice::Rate { rate, updated_at }) => {
+                Ok(PriceWithTimestampResponse { rate, updated_at })
+            }
+            Some(MockPrice::Error { error }) => Err(ContractError::Mocked(error)),
+            None => Err(ContractError::NoInfo {
+                denom1: sell.to_owned(),
+                denom2: buy.to_owned(),
+            }),
+        }
+    }
+
+    pub fn price(deps: Deps, sell: &str, buy: &str) -> Result<PriceResponse, ContractError> {
+        let PriceWithTimestampResponse { rate, .. } = price_with_timestamp(deps, sell, buy)?;
+        Ok(PriceResponse { rate })
+    }
+
+    pub fn route(deps: Deps, sell: String, buy: String) -> Result<RouteResponse, ContractError> {
+        let pool_id = pool_id(deps, &sell, &buy)?;
+
+        Ok(RouteResponse {
+            route: vec![RouteStep {
+                pool_id,
+                denom_in: sell,
+                denom_out: buy,
+            }],
+        })
+    }
+
+    pub fn pool_id(deps: Deps, denom1: &str, denom2: &str) -> Result<u64, ContractError> {
+        POOLS
+            .may_load(deps.storage, sorted_tuple(denom1, denom2))?
+            .ok_or_else(|| ContractError::NoInfo {
+                denom1: denom1.to_owned(),
+                denom2: denom2.to_owned(),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+
+    use crate::msg::PriceWithTimestampResponse;
+
+    fn set_price(deps: DepsMut, sell: &str, buy: &str, rate: Decimal) {
+        execute(
+            deps,
+            mock_env(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::SetPrice {
+                sell: sell.to_owned(),
+                buy: buy.to_owned(),
+                rate,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn set_price_both_ways() {
+        let mut deps = mock_dependencies();
+        set_price(deps.as_mut(), "ATOM", "OSMO", Decimal::percent(200));
+
+        assert_eq!(
+            query::price(deps.as_ref(), "ATOM", "OSMO").unwrap().rate,
+            Decimal::percent(200)
+        );
+        assert_eq!(
+            query::price(deps.as_ref(), "OSMO", "ATOM").unwrap().rate,
+            Decimal::percent(50)
+        );
+        assert_eq!(
+            query::price_with_timestamp(deps.as_ref(), "ATOM", "OSMO").unwrap(),
+            PriceWithTimestampResponse {
+                rate: Decimal::percent(200),
+                updated_at: mock_env().block.time,
+            }
+        );
+    }
+
+    #[test]
+    fn zero_price() {
+        let mut deps = mock_dependencies();
+        set_price(deps.as_mut(), "ATOM", "OSMO", Decimal::percent(200));
+        set_price(deps.as_mut(), "ATOM", "OSMO", Decimal::zero());
+
+        assert_eq!(
+            query::price(deps.as_ref(), "ATOM", "OSMO").unwrap().rate,
+            Decimal::zero()
+        );
+        assert_eq!(
+            query::price(deps.as_ref(), "OSMO", "ATOM").unwrap_err(),
+            ContractError::NoInfo {
+                denom1: "OSMO".to_owned(),
+                denom2: "ATOM".to_owned(),
+            }
+        );
+    }
+
+    #[test]
+    fn price_error() {
+        let mut deps = mock_dependencies();
+        set_price(deps.as_mut(), "ATOM", "OSMO", Decimal::percent(200));
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::SetPriceError {
+                denom1: "OSMO".to_owned(),
+                denom2: "ATOM".to_owned(),
+                error: "Oracle is down".to_owned(),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            query::price(deps.as_ref(), "ATOM", "OSMO").unwrap_err(),
+            ContractError::Mocked("Oracle is down".to_owned())
+        );
+        assert_eq!(
+            query::price(deps.as_ref(), "OSMO", "ATOM").unwrap_err(),
+            ContractError::Mocked("Oracle is down".to_owned())
+        );
+    }
+
+    #[test]
+    fn pools() {
+        let mut deps = mock_dependencies();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::SetPool {
+                pool_id: 3,
+                denom1: "OSMO".to_owned(),
+                denom2: "ATOM".to_owned(),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(query::pool_id(deps.as_ref(), "ATOM", "OSMO").unwrap(), 3);
+        assert_eq!(
+            query::route(deps.as_ref(), "ATOM".to_owned(), "OSMO".to_owned())
+                .unwrap()
+                .route[0]
+                .pool_id,
+            3
+        );
+        query::pool_id(deps.as_ref(), "ATOM", "BTC").unwrap_err();
+    }
+}
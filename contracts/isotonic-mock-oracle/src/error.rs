@@ -0,0 +1,14 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("There is no info about the prices for this trading pair: {denom1}, {denom2}")]
+    NoInfo { denom1: String, denom2: String },
+
+    #[error("{0}")]
+    Mocked(String),
+}
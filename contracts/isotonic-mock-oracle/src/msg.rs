@@ -0,0 +1,49 @@
+use cosmwasm_std::Decimal;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub use utils::oracle::{PriceResponse, PriceWithTimestampResponse, RouteResponse, RouteStep};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Sets the price of `sell` in `buy`. Unless the rate is zero, the reverse pair is
+    /// set to the inverted rate. A zero rate removes the price of the reverse pair.
+    /// Callable by anyone.
+    SetPrice {
+        sell: String,
+        buy: String,
+        rate: Decimal,
+    },
+    /// Makes price queries for the trading pair (in both directions) fail with the given error.
+    /// Callable by anyone.
+    SetPriceError {
+        denom1: String,
+        denom2: String,
+        error: String,
+    },
+    /// Sets the pool returned by `PoolId` and `Route` queries for the trading pair.
+    /// The order of denoms doesn't matter. Callable by anyone.
+    SetPool {
+        pool_id: u64,
+        denom1: String,
+        denom2: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// Queries the exchange rate between two denoms. Returns `PriceResponse`
+    Price { sell: String, buy: String },
+    /// Same as `Price`, with the time the price was set at. Returns `PriceWithTimestampResponse`
+    PriceWithTimestamp { sell: String, buy: String },
+    /// Queries the swap route between two denoms - a single swap through the pool set with
+    /// `SetPool`. Returns `RouteResponse`
+    Route { sell: String, buy: String },
+    /// Queries the pool set with `SetPool` for the trading pair
+    PoolId { denom1: String, denom2: String },
+}
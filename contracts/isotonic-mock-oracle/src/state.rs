@@ -0,0 +1,21 @@
+use cosmwasm_std::{Decimal, Timestamp};
+use cw_storage_plus::Map;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum MockPrice {
+    Rate {
+        rate: Decimal,
+        updated_at: Timestamp,
+    },
+    Error {
+        error: String,
+    },
+}
+
+/// Prices by (sell, buy) denoms
+pub const PRICES: Map<(&str, &str), MockPrice> = Map::new("prices");
+/// Pools by denoms, given in ascending order
+pub const POOLS: Map<(&str, &str), u64> = Map::new("pools");
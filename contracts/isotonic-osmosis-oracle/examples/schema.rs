@@ -3,7 +3,10 @@ use std::fs::create_dir_all;
 
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
-use isotonic_osmosis_oracle::msg::{ExecuteMsg, InstantiateMsg, PriceResponse, QueryMsg};
+use isotonic_osmosis_oracle::msg::{
+    ExecuteMsg, InstantiateMsg, ListPoolsResponse, PriceResponse, PriceWithTimestampResponse,
+    QueryMsg, RouteResponse,
+};
 
 fn main() {
     let mut out_dir = current_dir().unwrap();
@@ -15,4 +18,7 @@ fn main() {
     export_schema(&schema_for!(ExecuteMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
     export_schema(&schema_for!(PriceResponse), &out_dir);
+    export_schema(&schema_for!(PriceWithTimestampResponse), &out_dir);
+    export_schema(&schema_for!(RouteResponse), &out_dir);
+    export_schema(&schema_for!(ListPoolsResponse), &out_dir);
 }
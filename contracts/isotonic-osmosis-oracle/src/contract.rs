@@ -50,28 +50,77 @@ pub fn execute(
             denom1,
             denom2,
         } => exec::register_pool(deps, info, pool_id, &denom1, &denom2),
+        DeregisterPool { denom1, denom2 } => exec::deregister_pool(deps, info, &denom1, &denom2),
+        UpdatePool {
+            pool_id,
+            denom1,
+            denom2,
+        } => exec::update_pool(deps, info, pool_id, &denom1, &denom2),
+        ProposeController { new_controller } => {
+            let new_controller = deps.api.addr_validate(&new_controller)?;
+            exec::propose_controller(deps, info, new_controller)
+        }
+        AcceptController {} => exec::accept_controller(deps, info),
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     use QueryMsg::*;
 
     let res = match msg {
         Price { sell, buy } => to_binary(&query::price(deps, sell, buy)?)?,
+        PriceWithTimestamp { sell, buy } => {
+            to_binary(&query::price_with_timestamp(deps, env, sell, buy)?)?
+        }
+        Route { sell, buy } => to_binary(&query::route(deps, sell, buy)?)?,
         PoolId { denom1, denom2 } => to_binary(&query::pool_id(deps, &denom1, &denom2)?)?,
+        ListPools { start_after, limit } => {
+            to_binary(&query::list_pools(deps, start_after, limit)?)?
+        }
     };
 
     Ok(res)
 }
 
 mod exec {
-    use cosmwasm_std::ensure_eq;
+    use cosmwasm_std::{ensure_eq, Addr, QueryRequest};
+    use osmo_bindings::PoolStateResponse;
 
-    use crate::state::POOLS;
+    use crate::state::{PENDING_CONTROLLER, POOLS};
 
     use super::*;
 
+    fn ensure_controller(deps: Deps, info: &MessageInfo) -> Result<Config, ContractError> {
+        let cfg = CONFIG.load(deps.storage)?;
+        ensure_eq!(info.sender, cfg.controller, ContractError::Unauthorized {});
+        Ok(cfg)
+    }
+
+    /// Makes sure the Osmosis pool actually trades both denoms
+    fn validate_pool(
+        deps: Deps,
+        pool_id: u64,
+        denom1: &str,
+        denom2: &str,
+    ) -> Result<(), ContractError> {
+        let pool: PoolStateResponse =
+            deps.querier
+                .query(&QueryRequest::Custom(OsmosisQuery::PoolState {
+                    id: pool_id,
+                }))?;
+
+        if denom1 == denom2 || !pool.has_denom(denom1) || !pool.has_denom(denom2) {
+            return Err(ContractError::InvalidPool {
+                pool_id,
+                denom1: denom1.to_owned(),
+                denom2: denom2.to_owned(),
+            });
+        }
+
+        Ok(())
+    }
+
     pub fn register_pool(
         deps: DepsMut,
         info: MessageInfo,
@@ -79,8 +128,8 @@ mod exec {
         denom1: &str,
         denom2: &str,
     ) -> Result<Response, ContractError> {
-        let cfg = CONFIG.load(deps.storage)?;
-        ensure_eq!(info.sender, cfg.controller, ContractError::Unauthorized {});
+        let cfg = ensure_controller(deps.as_ref(), &info)?;
+        validate_pool(deps.as_ref(), pool_id, denom1, denom2)?;
 
         POOLS.save(deps.storage, sorted_tuple(denom1, denom2), &pool_id)?;
 
@@ -89,13 +138,90 @@ mod exec {
             .add_attribute("sender", info.sender)
             .add_attribute("controller", cfg.controller))
     }
+
+    pub fn deregister_pool(
+        deps: DepsMut,
+        info: MessageInfo,
+        denom1: &str,
+        denom2: &str,
+    ) -> Result<Response, ContractError> {
+        ensure_controller(deps.as_ref(), &info)?;
+
+        let pool_id = query::pool_id(deps.as_ref(), denom1, denom2)?;
+        POOLS.remove(deps.storage, sorted_tuple(denom1, denom2));
+
+        Ok(Response::new()
+            .add_attribute("action", "deregister_pool")
+            .add_attribute("sender", info.sender)
+            .add_attribute("pool_id", pool_id.to_string()))
+    }
+
+    pub fn update_pool(
+        deps: DepsMut,
+        info: MessageInfo,
+        pool_id: u64,
+        denom1: &str,
+        denom2: &str,
+    ) -> Result<Response, ContractError> {
+        ensure_controller(deps.as_ref(), &info)?;
+
+        let old_pool_id = query::pool_id(deps.as_ref(), denom1, denom2)?;
+        validate_pool(deps.as_ref(), pool_id, denom1, denom2)?;
+
+        POOLS.save(deps.storage, sorted_tuple(denom1, denom2), &pool_id)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "update_pool")
+            .add_attribute("sender", info.sender)
+            .add_attribute("old_pool_id", old_pool_id.to_string())
+            .add_attribute("pool_id", pool_id.to_string()))
+    }
+
+    pub fn propose_controller(
+        deps: DepsMut,
+        info: MessageInfo,
+        new_controller: Addr,
+    ) -> Result<Response, ContractError> {
+        ensure_controller(deps.as_ref(), &info)?;
+
+        PENDING_CONTROLLER.save(deps.storage, &new_controller)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "propose_controller")
+            .add_attribute("sender", info.sender)
+            .add_attribute("new_controller", new_controller))
+    }
+
+    pub fn accept_controller(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+        let pending = PENDING_CONTROLLER
+            .may_load(deps.storage)?
+            .ok_or(ContractError::NoPendingController {})?;
+        ensure_eq!(info.sender, pending, ContractError::Unauthorized {});
+
+        CONFIG.update(deps.storage, |mut cfg| -> Result<_, ContractError> {
+            cfg.controller = pending;
+            Ok(cfg)
+        })?;
+        PENDING_CONTROLLER.remove(deps.storage);
+
+        Ok(Response::new()
+            .add_attribute("action", "accept_controller")
+            .add_attribute("controller", info.sender))
+    }
 }
 
 mod query {
-    use cosmwasm_std::QueryRequest;
+    use cosmwasm_std::{Order, QueryRequest, StdResult};
+    use cw_storage_plus::Bound;
     use osmo_bindings::{SpotPriceResponse, Swap};
 
-    use crate::{msg::PriceResponse, state::POOLS};
+    use crate::{
+        msg::{
+            ListPoolsResponse, PoolResponse, PriceResponse, PriceWithTimestampResponse,
+            RouteResponse, RouteStep,
+        },
+        state::POOLS,
+    };
 
     use super::*;
 
@@ -116,6 +242,32 @@ mod query {
         Ok(PriceResponse { rate: price.price })
     }
 
+    pub fn price_with_timestamp(
+        deps: Deps,
+        env: Env,
+        sell: String,
+        buy: String,
+    ) -> Result<PriceWithTimestampResponse, ContractError> {
+        let PriceResponse { rate } = price(deps, sell, buy)?;
+
+        Ok(PriceWithTimestampResponse {
+            rate,
+            updated_at: env.block.time,
+        })
+    }
+
+    pub fn route(deps: Deps, sell: String, buy: String) -> Result<RouteResponse, ContractError> {
+        let pool_id = pool_id(deps, &sell, &buy)?;
+
+        Ok(RouteResponse {
+            route: vec![RouteStep {
+                pool_id,
+                denom_in: sell,
+                denom_out: buy,
+            }],
+        })
+    }
+
     pub fn pool_id(deps: Deps, denom1: &str, denom2: &str) -> Result<u64, ContractError> {
         POOLS
             .may_load(deps.storage, sorted_tuple(denom1, denom2))?
@@ -124,6 +276,37 @@ mod query {
                 denom2: denom2.to_owned(),
             })
     }
+
+    // settings for pagination
+    const MAX_LIMIT: u32 = 30;
+    const DEFAULT_LIMIT: u32 = 10;
+
+    pub fn list_pools(
+        deps: Deps,
+        start_after: Option<(String, String)>,
+        limit: Option<u32>,
+    ) -> Result<ListPoolsResponse, ContractError> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start_after = start_after.map(|(denom1, denom2)| sorted_tuple(denom1, denom2));
+        let start = start_after
+            .as_ref()
+            .map(|(denom1, denom2)| Bound::exclusive((denom1.as_str(), denom2.as_str())));
+
+        let pools = POOLS
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|pool| {
+                let ((denom1, denom2), pool_id) = pool?;
+                Ok(PoolResponse {
+                    pool_id,
+                    denom1,
+                    denom2,
+                })
+            })
+            .collect::<StdResult<_>>()?;
+
+        Ok(ListPoolsResponse { pools })
+    }
 }
 
 #[cfg(test)]
@@ -133,6 +316,7 @@ mod tests {
     use super::*;
 
     use cosmwasm_std::{
+        from_binary,
         testing::{mock_env, mock_info},
         Order,
     };
@@ -143,15 +327,42 @@ mod tests {
         use std::collections::HashMap;
 
         use cosmwasm_std::{
+            coin,
             testing::{MockApi, MockQuerier, MockStorage},
-            OwnedDeps,
+            ContractResult, OwnedDeps, SystemError, SystemResult,
         };
+        use osmo_bindings::PoolStateResponse;
+
+        /// Mocked Osmosis pools: 2 and 4 are ATOM/OSMO pools, 3 is a BTC/OSMO pool
+        fn pool_state(id: u64) -> Option<PoolStateResponse> {
+            let assets = match id {
+                2 | 4 => vec![coin(100, "ATOM"), coin(100, "OSMO")],
+                3 => vec![coin(100, "BTC"), coin(100, "OSMO")],
+                _ => return None,
+            };
+
+            Some(PoolStateResponse {
+                assets,
+                shares: coin(100, format!("gamm/pool/{}", id)),
+            })
+        }
+
+        pub fn mock_dependencies(
+        ) -> OwnedDeps<MockStorage, MockApi, MockQuerier<OsmosisQuery>, OsmosisQuery> {
+            let querier = MockQuerier::new(&[]).with_custom_handler(|query| match query {
+                OsmosisQuery::PoolState { id } => match pool_state(*id) {
+                    Some(state) => SystemResult::Ok(ContractResult::Ok(to_binary(&state).unwrap())),
+                    None => SystemResult::Ok(ContractResult::Err(format!("pool {} not found", id))),
+                },
+                _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                    kind: "custom".to_owned(),
+                }),
+            });
 
-        pub fn mock_dependencies() -> OwnedDeps<MockStorage, MockApi, MockQuerier, OsmosisQuery> {
             OwnedDeps {
                 storage: MockStorage::default(),
                 api: MockApi::default(),
-                querier: MockQuerier::default(),
+                querier,
                 custom_query_type: std::marker::PhantomData,
             }
         }
@@ -242,4 +453,292 @@ mod tests {
 
         assert_eq!(Err(ContractError::Unauthorized {}), res);
     }
+
+    #[test]
+    fn register_pool_without_denoms() {
+        let mut deps = helpers::mock_dependencies();
+        helpers::instantiate_contract(deps.as_mut(), "admin");
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("admin", &[]),
+            ExecuteMsg::RegisterPool {
+                pool_id: 3,
+                denom1: "ATOM".to_owned(),
+                denom2: "OSMO".to_owned(),
+            },
+        );
+
+        assert_eq!(
+            Err(ContractError::InvalidPool {
+                pool_id: 3,
+                denom1: "ATOM".to_owned(),
+                denom2: "OSMO".to_owned(),
+            }),
+            res
+        );
+        assert!(helpers::list_pools(deps.as_ref()).is_empty());
+    }
+
+    #[test]
+    fn register_unknown_pool() {
+        let mut deps = helpers::mock_dependencies();
+        helpers::instantiate_contract(deps.as_mut(), "admin");
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("admin", &[]),
+            ExecuteMsg::RegisterPool {
+                pool_id: 7,
+                denom1: "ATOM".to_owned(),
+                denom2: "OSMO".to_owned(),
+            },
+        )
+        .unwrap_err();
+
+        assert!(helpers::list_pools(deps.as_ref()).is_empty());
+    }
+
+    #[test]
+    fn deregister_pool() {
+        let mut deps = helpers::mock_dependencies();
+        helpers::instantiate_contract(deps.as_mut(), "admin");
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("admin", &[]),
+            ExecuteMsg::RegisterPool {
+                pool_id: 2,
+                denom1: "ATOM".to_owned(),
+                denom2: "OSMO".to_owned(),
+            },
+        )
+        .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("eve", &[]),
+            ExecuteMsg::DeregisterPool {
+                denom1: "OSMO".to_owned(),
+                denom2: "ATOM".to_owned(),
+            },
+        );
+        assert_eq!(Err(ContractError::Unauthorized {}), res);
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("admin", &[]),
+            ExecuteMsg::DeregisterPool {
+                denom1: "OSMO".to_owned(),
+                denom2: "ATOM".to_owned(),
+            },
+        )
+        .unwrap();
+        assert!(helpers::list_pools(deps.as_ref()).is_empty());
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("admin", &[]),
+            ExecuteMsg::DeregisterPool {
+                denom1: "OSMO".to_owned(),
+                denom2: "ATOM".to_owned(),
+            },
+        );
+        assert_eq!(
+            Err(ContractError::NoInfo {
+                denom1: "OSMO".to_owned(),
+                denom2: "ATOM".to_owned(),
+            }),
+            res
+        );
+    }
+
+    #[test]
+    fn update_pool() {
+        let mut deps = helpers::mock_dependencies();
+        helpers::instantiate_contract(deps.as_mut(), "admin");
+
+        // only registered pairs can be updated
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("admin", &[]),
+            ExecuteMsg::UpdatePool {
+                pool_id: 3,
+                denom1: "BTC".to_owned(),
+                denom2: "OSMO".to_owned(),
+            },
+        );
+        assert_eq!(
+            Err(ContractError::NoInfo {
+                denom1: "BTC".to_owned(),
+                denom2: "OSMO".to_owned(),
+            }),
+            res
+        );
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("admin", &[]),
+            ExecuteMsg::RegisterPool {
+                pool_id: 2,
+                denom1: "ATOM".to_owned(),
+                denom2: "OSMO".to_owned(),
+            },
+        )
+        .unwrap();
+
+        // the new pool has to hold both denoms as well
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("admin", &[]),
+            ExecuteMsg::UpdatePool {
+                pool_id: 3,
+                denom1: "ATOM".to_owned(),
+                denom2: "OSMO".to_owned(),
+            },
+        );
+        assert_eq!(
+            Err(ContractError::InvalidPool {
+                pool_id: 3,
+                denom1: "ATOM".to_owned(),
+                denom2: "OSMO".to_owned(),
+            }),
+            res
+        );
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("eve", &[]),
+            ExecuteMsg::UpdatePool {
+                pool_id: 4,
+                denom1: "ATOM".to_owned(),
+                denom2: "OSMO".to_owned(),
+            },
+        );
+        assert_eq!(Err(ContractError::Unauthorized {}), res);
+
+        // the order of denoms doesn't matter
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("admin", &[]),
+            ExecuteMsg::UpdatePool {
+                pool_id: 4,
+                denom1: "OSMO".to_owned(),
+                denom2: "ATOM".to_owned(),
+            },
+        )
+        .unwrap();
+
+        let pool_id: u64 = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::PoolId {
+                    denom1: "ATOM".to_owned(),
+                    denom2: "OSMO".to_owned(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(pool_id, 4);
+        assert_eq!(helpers::list_pools(deps.as_ref()).len(), 1);
+    }
+
+    #[test]
+    fn transfer_controller() {
+        let mut deps = helpers::mock_dependencies();
+        helpers::instantiate_contract(deps.as_mut(), "admin");
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bob", &[]),
+            ExecuteMsg::AcceptController {},
+        );
+        assert_eq!(Err(ContractError::NoPendingController {}), res);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("eve", &[]),
+            ExecuteMsg::ProposeController {
+                new_controller: "eve".to_owned(),
+            },
+        );
+        assert_eq!(Err(ContractError::Unauthorized {}), res);
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("admin", &[]),
+            ExecuteMsg::ProposeController {
+                new_controller: "bob".to_owned(),
+            },
+        )
+        .unwrap();
+
+        // proposing doesn't change anything until accepted
+        assert_eq!(
+            CONFIG.load(&deps.storage).unwrap().controller,
+            cosmwasm_std::Addr::unchecked("admin")
+        );
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("eve", &[]),
+            ExecuteMsg::AcceptController {},
+        );
+        assert_eq!(Err(ContractError::Unauthorized {}), res);
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bob", &[]),
+            ExecuteMsg::AcceptController {},
+        )
+        .unwrap();
+
+        assert_eq!(
+            CONFIG.load(&deps.storage).unwrap().controller,
+            cosmwasm_std::Addr::unchecked("bob")
+        );
+
+        // the old controller lost its privileges
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("admin", &[]),
+            ExecuteMsg::RegisterPool {
+                pool_id: 2,
+                denom1: "ATOM".to_owned(),
+                denom2: "OSMO".to_owned(),
+            },
+        );
+        assert_eq!(Err(ContractError::Unauthorized {}), res);
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bob", &[]),
+            ExecuteMsg::RegisterPool {
+                pool_id: 2,
+                denom1: "ATOM".to_owned(),
+                denom2: "OSMO".to_owned(),
+            },
+        )
+        .unwrap();
+    }
 }
@@ -14,4 +14,14 @@ pub enum ContractError {
 
     #[error("There is no info about the prices for this trading pair: {denom1}, {denom2}")]
     NoInfo { denom1: String, denom2: String },
+
+    #[error("Pool {pool_id} does not hold both {denom1} and {denom2}")]
+    InvalidPool {
+        pool_id: u64,
+        denom1: String,
+        denom2: String,
+    },
+
+    #[error("There is no pending controller proposal")]
+    NoPendingController {},
 }
@@ -1,7 +1,8 @@
-use cosmwasm_std::Decimal;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+pub use utils::oracle::{PriceResponse, PriceWithTimestampResponse, RouteResponse, RouteStep};
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
     pub controller: String,
@@ -11,12 +12,26 @@ pub struct InstantiateMsg {
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
     /// Register an Osmosis liquidity pool for a given trading pair. Only callable by the controller.
-    /// The order of denoms doesn't matter.
+    /// The order of denoms doesn't matter. The pool is checked to actually hold both denoms.
     RegisterPool {
         pool_id: u64,
         denom1: String,
         denom2: String,
     },
+    /// Removes the pool registered for a given trading pair. Only callable by the controller.
+    DeregisterPool { denom1: String, denom2: String },
+    /// Points an already registered trading pair to a different Osmosis liquidity pool.
+    /// Only callable by the controller.
+    UpdatePool {
+        pool_id: u64,
+        denom1: String,
+        denom2: String,
+    },
+    /// Proposes a new controller. The change takes effect once the proposed address
+    /// sends `AcceptController`. Only callable by the controller.
+    ProposeController { new_controller: String },
+    /// Accepts the pending controller proposal. Only callable by the proposed controller.
+    AcceptController {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -27,14 +42,40 @@ pub enum QueryMsg {
         sell: String,
         buy: String,
     },
+    /// Same as `Price`. Spot prices are always up to date, so the timestamp is the current block time.
+    /// Returns `PriceWithTimestampResponse`
+    PriceWithTimestamp {
+        sell: String,
+        buy: String,
+    },
+    /// Queries the swap route between two denoms - a single swap through the registered pool.
+    /// Returns `RouteResponse`
+    Route {
+        sell: String,
+        buy: String,
+    },
     PoolId {
         denom1: String,
         denom2: String,
     },
+    /// Lists all registered pools. Pagination by the (ascending) pair of denoms.
+    /// Returns `ListPoolsResponse`
+    ListPools {
+        start_after: Option<(String, String)>,
+        limit: Option<u32>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct PoolResponse {
+    pub pool_id: u64,
+    pub denom1: String,
+    pub denom2: String,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
-pub struct PriceResponse {
-    pub rate: Decimal,
+pub struct ListPoolsResponse {
+    pub pools: Vec<PoolResponse>,
 }
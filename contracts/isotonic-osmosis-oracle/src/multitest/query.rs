@@ -1,6 +1,7 @@
 use cosmwasm_std::{coin, Decimal, StdError};
 
 use super::suite::SuiteBuilder;
+use crate::msg::{PoolResponse, RouteStep};
 
 #[test]
 fn query_pool_id_both_ways() {
@@ -28,6 +29,34 @@ fn query_price_both_ways() {
     );
 }
 
+#[test]
+fn query_price_with_timestamp() {
+    let suite = SuiteBuilder::new()
+        .with_pool(2, (coin(100, "ATOM"), coin(200, "OSMO")))
+        .build();
+
+    let resp = suite.query_price_with_timestamp("ATOM", "OSMO").unwrap();
+    assert_eq!(resp.rate, Decimal::percent(200));
+    assert_eq!(resp.updated_at, suite.block_time());
+}
+
+#[test]
+fn query_route() {
+    let suite = SuiteBuilder::new()
+        .with_pool(2, (coin(100, "ATOM"), coin(200, "OSMO")))
+        .build();
+
+    assert_eq!(
+        suite.query_route("OSMO", "ATOM").unwrap(),
+        vec![RouteStep {
+            pool_id: 2,
+            denom_in: "OSMO".to_owned(),
+            denom_out: "ATOM".to_owned(),
+        }]
+    );
+    suite.query_route("OSMO", "BTC").unwrap_err();
+}
+
 #[test]
 fn query_unknown_pool() {
     let suite = SuiteBuilder::new().build();
@@ -40,3 +69,38 @@ fn query_unknown_pool() {
         err.downcast().unwrap()
     );
 }
+
+#[test]
+fn list_pools_paginated() {
+    let suite = SuiteBuilder::new()
+        .with_pool(1, (coin(100, "OSMO"), coin(100, "AKT")))
+        .with_pool(2, (coin(100, "BTC"), coin(100, "OSMO")))
+        .with_pool(3, (coin(100, "ETH"), coin(100, "OSMO")))
+        .build();
+
+    let pool = |pool_id, denom1: &str, denom2: &str| PoolResponse {
+        pool_id,
+        denom1: denom1.to_owned(),
+        denom2: denom2.to_owned(),
+    };
+
+    assert_eq!(
+        suite.list_pools(None, None).unwrap(),
+        vec![
+            pool(1, "AKT", "OSMO"),
+            pool(2, "BTC", "OSMO"),
+            pool(3, "ETH", "OSMO"),
+        ]
+    );
+
+    assert_eq!(
+        suite.list_pools(None, 2).unwrap(),
+        vec![pool(1, "AKT", "OSMO"), pool(2, "BTC", "OSMO")]
+    );
+
+    // start_after is order-insensitive, same as the pair lookups
+    assert_eq!(
+        suite.list_pools(Some(("OSMO", "BTC")), None).unwrap(),
+        vec![pool(3, "ETH", "OSMO")]
+    );
+}
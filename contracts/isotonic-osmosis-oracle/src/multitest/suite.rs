@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use anyhow::Result as AnyResult;
-use cosmwasm_std::{Addr, Coin, Decimal};
+use cosmwasm_std::{Addr, Coin, Decimal, Timestamp};
 use cw_multi_test::{Contract, ContractWrapper, Executor};
 use derivative::Derivative;
 use osmo_bindings::{OsmosisMsg, OsmosisQuery};
@@ -101,6 +101,10 @@ pub struct Suite {
 }
 
 impl Suite {
+    pub fn block_time(&self) -> Timestamp {
+        self.app.block_info().time
+    }
+
     pub fn query_price(&self, sell: &str, buy: &str) -> AnyResult<Decimal> {
         let resp: PriceResponse = self.app.wrap().query_wasm_smart(
             self.osmosis_oracle.clone(),
@@ -113,6 +117,34 @@ impl Suite {
         Ok(resp.rate)
     }
 
+    pub fn query_price_with_timestamp(
+        &self,
+        sell: &str,
+        buy: &str,
+    ) -> AnyResult<PriceWithTimestampResponse> {
+        let resp: PriceWithTimestampResponse = self.app.wrap().query_wasm_smart(
+            self.osmosis_oracle.clone(),
+            &QueryMsg::PriceWithTimestamp {
+                sell: sell.to_owned(),
+                buy: buy.to_owned(),
+            },
+        )?;
+
+        Ok(resp)
+    }
+
+    pub fn query_route(&self, sell: &str, buy: &str) -> AnyResult<Vec<RouteStep>> {
+        let resp: RouteResponse = self.app.wrap().query_wasm_smart(
+            self.osmosis_oracle.clone(),
+            &QueryMsg::Route {
+                sell: sell.to_owned(),
+                buy: buy.to_owned(),
+            },
+        )?;
+
+        Ok(resp.route)
+    }
+
     pub fn query_pool_id(&self, denom1: &str, denom2: &str) -> AnyResult<u64> {
         let resp: u64 = self.app.wrap().query_wasm_smart(
             self.osmosis_oracle.clone(),
@@ -124,4 +156,20 @@ impl Suite {
 
         Ok(resp)
     }
+
+    pub fn list_pools(
+        &self,
+        start_after: Option<(&str, &str)>,
+        limit: impl Into<Option<u32>>,
+    ) -> AnyResult<Vec<PoolResponse>> {
+        let resp: ListPoolsResponse = self.app.wrap().query_wasm_smart(
+            self.osmosis_oracle.clone(),
+            &QueryMsg::ListPools {
+                start_after: start_after.map(|(d1, d2)| (d1.to_owned(), d2.to_owned())),
+                limit: limit.into(),
+            },
+        )?;
+
+        Ok(resp.pools)
+    }
 }
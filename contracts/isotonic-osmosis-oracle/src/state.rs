@@ -17,5 +17,7 @@ pub struct PriceRecord {
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
+/// The controller proposed by `ProposeController`, waiting to be accepted
+pub const PENDING_CONTROLLER: Item<Addr> = Item::new("pending_controller");
 /// The list of all pools the oracle is aware of. The denoms are expected to be given in ascending order
 pub const POOLS: Map<(&str, &str), u64> = Map::new("prices");
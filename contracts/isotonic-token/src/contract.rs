@@ -1,17 +1,20 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    coin, to_binary, Addr, BankMsg, Binary, Coin, Decimal, Deps, DepsMut, Env, MessageInfo,
-    Response, StdResult, SubMsg, Uint128,
+    coin, to_binary, Addr, BankMsg, Binary, Coin, Decimal, Deps, DepsMut, Env, MessageInfo, Order,
+    Response, StdResult, SubMsg, Uint128, WasmMsg,
 };
 use cw2::set_contract_version;
 use cw20::Cw20ReceiveMsg;
+use cw_storage_plus::Bound;
+use utils::events::{Burn, Distribute, Mint, Rebase, Transfer, TypedEvent};
 
 use crate::display_amount::DisplayAmount;
 use crate::error::ContractError;
 use crate::msg::{
-    BalanceResponse, ControllerQuery, ExecuteMsg, FundsResponse, InstantiateMsg,
-    MultiplierResponse, QueryMsg, TokenInfoResponse, TransferableAmountResp,
+    AllAccountsResponse, BalanceResponse, ControllerExecuteMsg, ControllerQuery, ExecuteMsg,
+    FundsResponse, InstantiateMsg, MultiplierResponse, QueryMsg, TokenInfoResponse,
+    TransferableAmountResp,
 };
 use crate::state::{
     Distribution, TokenInfo, WithdrawAdjustment, BALANCES, CONTROLLER, DISTRIBUTION, MULTIPLIER,
@@ -84,6 +87,25 @@ fn can_transfer(
     }
 }
 
+/// Notifies the controller that `amount` of tokens (display amount) moved from `sender` to
+/// `recipient`
+fn after_transfer(
+    deps: Deps,
+    sender: &Addr,
+    recipient: &Addr,
+    amount: Uint128,
+) -> StdResult<WasmMsg> {
+    Ok(WasmMsg::Execute {
+        contract_addr: CONTROLLER.load(deps.storage)?.to_string(),
+        msg: to_binary(&ControllerExecuteMsg::AfterTransfer {
+            sender: sender.to_string(),
+            recipient: recipient.to_string(),
+            amount,
+        })?,
+        funds: vec![],
+    })
+}
+
 /// Performs tokens transfer.
 fn transfer_tokens(
     mut deps: DepsMut,
@@ -122,7 +144,7 @@ fn transfer_tokens(
 
 /// Handler for `ExecuteMsg::Transfer`
 fn transfer(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     recipient: Addr,
@@ -136,22 +158,32 @@ fn transfer(
     )?;
 
     let multiplier = MULTIPLIER.load(deps.storage)?;
+    let display_amount = amount.display_amount();
     let amount = amount.to_stored_amount(multiplier);
 
-    transfer_tokens(deps, &info.sender, &recipient, amount, multiplier)?;
+    transfer_tokens(deps.branch(), &info.sender, &recipient, amount, multiplier)?;
+    let event = Transfer {
+        sender: info.sender.to_string(),
+        recipient: recipient.to_string(),
+        amount: display_amount,
+        stored_amount: amount,
+    };
+    let notify = after_transfer(deps.as_ref(), &info.sender, &recipient, display_amount)?;
 
     let res = Response::new()
         .add_attribute("action", "transfer")
         .add_attribute("from", info.sender)
         .add_attribute("to", recipient)
-        .add_attribute("amount", amount);
+        .add_attribute("amount", amount)
+        .add_event(event.to_event())
+        .add_message(notify);
 
     Ok(res)
 }
 
 /// Handler for `ExecuteMsg::TransferFrom`
 fn transfer_from(
-    deps: DepsMut,
+    mut deps: DepsMut,
     info: MessageInfo,
     sender: Addr,
     recipient: Addr,
@@ -163,22 +195,32 @@ fn transfer_from(
     }
 
     let multiplier = MULTIPLIER.load(deps.storage)?;
+    let display_amount = amount.display_amount();
     let amount = amount.to_stored_amount(multiplier);
 
-    transfer_tokens(deps, &sender, &recipient, amount, multiplier)?;
+    transfer_tokens(deps.branch(), &sender, &recipient, amount, multiplier)?;
+    let event = Transfer {
+        sender: sender.to_string(),
+        recipient: recipient.to_string(),
+        amount: display_amount,
+        stored_amount: amount,
+    };
+    let notify = after_transfer(deps.as_ref(), &sender, &recipient, display_amount)?;
 
     let res = Response::new()
         .add_attribute("action", "transfer")
         .add_attribute("from", sender)
         .add_attribute("to", recipient)
-        .add_attribute("amount", amount);
+        .add_attribute("amount", amount)
+        .add_event(event.to_event())
+        .add_message(notify);
 
     Ok(res)
 }
 
 /// Handler for `ExecuteMsg::Send`
 fn send(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     recipient: Addr,
@@ -193,15 +235,29 @@ fn send(
     )?;
 
     let multiplier = MULTIPLIER.load(deps.storage)?;
+    let display_amount = amount.display_amount();
     let amount = amount.to_stored_amount(multiplier);
 
-    transfer_tokens(deps, &info.sender, &recipient, amount, multiplier)?;
+    transfer_tokens(deps.branch(), &info.sender, &recipient, amount, multiplier)?;
+    let event = Transfer {
+        sender: info.sender.to_string(),
+        recipient: recipient.to_string(),
+        amount: display_amount,
+        stored_amount: amount,
+    };
 
     let res = Response::new()
         .add_attribute("action", "send")
         .add_attribute("from", &info.sender)
         .add_attribute("to", &recipient)
         .add_attribute("amount", amount)
+        .add_event(event.to_event())
+        .add_message(after_transfer(
+            deps.as_ref(),
+            &info.sender,
+            &recipient,
+            display_amount,
+        )?)
         .add_message(
             Cw20ReceiveMsg {
                 sender: info.sender.into(),
@@ -223,6 +279,7 @@ pub fn mint(
 ) -> Result<Response, ContractError> {
     let controller = CONTROLLER.load(deps.storage)?;
     let multiplier = MULTIPLIER.load(deps.storage)?;
+    let display_amount = amount.display_amount();
     let amount = amount.to_stored_amount(multiplier);
 
     if info.sender != controller {
@@ -250,8 +307,16 @@ pub fn mint(
 
     let res = Response::new()
         .add_attribute("action", "mint")
-        .add_attribute("to", recipient)
-        .add_attribute("amount", amount);
+        .add_attribute("to", &recipient)
+        .add_attribute("amount", amount)
+        .add_event(
+            Mint {
+                recipient,
+                amount: display_amount,
+                stored_amount: amount,
+            }
+            .to_event(),
+        );
     Ok(res)
 }
 
@@ -265,6 +330,7 @@ pub fn burn_from(
     let controller = CONTROLLER.load(deps.storage)?;
     let multiplier = MULTIPLIER.load(deps.storage)?;
     let owner = deps.api.addr_validate(&owner)?;
+    let display_amount = amount.display_amount();
     let amount = amount.to_stored_amount(multiplier);
 
     if info.sender != controller {
@@ -302,9 +368,17 @@ pub fn burn_from(
 
     let res = Response::new()
         .add_attribute("action", "burn_from")
-        .add_attribute("from", owner)
+        .add_attribute("from", &owner)
         .add_attribute("by", info.sender)
-        .add_attribute("amount", amount);
+        .add_attribute("amount", amount)
+        .add_event(
+            Burn {
+                owner: owner.to_string(),
+                amount: display_amount,
+                stored_amount: amount,
+            }
+            .to_event(),
+        );
     Ok(res)
 }
 
@@ -315,13 +389,14 @@ pub fn rebase(deps: DepsMut, info: MessageInfo, ratio: Decimal) -> Result<Respon
         return Err(ContractError::Unauthorized {});
     }
 
-    MULTIPLIER.update(deps.storage, |multiplier: Decimal| -> StdResult<_> {
+    let multiplier = MULTIPLIER.update(deps.storage, |multiplier: Decimal| -> StdResult<_> {
         Ok(multiplier * ratio)
     })?;
 
     let res = Response::new()
         .add_attribute("action", "rebase")
-        .add_attribute("ratio", ratio.to_string());
+        .add_attribute("ratio", ratio.to_string())
+        .add_event(Rebase { ratio, multiplier }.to_event());
 
     Ok(res)
 }
@@ -385,7 +460,15 @@ pub fn distribute(
         .add_attribute("action", "distribute_tokens")
         .add_attribute("sender", sender.as_str())
         .add_attribute("denom", &distribution.denom)
-        .add_attribute("amount", amount.to_string());
+        .add_attribute("amount", amount.to_string())
+        .add_event(
+            Distribute {
+                sender: sender.to_string(),
+                denom: distribution.denom,
+                amount: Uint128::new(amount),
+            }
+            .to_event(),
+        );
 
     Ok(resp)
 }
@@ -488,6 +571,30 @@ pub fn query_token_info(deps: Deps) -> StdResult<TokenInfoResponse> {
     })
 }
 
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+/// Handler for `QueryMsg::AllAccounts`
+pub fn query_all_accounts(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<AllAccountsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after.map(Addr::unchecked);
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    let accounts = BALANCES
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter(|balance| !matches!(balance, Ok((_, amount)) if amount.is_zero()))
+        .map(|balance| balance.map(|(addr, _)| addr.into_string()))
+        .take(limit)
+        .collect::<StdResult<_>>()?;
+
+    Ok(AllAccountsResponse { accounts })
+}
+
 /// Handler for `QueryMsg::Multiplier`
 pub fn query_multiplier(deps: Deps) -> StdResult<MultiplierResponse> {
     let multiplier = MULTIPLIER.load(deps.storage)?;
@@ -543,6 +650,9 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         DistributedFunds {} => to_binary(&query_distributed_funds(deps)?),
         UndistributedFunds {} => to_binary(&query_undistributed_funds(deps, env)?),
         WithdrawableFunds { owner } => to_binary(&query_withdrawable_funds(deps, owner)?),
+        AllAccounts { start_after, limit } => {
+            to_binary(&query_all_accounts(deps, start_after, limit)?)
+        }
     }
 }
 
@@ -71,6 +71,19 @@ pub enum ExecuteMsg {
     WithdrawFunds {},
 }
 
+/// Messages the token sends to its controller
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ControllerExecuteMsg {
+    /// Sent after `Transfer`, `TransferFrom` and `Send` moved `amount` (display amount) of
+    /// tokens from `sender` to `recipient`
+    AfterTransfer {
+        sender: String,
+        recipient: String,
+        amount: Uint128,
+    },
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ControllerQuery {
@@ -105,6 +118,12 @@ pub enum QueryMsg {
     UndistributedFunds {},
     /// Queries for funds distributed but not yet withdrawn by owner
     WithdrawableFunds { owner: String },
+    /// Lists addresses holding a non-zero balance, ordered by address.
+    /// Return type: `AllAccountsResponse`.
+    AllAccounts {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
@@ -112,6 +131,11 @@ pub struct BalanceResponse {
     pub balance: DisplayAmount,
 }
 
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct AllAccountsResponse {
+    pub accounts: Vec<String>,
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct TokenInfoResponse {
     pub name: String,
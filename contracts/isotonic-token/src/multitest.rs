@@ -44,6 +44,28 @@ fn fresh_queries() {
     );
 }
 
+#[test]
+fn all_accounts() {
+    let mut suite = Suite::new();
+    let controller = suite.controller();
+    let controller = controller.as_str();
+
+    for account in ["carol", "alice", "bob", "dave"] {
+        suite.mint(controller, account, Uint128::new(100)).unwrap();
+    }
+    // Accounts with their whole balance burned are not listed
+    suite.burn(controller, "bob", Uint128::new(100)).unwrap();
+
+    assert_eq!(
+        suite.query_all_accounts(None, 2).unwrap(),
+        vec!["alice".to_owned(), "carol".to_owned()]
+    );
+    assert_eq!(
+        suite.query_all_accounts("carol".to_owned(), None).unwrap(),
+        vec!["dave".to_owned()]
+    );
+}
+
 mod minting {
     use super::*;
 
@@ -781,7 +803,7 @@ mod distribution {
 
     #[test]
     fn weight_changed_after_distribution() {
-        let members = vec!["member1", "member2", "member3", "member4"];
+        let members = ["member1", "member2", "member3", "member4"];
         let reward = "Reward";
 
         let mut suite = SuiteBuilder::new()
@@ -845,7 +867,7 @@ mod distribution {
 
     #[test]
     fn weight_changed_after_distribution_accumulated() {
-        let members = vec!["member1", "member2", "member3", "member4"];
+        let members = ["member1", "member2", "member3", "member4"];
         let reward = "Reward";
 
         let mut suite = SuiteBuilder::new()
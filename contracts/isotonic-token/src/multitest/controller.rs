@@ -6,7 +6,7 @@ use cosmwasm_std::{
 };
 use cw_multi_test::Contract;
 
-use crate::msg::{ControllerQuery, TransferableAmountResp};
+use crate::msg::{ControllerExecuteMsg, ControllerQuery, TransferableAmountResp};
 
 /// Controller contract stub allowing to easy testing the transfer without actual controller
 /// contract
@@ -47,9 +47,15 @@ impl Contract<Empty> for Controller {
         _deps: DepsMut,
         _env: Env,
         _info: MessageInfo,
-        _msg: Vec<u8>,
+        msg: Vec<u8>,
     ) -> AnyResult<Response<Empty>> {
-        Err(anyhow!("Controller stub execution"))
+        use ControllerExecuteMsg::*;
+
+        let msg: ControllerExecuteMsg = from_slice(&msg)?;
+
+        match msg {
+            AfterTransfer { .. } => Ok(Response::default()),
+        }
     }
 
     fn query(&self, _deps: Deps, _env: Env, msg: Vec<u8>) -> anyhow::Result<Binary> {
@@ -73,10 +73,10 @@ impl Contract<Empty> for Receiver {
         };
 
         match msg {
-            Valid {} => self.counter.update(deps.storage, |cnt| -> StdResult<_> {
+            Valid => self.counter.update(deps.storage, |cnt| -> StdResult<_> {
                 Ok(cnt + Uint128::new(1))
             })?,
-            Invalid {} => bail!("Invalid message on receiver"),
+            Invalid => bail!("Invalid message on receiver"),
         };
 
         Ok(Response::new())
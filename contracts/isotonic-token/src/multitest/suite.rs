@@ -2,8 +2,8 @@ use std::collections::HashMap;
 
 use crate::display_amount::DisplayAmount;
 use crate::msg::{
-    BalanceResponse, ExecuteMsg, FundsResponse, InstantiateMsg, MultiplierResponse, QueryMsg,
-    TokenInfoResponse,
+    AllAccountsResponse, BalanceResponse, ExecuteMsg, FundsResponse, InstantiateMsg,
+    MultiplierResponse, QueryMsg, TokenInfoResponse,
 };
 use crate::multitest::controller::Controller;
 use crate::multitest::receiver::{QueryResp as ReceiverQueryResp, Receiver};
@@ -309,6 +309,25 @@ impl Suite {
             .map_err(|err| anyhow!(err))
     }
 
+    pub fn query_all_accounts(
+        &self,
+        start_after: impl Into<Option<String>>,
+        limit: impl Into<Option<u32>>,
+    ) -> AnyResult<Vec<String>> {
+        let resp: AllAccountsResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(
+                self.token.clone(),
+                &QueryMsg::AllAccounts {
+                    start_after: start_after.into(),
+                    limit: limit.into(),
+                },
+            )
+            .map_err(|err| anyhow!(err))?;
+        Ok(resp.accounts)
+    }
+
     /// Queries receiver for count of valid messages it received
     pub fn query_receiver(&self) -> AnyResult<u128> {
         let resp: ReceiverQueryResp = self
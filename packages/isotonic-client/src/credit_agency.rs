@@ -0,0 +1,192 @@
+use cosmwasm_std::{Addr, CosmosMsg, QuerierWrapper, StdResult};
+use osmo_bindings::OsmosisQuery;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use isotonic_credit_agency::msg::{
+    ExecuteMsg, IsOnMarketResponse, ListEnteredMarketsResponse, ListMarketParticipantsResponse,
+    ListMarketsResponse, MarketConfig, MarketResponse, Operation, PortfolioResponse,
+    ProtocolStatsResponse, QueryMsg,
+};
+use isotonic_credit_agency::state::Config;
+use utils::{coin::Coin, credit_line::CreditLineResponse, token::Token};
+
+use crate::execute;
+
+/// Isotonic credit agency contract
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct CreditAgencyContract(pub Addr);
+
+impl CreditAgencyContract {
+    pub fn addr(&self) -> &Addr {
+        &self.0
+    }
+
+    fn execute<T>(&self, msg: ExecuteMsg) -> StdResult<CosmosMsg<T>> {
+        execute(&self.0, &msg, vec![])
+    }
+
+    fn query<R: DeserializeOwned>(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+        msg: QueryMsg,
+    ) -> StdResult<R> {
+        querier.query_wasm_smart(&self.0, &msg)
+    }
+
+    /// Only callable by the credit agency owner
+    pub fn create_market<T>(&self, config: MarketConfig) -> StdResult<CosmosMsg<T>> {
+        self.execute(ExecuteMsg::CreateMarket(Box::new(config)))
+    }
+
+    pub fn liquidate<T>(
+        &self,
+        account: impl Into<String>,
+        collateral_denom: Token,
+        amount_to_repay: Coin,
+    ) -> StdResult<CosmosMsg<T>> {
+        self.execute(ExecuteMsg::Liquidate {
+            account: account.into(),
+            collateral_denom,
+            amount_to_repay,
+        })
+    }
+
+    /// Only callable by markets
+    pub fn enter_market<T>(&self, account: impl Into<String>) -> StdResult<CosmosMsg<T>> {
+        self.execute(ExecuteMsg::EnterMarket {
+            account: account.into(),
+        })
+    }
+
+    pub fn exit_market<T>(&self, market: impl Into<String>) -> StdResult<CosmosMsg<T>> {
+        self.execute(ExecuteMsg::ExitMarket {
+            market: market.into(),
+        })
+    }
+
+    pub fn repay_with_collateral<T>(
+        &self,
+        max_collateral: Coin,
+        amount_to_repay: Coin,
+    ) -> StdResult<CosmosMsg<T>> {
+        self.execute(ExecuteMsg::RepayWithCollateral {
+            max_collateral,
+            amount_to_repay,
+        })
+    }
+
+    pub fn accrue_all<T>(
+        &self,
+        start_after: Option<Token>,
+        limit: Option<u32>,
+    ) -> StdResult<CosmosMsg<T>> {
+        self.execute(ExecuteMsg::AccrueAll { start_after, limit })
+    }
+
+    /// Performs `ops` in order for the sender. `funds` have to match the deposits and
+    /// repayments exactly.
+    pub fn batch<T>(
+        &self,
+        ops: Vec<Operation>,
+        funds: Vec<cosmwasm_std::Coin>,
+    ) -> StdResult<CosmosMsg<T>> {
+        execute(&self.0, &ExecuteMsg::Batch { ops }, funds)
+    }
+
+    pub fn configuration(&self, querier: &QuerierWrapper<OsmosisQuery>) -> StdResult<Config> {
+        self.query(querier, QueryMsg::Configuration {})
+    }
+
+    pub fn market(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+        market_token: Token,
+    ) -> StdResult<MarketResponse> {
+        self.query(querier, QueryMsg::Market { market_token })
+    }
+
+    pub fn list_markets(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+        start_after: Option<Token>,
+        limit: Option<u32>,
+    ) -> StdResult<ListMarketsResponse> {
+        self.query(querier, QueryMsg::ListMarkets { start_after, limit })
+    }
+
+    pub fn total_credit_line(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+        account: impl Into<String>,
+    ) -> StdResult<CreditLineResponse> {
+        let msg = QueryMsg::TotalCreditLine {
+            account: account.into(),
+        };
+        self.query(querier, msg)
+    }
+
+    pub fn list_entered_markets(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+        account: impl Into<String>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<ListEnteredMarketsResponse> {
+        let msg = QueryMsg::ListEnteredMarkets {
+            account: account.into(),
+            start_after,
+            limit,
+        };
+        self.query(querier, msg)
+    }
+
+    pub fn is_on_market(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+        account: impl Into<String>,
+        market: impl Into<String>,
+    ) -> StdResult<IsOnMarketResponse> {
+        let msg = QueryMsg::IsOnMarket {
+            account: account.into(),
+            market: market.into(),
+        };
+        self.query(querier, msg)
+    }
+
+    pub fn portfolio(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+        account: impl Into<String>,
+    ) -> StdResult<PortfolioResponse> {
+        let msg = QueryMsg::Portfolio {
+            account: account.into(),
+        };
+        self.query(querier, msg)
+    }
+
+    pub fn protocol_stats(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+        start_after: Option<Token>,
+        limit: Option<u32>,
+    ) -> StdResult<ProtocolStatsResponse> {
+        self.query(querier, QueryMsg::ProtocolStats { start_after, limit })
+    }
+
+    pub fn list_market_participants(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+        market: impl Into<String>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<ListMarketParticipantsResponse> {
+        let msg = QueryMsg::ListMarketParticipants {
+            market: market.into(),
+            start_after,
+            limit,
+        };
+        self.query(querier, msg)
+    }
+}
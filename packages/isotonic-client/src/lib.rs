@@ -0,0 +1,25 @@
+//! Typed wrappers around the addresses of deployed Isotonic contracts. Other contracts can use
+//! them to query the contracts and build their messages without copying the message types.
+
+mod credit_agency;
+mod market;
+mod oracle;
+mod token;
+
+pub use credit_agency::CreditAgencyContract;
+pub use market::MarketContract;
+pub use oracle::OracleContract;
+pub use token::TokenContract;
+
+use cosmwasm_std::{to_binary, Addr, Coin, CosmosMsg, StdResult, WasmMsg};
+use serde::Serialize;
+
+/// Wraps `msg` for execution on `contract`, sending `funds` along
+fn execute<T>(contract: &Addr, msg: &impl Serialize, funds: Vec<Coin>) -> StdResult<CosmosMsg<T>> {
+    Ok(WasmMsg::Execute {
+        contract_addr: contract.to_string(),
+        msg: to_binary(msg)?,
+        funds,
+    }
+    .into())
+}
@@ -0,0 +1,521 @@
+use cosmwasm_std::{Addr, Coin, CosmosMsg, Decimal, QuerierWrapper, StdResult, Uint128};
+use osmo_bindings::OsmosisQuery;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use isotonic_market::msg::{
+    AccountInterestResponse, ApyResponse, ExecuteMsg, InterestResponse, ListPositionsResponse,
+    OverdueResponse, PendingRampsResponse, QueryMsg, RateHistoryResponse, ReserveBucketsResponse,
+    ReserveResponse, SimulationResponse, StableDebtResponse, StableRateResponse, TermLoansResponse,
+    TermRateResponse, TokensBalanceResponse, TransferableAmountResponse,
+};
+use isotonic_market::state::Config;
+use utils::{
+    coin::Coin as IsotonicCoin, credit_line::CreditLineResponse, interest::Interest,
+    price::PriceRate, time::Expiration, token::Token,
+};
+
+use crate::execute;
+
+/// Isotonic market contract
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct MarketContract(pub Addr);
+
+impl MarketContract {
+    pub fn addr(&self) -> &Addr {
+        &self.0
+    }
+
+    fn execute<T>(&self, msg: ExecuteMsg, funds: Vec<Coin>) -> StdResult<CosmosMsg<T>> {
+        execute(&self.0, &msg, funds)
+    }
+
+    fn query<R: serde::de::DeserializeOwned>(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+        msg: QueryMsg,
+    ) -> StdResult<R> {
+        querier.query_wasm_smart(&self.0, &msg)
+    }
+
+    /// Deposits `funds` of the market token for L-Tokens
+    pub fn deposit<T>(&self, funds: Coin) -> StdResult<CosmosMsg<T>> {
+        self.execute(ExecuteMsg::Deposit {}, vec![funds])
+    }
+
+    /// Deposits `funds` of the market token for L-Tokens minted to `account`
+    pub fn deposit_to<T>(
+        &self,
+        account: impl Into<String>,
+        funds: Coin,
+    ) -> StdResult<CosmosMsg<T>> {
+        let msg = ExecuteMsg::DepositTo {
+            account: account.into(),
+        };
+        self.execute(msg, vec![funds])
+    }
+
+    pub fn withdraw<T>(&self, amount: impl Into<Uint128>) -> StdResult<CosmosMsg<T>> {
+        let msg = ExecuteMsg::Withdraw {
+            amount: amount.into(),
+        };
+        self.execute(msg, vec![])
+    }
+
+    /// Repays variable-rate debt with `funds` of the market token
+    pub fn repay<T>(&self, funds: Coin) -> StdResult<CosmosMsg<T>> {
+        self.execute(ExecuteMsg::Repay {}, vec![funds])
+    }
+
+    pub fn borrow<T>(&self, amount: impl Into<Uint128>) -> StdResult<CosmosMsg<T>> {
+        let msg = ExecuteMsg::Borrow {
+            amount: amount.into(),
+        };
+        self.execute(msg, vec![])
+    }
+
+    /// Only callable by the credit agency
+    pub fn repay_to<T>(
+        &self,
+        account: impl Into<String>,
+        amount: impl Into<Uint128>,
+        funds: Coin,
+    ) -> StdResult<CosmosMsg<T>> {
+        let msg = ExecuteMsg::RepayTo {
+            account: account.into(),
+            amount: amount.into(),
+        };
+        self.execute(msg, vec![funds])
+    }
+
+    /// Only callable by the credit agency
+    pub fn deposit_for<T>(
+        &self,
+        account: impl Into<String>,
+        funds: Coin,
+    ) -> StdResult<CosmosMsg<T>> {
+        let msg = ExecuteMsg::DepositFor {
+            account: account.into(),
+        };
+        self.execute(msg, vec![funds])
+    }
+
+    /// Only callable by the credit agency
+    pub fn withdraw_for<T>(
+        &self,
+        account: impl Into<String>,
+        amount: impl Into<Uint128>,
+    ) -> StdResult<CosmosMsg<T>> {
+        let msg = ExecuteMsg::WithdrawFor {
+            account: account.into(),
+            amount: amount.into(),
+        };
+        self.execute(msg, vec![])
+    }
+
+    /// Only callable by the credit agency
+    pub fn borrow_for<T>(
+        &self,
+        account: impl Into<String>,
+        amount: impl Into<Uint128>,
+    ) -> StdResult<CosmosMsg<T>> {
+        let msg = ExecuteMsg::BorrowFor {
+            account: account.into(),
+            amount: amount.into(),
+        };
+        self.execute(msg, vec![])
+    }
+
+    /// Only callable by the credit agency
+    pub fn repay_for<T>(&self, account: impl Into<String>, funds: Coin) -> StdResult<CosmosMsg<T>> {
+        let msg = ExecuteMsg::RepayFor {
+            account: account.into(),
+        };
+        self.execute(msg, vec![funds])
+    }
+
+    /// Only callable by the credit agency
+    pub fn adjust_common_token<T>(&self, new_token: Token) -> StdResult<CosmosMsg<T>> {
+        self.execute(ExecuteMsg::AdjustCommonToken { new_token }, vec![])
+    }
+
+    /// Only callable by the credit agency
+    pub fn swap_withdraw_from<T>(
+        &self,
+        account: impl Into<String>,
+        sell_limit: impl Into<Uint128>,
+        buy: IsotonicCoin,
+    ) -> StdResult<CosmosMsg<T>> {
+        let msg = ExecuteMsg::SwapWithdrawFrom {
+            account: account.into(),
+            sell_limit: sell_limit.into(),
+            buy,
+        };
+        self.execute(msg, vec![])
+    }
+
+    /// Only callable by the credit agency
+    pub fn distribute_as_ltokens<T>(&self, funds: Coin) -> StdResult<CosmosMsg<T>> {
+        self.execute(ExecuteMsg::DistributeAsLTokens {}, vec![funds])
+    }
+
+    pub fn accrue_interest<T>(&self) -> StdResult<CosmosMsg<T>> {
+        self.execute(ExecuteMsg::AccrueInterest {}, vec![])
+    }
+
+    /// Only callable by the credit agency
+    pub fn accrue_interest_for<T>(&self, recipient: impl Into<String>) -> StdResult<CosmosMsg<T>> {
+        let msg = ExecuteMsg::AccrueInterestFor {
+            recipient: recipient.into(),
+        };
+        self.execute(msg, vec![])
+    }
+
+    pub fn borrow_stable<T>(
+        &self,
+        amount: impl Into<Uint128>,
+        max_rate: Option<Decimal>,
+    ) -> StdResult<CosmosMsg<T>> {
+        let msg = ExecuteMsg::BorrowStable {
+            amount: amount.into(),
+            max_rate,
+        };
+        self.execute(msg, vec![])
+    }
+
+    /// Repays stable-rate debt with `funds` of the market token
+    pub fn repay_stable<T>(&self, funds: Coin) -> StdResult<CosmosMsg<T>> {
+        self.execute(ExecuteMsg::RepayStable {}, vec![funds])
+    }
+
+    pub fn borrow_term<T>(
+        &self,
+        amount: impl Into<Uint128>,
+        duration: u64,
+        max_rate: Option<Decimal>,
+    ) -> StdResult<CosmosMsg<T>> {
+        let msg = ExecuteMsg::BorrowTerm {
+            amount: amount.into(),
+            duration,
+            max_rate,
+        };
+        self.execute(msg, vec![])
+    }
+
+    /// Repays the term loan `loan_id` with `funds` of the market token
+    pub fn repay_term<T>(&self, loan_id: u64, funds: Coin) -> StdResult<CosmosMsg<T>> {
+        self.execute(ExecuteMsg::RepayTerm { loan_id }, vec![funds])
+    }
+
+    pub fn withdraw_reserve<T>(&self, bucket: impl Into<String>) -> StdResult<CosmosMsg<T>> {
+        let msg = ExecuteMsg::WithdrawReserve {
+            bucket: bucket.into(),
+        };
+        self.execute(msg, vec![])
+    }
+
+    /// Only callable by the credit agency
+    pub fn deprecate<T>(
+        &self,
+        collateral_ratio_ramp_end: Option<Expiration>,
+        interest_rates: Option<Interest>,
+    ) -> StdResult<CosmosMsg<T>> {
+        let msg = ExecuteMsg::Deprecate {
+            collateral_ratio_ramp_end,
+            interest_rates,
+        };
+        self.execute(msg, vec![])
+    }
+
+    pub fn configuration(&self, querier: &QuerierWrapper<OsmosisQuery>) -> StdResult<Config> {
+        self.query(querier, QueryMsg::Configuration {})
+    }
+
+    pub fn tokens_balance(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+        account: impl Into<String>,
+    ) -> StdResult<TokensBalanceResponse> {
+        let msg = QueryMsg::TokensBalance {
+            account: account.into(),
+        };
+        self.query(querier, msg)
+    }
+
+    pub fn transferable_amount(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+        token: impl Into<String>,
+        account: impl Into<String>,
+    ) -> StdResult<TransferableAmountResponse> {
+        let msg = QueryMsg::TransferableAmount {
+            token: token.into(),
+            account: account.into(),
+        };
+        self.query(querier, msg)
+    }
+
+    pub fn withdrawable(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+        account: impl Into<String>,
+    ) -> StdResult<Coin> {
+        let msg = QueryMsg::Withdrawable {
+            account: account.into(),
+        };
+        self.query(querier, msg)
+    }
+
+    pub fn borrowable(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+        account: impl Into<String>,
+    ) -> StdResult<Coin> {
+        let msg = QueryMsg::Borrowable {
+            account: account.into(),
+        };
+        self.query(querier, msg)
+    }
+
+    pub fn interest(&self, querier: &QuerierWrapper<OsmosisQuery>) -> StdResult<InterestResponse> {
+        self.query(querier, QueryMsg::Interest {})
+    }
+
+    pub fn price_market_local_per_common(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+    ) -> StdResult<PriceRate> {
+        self.query(querier, QueryMsg::PriceMarketLocalPerCommon {})
+    }
+
+    pub fn credit_line(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+        account: impl Into<String>,
+    ) -> StdResult<CreditLineResponse> {
+        let msg = QueryMsg::CreditLine {
+            account: account.into(),
+        };
+        self.query(querier, msg)
+    }
+
+    pub fn reserve(&self, querier: &QuerierWrapper<OsmosisQuery>) -> StdResult<ReserveResponse> {
+        self.query(querier, QueryMsg::Reserve {})
+    }
+
+    pub fn apy(&self, querier: &QuerierWrapper<OsmosisQuery>) -> StdResult<ApyResponse> {
+        self.query(querier, QueryMsg::Apy {})
+    }
+
+    pub fn pending_ramps(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+    ) -> StdResult<PendingRampsResponse> {
+        self.query(querier, QueryMsg::PendingRamps {})
+    }
+
+    pub fn stable_debt(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+        account: impl Into<String>,
+    ) -> StdResult<StableDebtResponse> {
+        let msg = QueryMsg::StableDebt {
+            account: account.into(),
+        };
+        self.query(querier, msg)
+    }
+
+    pub fn stable_rate(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+    ) -> StdResult<StableRateResponse> {
+        self.query(querier, QueryMsg::StableRate {})
+    }
+
+    pub fn term_loans(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+        account: impl Into<String>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<TermLoansResponse> {
+        let msg = QueryMsg::TermLoans {
+            account: account.into(),
+            start_after,
+            limit,
+        };
+        self.query(querier, msg)
+    }
+
+    pub fn term_rate(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+        amount: impl Into<Uint128>,
+    ) -> StdResult<TermRateResponse> {
+        let msg = QueryMsg::TermRate {
+            amount: amount.into(),
+        };
+        self.query(querier, msg)
+    }
+
+    pub fn overdue(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+        account: impl Into<String>,
+    ) -> StdResult<OverdueResponse> {
+        let msg = QueryMsg::Overdue {
+            account: account.into(),
+        };
+        self.query(querier, msg)
+    }
+
+    pub fn reserve_buckets(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+    ) -> StdResult<ReserveBucketsResponse> {
+        self.query(querier, QueryMsg::ReserveBuckets {})
+    }
+
+    pub fn list_positions(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<ListPositionsResponse> {
+        self.query(querier, QueryMsg::ListPositions { start_after, limit })
+    }
+
+    pub fn account_interest(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+        account: impl Into<String>,
+    ) -> StdResult<AccountInterestResponse> {
+        let msg = QueryMsg::AccountInterest {
+            account: account.into(),
+        };
+        self.query(querier, msg)
+    }
+
+    pub fn rate_history(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<RateHistoryResponse> {
+        self.query(querier, QueryMsg::RateHistory { start_after, limit })
+    }
+
+    pub fn simulate_borrow(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+        account: impl Into<String>,
+        amount: impl Into<Uint128>,
+    ) -> StdResult<SimulationResponse> {
+        let msg = QueryMsg::SimulateBorrow {
+            account: account.into(),
+            amount: amount.into(),
+        };
+        self.query(querier, msg)
+    }
+
+    pub fn simulate_withdraw(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+        account: impl Into<String>,
+        amount: impl Into<Uint128>,
+    ) -> StdResult<SimulationResponse> {
+        let msg = QueryMsg::SimulateWithdraw {
+            account: account.into(),
+            amount: amount.into(),
+        };
+        self.query(querier, msg)
+    }
+
+    pub fn simulate_repay(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+        account: impl Into<String>,
+        amount: impl Into<Uint128>,
+    ) -> StdResult<SimulationResponse> {
+        let msg = QueryMsg::SimulateRepay {
+            account: account.into(),
+            amount: amount.into(),
+        };
+        self.query(querier, msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use cosmwasm_std::testing::MockQuerier;
+    use cosmwasm_std::{
+        coin, from_binary, to_binary, ContractResult, Empty, SystemResult, WasmMsg, WasmQuery,
+    };
+
+    #[test]
+    fn builds_execute_msgs() {
+        let market = MarketContract(Addr::unchecked("market"));
+
+        let msg: CosmosMsg<Empty> = market.deposit(coin(100, "atom")).unwrap();
+        assert_eq!(
+            msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "market".to_owned(),
+                msg: to_binary(&ExecuteMsg::Deposit {}).unwrap(),
+                funds: vec![coin(100, "atom")],
+            })
+        );
+
+        let msg: CosmosMsg<Empty> = market.borrow_term(50u128, 3600, None).unwrap();
+        assert_eq!(
+            msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "market".to_owned(),
+                msg: to_binary(&ExecuteMsg::BorrowTerm {
+                    amount: Uint128::new(50),
+                    duration: 3600,
+                    max_rate: None,
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn queries_market() {
+        let mut querier = MockQuerier::<OsmosisQuery>::new(&[]);
+        querier.update_wasm(|query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "market" => {
+                let response = match from_binary(msg).unwrap() {
+                    QueryMsg::Apy {} => to_binary(&ApyResponse {
+                        borrower: Decimal::percent(10),
+                        lender: Decimal::percent(5),
+                    }),
+                    QueryMsg::Borrowable { account } if account == "borrower" => {
+                        to_binary(&coin(40, "atom"))
+                    }
+                    _ => panic!("Unexpected query"),
+                };
+                SystemResult::Ok(ContractResult::Ok(response.unwrap()))
+            }
+            _ => panic!("Unexpected contract"),
+        });
+        let querier = QuerierWrapper::new(&querier);
+
+        let market = MarketContract(Addr::unchecked("market"));
+        assert_eq!(
+            market.apy(&querier).unwrap(),
+            ApyResponse {
+                borrower: Decimal::percent(10),
+                lender: Decimal::percent(5),
+            }
+        );
+        assert_eq!(
+            market.borrowable(&querier, "borrower").unwrap(),
+            coin(40, "atom")
+        );
+    }
+}
@@ -0,0 +1,140 @@
+use cosmwasm_std::{Addr, CosmosMsg, QuerierWrapper, StdResult};
+use osmo_bindings::OsmosisQuery;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use isotonic_osmosis_oracle::msg::{ExecuteMsg, ListPoolsResponse, QueryMsg};
+use utils::oracle::{OracleQueryMsg, PriceResponse, PriceWithTimestampResponse, RouteResponse};
+
+use crate::execute;
+
+/// Price oracle used by the markets. The price and route queries work with any oracle supporting
+/// `OracleQueryMsg`, the rest is specific to the Osmosis oracle.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct OracleContract(pub Addr);
+
+impl OracleContract {
+    pub fn addr(&self) -> &Addr {
+        &self.0
+    }
+
+    fn execute<T>(&self, msg: ExecuteMsg) -> StdResult<CosmosMsg<T>> {
+        execute(&self.0, &msg, vec![])
+    }
+
+    /// Only callable by the controller
+    pub fn register_pool<T>(
+        &self,
+        pool_id: u64,
+        denom1: impl Into<String>,
+        denom2: impl Into<String>,
+    ) -> StdResult<CosmosMsg<T>> {
+        self.execute(ExecuteMsg::RegisterPool {
+            pool_id,
+            denom1: denom1.into(),
+            denom2: denom2.into(),
+        })
+    }
+
+    /// Only callable by the controller
+    pub fn deregister_pool<T>(
+        &self,
+        denom1: impl Into<String>,
+        denom2: impl Into<String>,
+    ) -> StdResult<CosmosMsg<T>> {
+        self.execute(ExecuteMsg::DeregisterPool {
+            denom1: denom1.into(),
+            denom2: denom2.into(),
+        })
+    }
+
+    /// Only callable by the controller
+    pub fn update_pool<T>(
+        &self,
+        pool_id: u64,
+        denom1: impl Into<String>,
+        denom2: impl Into<String>,
+    ) -> StdResult<CosmosMsg<T>> {
+        self.execute(ExecuteMsg::UpdatePool {
+            pool_id,
+            denom1: denom1.into(),
+            denom2: denom2.into(),
+        })
+    }
+
+    /// Only callable by the controller
+    pub fn propose_controller<T>(
+        &self,
+        new_controller: impl Into<String>,
+    ) -> StdResult<CosmosMsg<T>> {
+        self.execute(ExecuteMsg::ProposeController {
+            new_controller: new_controller.into(),
+        })
+    }
+
+    /// Only callable by the proposed controller
+    pub fn accept_controller<T>(&self) -> StdResult<CosmosMsg<T>> {
+        self.execute(ExecuteMsg::AcceptController {})
+    }
+
+    pub fn price(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+        sell: impl Into<String>,
+        buy: impl Into<String>,
+    ) -> StdResult<PriceResponse> {
+        let msg = OracleQueryMsg::Price {
+            sell: sell.into(),
+            buy: buy.into(),
+        };
+        querier.query_wasm_smart(&self.0, &msg)
+    }
+
+    pub fn price_with_timestamp(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+        sell: impl Into<String>,
+        buy: impl Into<String>,
+    ) -> StdResult<PriceWithTimestampResponse> {
+        let msg = OracleQueryMsg::PriceWithTimestamp {
+            sell: sell.into(),
+            buy: buy.into(),
+        };
+        querier.query_wasm_smart(&self.0, &msg)
+    }
+
+    pub fn route(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+        sell: impl Into<String>,
+        buy: impl Into<String>,
+    ) -> StdResult<RouteResponse> {
+        let msg = OracleQueryMsg::Route {
+            sell: sell.into(),
+            buy: buy.into(),
+        };
+        querier.query_wasm_smart(&self.0, &msg)
+    }
+
+    pub fn pool_id(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+        denom1: impl Into<String>,
+        denom2: impl Into<String>,
+    ) -> StdResult<u64> {
+        let msg = QueryMsg::PoolId {
+            denom1: denom1.into(),
+            denom2: denom2.into(),
+        };
+        querier.query_wasm_smart(&self.0, &msg)
+    }
+
+    pub fn list_pools(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+        start_after: Option<(String, String)>,
+        limit: Option<u32>,
+    ) -> StdResult<ListPoolsResponse> {
+        querier.query_wasm_smart(&self.0, &QueryMsg::ListPools { start_after, limit })
+    }
+}
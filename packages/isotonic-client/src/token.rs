@@ -0,0 +1,179 @@
+use cosmwasm_std::{Addr, Binary, Coin, CosmosMsg, Decimal, QuerierWrapper, StdResult, Uint128};
+use osmo_bindings::OsmosisQuery;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use isotonic_token::msg::{
+    AllAccountsResponse, BalanceResponse, ExecuteMsg, FundsResponse, MultiplierResponse, QueryMsg,
+    TokenInfoResponse,
+};
+use isotonic_token::DisplayAmount;
+
+use crate::execute;
+
+/// Isotonic token contract, as used for L-Tokens and B-Tokens. Amounts are display amounts.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct TokenContract(pub Addr);
+
+impl TokenContract {
+    pub fn addr(&self) -> &Addr {
+        &self.0
+    }
+
+    fn execute<T>(&self, msg: ExecuteMsg, funds: Vec<Coin>) -> StdResult<CosmosMsg<T>> {
+        execute(&self.0, &msg, funds)
+    }
+
+    fn query<R: DeserializeOwned>(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+        msg: QueryMsg,
+    ) -> StdResult<R> {
+        querier.query_wasm_smart(&self.0, &msg)
+    }
+
+    pub fn transfer<T>(
+        &self,
+        recipient: impl Into<String>,
+        amount: impl Into<Uint128>,
+    ) -> StdResult<CosmosMsg<T>> {
+        let msg = ExecuteMsg::Transfer {
+            recipient: recipient.into(),
+            amount: DisplayAmount::raw(amount),
+        };
+        self.execute(msg, vec![])
+    }
+
+    /// Only callable by the controller
+    pub fn transfer_from<T>(
+        &self,
+        sender: impl Into<String>,
+        recipient: impl Into<String>,
+        amount: impl Into<Uint128>,
+    ) -> StdResult<CosmosMsg<T>> {
+        let msg = ExecuteMsg::TransferFrom {
+            sender: sender.into(),
+            recipient: recipient.into(),
+            amount: DisplayAmount::raw(amount),
+        };
+        self.execute(msg, vec![])
+    }
+
+    pub fn send<T>(
+        &self,
+        contract: impl Into<String>,
+        amount: impl Into<Uint128>,
+        msg: Binary,
+    ) -> StdResult<CosmosMsg<T>> {
+        let msg = ExecuteMsg::Send {
+            contract: contract.into(),
+            amount: DisplayAmount::raw(amount),
+            msg,
+        };
+        self.execute(msg, vec![])
+    }
+
+    /// Only callable by the controller
+    pub fn mint<T>(
+        &self,
+        recipient: impl Into<String>,
+        amount: impl Into<Uint128>,
+    ) -> StdResult<CosmosMsg<T>> {
+        let msg = ExecuteMsg::Mint {
+            recipient: recipient.into(),
+            amount: DisplayAmount::raw(amount),
+        };
+        self.execute(msg, vec![])
+    }
+
+    /// Only callable by the controller
+    pub fn burn_from<T>(
+        &self,
+        owner: impl Into<String>,
+        amount: impl Into<Uint128>,
+    ) -> StdResult<CosmosMsg<T>> {
+        let msg = ExecuteMsg::BurnFrom {
+            owner: owner.into(),
+            amount: DisplayAmount::raw(amount),
+        };
+        self.execute(msg, vec![])
+    }
+
+    /// Only callable by the controller
+    pub fn rebase<T>(&self, ratio: Decimal) -> StdResult<CosmosMsg<T>> {
+        self.execute(ExecuteMsg::Rebase { ratio }, vec![])
+    }
+
+    /// Distributes `funds` along with everything sent to the token but not yet distributed
+    pub fn distribute<T>(
+        &self,
+        sender: Option<String>,
+        funds: Vec<Coin>,
+    ) -> StdResult<CosmosMsg<T>> {
+        self.execute(ExecuteMsg::Distribute { sender }, funds)
+    }
+
+    pub fn withdraw_funds<T>(&self) -> StdResult<CosmosMsg<T>> {
+        self.execute(ExecuteMsg::WithdrawFunds {}, vec![])
+    }
+
+    pub fn balance(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+        address: impl Into<String>,
+    ) -> StdResult<BalanceResponse> {
+        let msg = QueryMsg::Balance {
+            address: address.into(),
+        };
+        self.query(querier, msg)
+    }
+
+    pub fn token_info(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+    ) -> StdResult<TokenInfoResponse> {
+        self.query(querier, QueryMsg::TokenInfo {})
+    }
+
+    pub fn multiplier(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+    ) -> StdResult<MultiplierResponse> {
+        self.query(querier, QueryMsg::Multiplier {})
+    }
+
+    pub fn distributed_funds(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+    ) -> StdResult<FundsResponse> {
+        self.query(querier, QueryMsg::DistributedFunds {})
+    }
+
+    pub fn undistributed_funds(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+    ) -> StdResult<FundsResponse> {
+        self.query(querier, QueryMsg::UndistributedFunds {})
+    }
+
+    pub fn withdrawable_funds(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+        owner: impl Into<String>,
+    ) -> StdResult<FundsResponse> {
+        let msg = QueryMsg::WithdrawableFunds {
+            owner: owner.into(),
+        };
+        self.query(querier, msg)
+    }
+
+    pub fn all_accounts(
+        &self,
+        querier: &QuerierWrapper<OsmosisQuery>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<AllAccountsResponse> {
+        self.query(querier, QueryMsg::AllAccounts { start_after, limit })
+    }
+}
@@ -3,7 +3,7 @@ use std::ops::Add;
 
 use crate::coin::Coin;
 use crate::token::Token;
-use cosmwasm_std::Uint128;
+use cosmwasm_std::{Decimal, Uint128};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
@@ -78,6 +78,20 @@ impl CreditLineValues {
         }
     }
 
+    pub fn saturating_sub(self, rhs: Self) -> Self {
+        Self {
+            collateral: self.collateral.saturating_sub(rhs.collateral),
+            credit_line: self.credit_line.saturating_sub(rhs.credit_line),
+            debt: self.debt.saturating_sub(rhs.debt),
+        }
+    }
+
+    /// Credit line divided by debt, the account can be liquidated below 1.
+    /// `None` if there is no debt.
+    pub fn health(&self) -> Option<Decimal> {
+        (!self.debt.is_zero()).then(|| Decimal::from_ratio(self.credit_line, self.debt))
+    }
+
     pub fn make_response(self, denom: Token) -> CreditLineResponse {
         CreditLineResponse {
             collateral: Coin::new(self.collateral.u128(), denom.clone()),
@@ -128,7 +142,7 @@ mod tests {
 
     #[test]
     fn sum_credit_line_response() {
-        let responses = vec![
+        let responses = [
             CreditLineValues {
                 collateral: Uint128::new(500),
                 credit_line: Uint128::new(300),
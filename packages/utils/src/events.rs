@@ -0,0 +1,403 @@
+//! Events emitted by the Isotonic contracts on every state change. Every event has a fixed set of
+//! attributes, so it can be parsed back into its struct by indexers. Amounts in market, credit
+//! agency and liquidation events are in the market token, which is the display unit of L-Tokens
+//! and B-Tokens. Market and token events carry the stored amount of the L-Tokens or B-Tokens
+//! alongside.
+
+use std::fmt;
+use std::str::FromStr;
+
+use cosmwasm_std::{Decimal, Event, StdError, StdResult, Uint128};
+use serde::Serialize;
+use serde_json::Value;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum EventError {
+    #[error("Expected event of type {expected}, got {actual}")]
+    WrongType { expected: String, actual: String },
+
+    #[error("Event {event} has no attribute {key}")]
+    MissingAttribute { event: String, key: String },
+
+    #[error("Event {event} has invalid attribute {key}: {value}")]
+    InvalidAttribute {
+        event: String,
+        key: String,
+        value: String,
+    },
+}
+
+/// Event with a stable set of attributes, which can be parsed back from the emitted `Event`
+pub trait TypedEvent: Sized {
+    /// Type of the emitted event. Wasm module prefixes it with `wasm-` on chain.
+    const TYPE: &'static str;
+
+    fn to_event(&self) -> Event;
+
+    /// Parses the event, ignoring any attributes added by the chain
+    fn parse(event: &Event) -> Result<Self, EventError>;
+}
+
+/// Parses all events of type `E` out of `events`, skipping events of other types
+pub fn parse_all<E: TypedEvent>(events: &[Event]) -> Result<Vec<E>, EventError> {
+    events
+        .iter()
+        .filter(|event| has_type(event, E::TYPE))
+        .map(E::parse)
+        .collect()
+}
+
+fn has_type(event: &Event, ty: &str) -> bool {
+    event.ty == ty || event.ty.strip_prefix("wasm-") == Some(ty)
+}
+
+fn attribute<T: FromStr>(event: &Event, key: &str) -> Result<T, EventError> {
+    let value = event
+        .attributes
+        .iter()
+        .find(|attr| attr.key == key)
+        .map(|attr| &attr.value)
+        .ok_or_else(|| EventError::MissingAttribute {
+            event: event.ty.clone(),
+            key: key.to_owned(),
+        })?;
+
+    value.parse().map_err(|_| EventError::InvalidAttribute {
+        event: event.ty.clone(),
+        key: key.to_owned(),
+        value: value.clone(),
+    })
+}
+
+macro_rules! typed_event {
+    (
+        $(#[$meta:meta])*
+        $name:ident = $ty:literal {
+            $($(#[$field_meta:meta])* $field:ident: $field_ty:ty,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Debug, PartialEq)]
+        pub struct $name {
+            $($(#[$field_meta])* pub $field: $field_ty,)*
+        }
+
+        impl TypedEvent for $name {
+            const TYPE: &'static str = $ty;
+
+            fn to_event(&self) -> Event {
+                Event::new(Self::TYPE)
+                    $(.add_attribute(stringify!($field), self.$field.to_string()))*
+            }
+
+            fn parse(event: &Event) -> Result<Self, EventError> {
+                if !has_type(event, Self::TYPE) {
+                    return Err(EventError::WrongType {
+                        expected: Self::TYPE.to_owned(),
+                        actual: event.ty.clone(),
+                    });
+                }
+
+                Ok(Self {
+                    $($field: attribute(event, stringify!($field))?,)*
+                })
+            }
+        }
+
+        impl From<$name> for Event {
+            fn from(event: $name) -> Self {
+                event.to_event()
+            }
+        }
+    };
+}
+
+/// Kind of debt a borrow or repayment is about
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DebtKind {
+    /// B-Tokens, accruing with the variable rate
+    Variable,
+    Stable,
+    Term,
+}
+
+impl fmt::Display for DebtKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self {
+            DebtKind::Variable => "variable",
+            DebtKind::Stable => "stable",
+            DebtKind::Term => "term",
+        };
+        f.write_str(kind)
+    }
+}
+
+impl FromStr for DebtKind {
+    type Err = StdError;
+
+    fn from_str(s: &str) -> StdResult<Self> {
+        match s {
+            "variable" => Ok(DebtKind::Variable),
+            "stable" => Ok(DebtKind::Stable),
+            "term" => Ok(DebtKind::Term),
+            _ => Err(StdError::parse_err("DebtKind", s)),
+        }
+    }
+}
+
+typed_event! {
+    /// Market tokens deposited for L-Tokens
+    Deposit = "deposit" {
+        sender: String,
+        /// Account the L-Tokens are minted to
+        recipient: String,
+        amount: Uint128,
+        /// L-Tokens minted, in stored units
+        stored_amount: Uint128,
+    }
+}
+
+typed_event! {
+    /// L-Tokens burned for market tokens
+    Withdraw = "withdraw" {
+        account: String,
+        /// Account receiving the market tokens, or the tokens they were swapped to
+        recipient: String,
+        amount: Uint128,
+        /// L-Tokens burned, in stored units
+        stored_amount: Uint128,
+    }
+}
+
+typed_event! {
+    Borrow = "borrow" {
+        account: String,
+        kind: DebtKind,
+        /// Amount sent to the account
+        amount: Uint128,
+        /// Origination fee added to the debt on top of `amount`
+        fee: Uint128,
+        /// B-Tokens minted for `amount` and `fee`, in stored units. Zero for fixed-rate debt,
+        /// which is not tokenized.
+        stored_amount: Uint128,
+    }
+}
+
+typed_event! {
+    Repay = "repay" {
+        sender: String,
+        /// Account the debt is repaid for
+        account: String,
+        kind: DebtKind,
+        amount: Uint128,
+        /// B-Tokens burned, in stored units. Zero for fixed-rate debt.
+        stored_amount: Uint128,
+    }
+}
+
+typed_event! {
+    /// Debt of an account repaid with its collateral by someone else. The markets emit
+    /// `Repay` and `Withdraw` events with the stored amounts alongside.
+    Liquidate = "liquidate" {
+        liquidator: String,
+        account: String,
+        /// Market token of the market the collateral is taken from
+        collateral_denom: String,
+        /// Market token of the market the debt is repaid to
+        debt_denom: String,
+        repaid: Uint128,
+    }
+}
+
+typed_event! {
+    Mint = "mint" {
+        recipient: String,
+        amount: Uint128,
+        stored_amount: Uint128,
+    }
+}
+
+typed_event! {
+    Burn = "burn" {
+        owner: String,
+        amount: Uint128,
+        stored_amount: Uint128,
+    }
+}
+
+typed_event! {
+    Transfer = "transfer" {
+        sender: String,
+        recipient: String,
+        amount: Uint128,
+        stored_amount: Uint128,
+    }
+}
+
+typed_event! {
+    /// Token balances scaled by `ratio`
+    Rebase = "rebase" {
+        ratio: Decimal,
+        /// Multiplier from stored to display amounts after the rebase
+        multiplier: Decimal,
+    }
+}
+
+typed_event! {
+    /// Funds distributed to all holders of a token
+    Distribute = "distribute" {
+        sender: String,
+        denom: String,
+        amount: Uint128,
+    }
+}
+
+typed_event! {
+    /// Change made through a sudo message
+    ParameterChange = "parameter_change" {
+        /// Name of the sudo message
+        parameter: String,
+        /// Fields of the sudo message, as JSON
+        value: String,
+    }
+}
+
+impl ParameterChange {
+    /// Describes the change made by `msg`, a variant of a `SudoMsg`
+    pub fn from_msg<M: Serialize>(msg: &M) -> StdResult<Self> {
+        let json = serde_json::to_value(msg)
+            .map_err(|err| StdError::serialize_err(std::any::type_name::<M>(), err))?;
+
+        // Variants with fields serialize as `{"name":{...}}`, the others as `"name"`
+        match json {
+            Value::Object(variant) if variant.len() == 1 => {
+                let (parameter, value) = variant.into_iter().next().unwrap();
+                Ok(Self {
+                    parameter,
+                    value: value.to_string(),
+                })
+            }
+            Value::String(parameter) => Ok(Self {
+                parameter,
+                value: "{}".to_owned(),
+            }),
+            json => Err(StdError::generic_err(format!(
+                "Expected an enum variant, got {json}"
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_round_trip() {
+        let borrow = Borrow {
+            account: "account".to_owned(),
+            kind: DebtKind::Stable,
+            amount: Uint128::new(100),
+            fee: Uint128::new(1),
+            stored_amount: Uint128::new(10_100_000),
+        };
+        assert_eq!(Borrow::parse(&borrow.to_event()).unwrap(), borrow);
+
+        let rebase = Rebase {
+            ratio: Decimal::percent(110),
+            multiplier: Decimal::percent(121),
+        };
+        assert_eq!(Rebase::parse(&rebase.to_event()).unwrap(), rebase);
+    }
+
+    #[test]
+    fn parse_chain_event() {
+        // The chain prefixes the type and adds the contract address
+        let event = Event::new("wasm-deposit")
+            .add_attribute("_contract_address", "market")
+            .add_attribute("sender", "ca")
+            .add_attribute("recipient", "lender")
+            .add_attribute("amount", "100")
+            .add_attribute("stored_amount", "10000000");
+        let events = [Event::new("wasm").add_attribute("action", "deposit"), event];
+
+        assert_eq!(
+            parse_all::<Deposit>(&events).unwrap(),
+            vec![Deposit {
+                sender: "ca".to_owned(),
+                recipient: "lender".to_owned(),
+                amount: Uint128::new(100),
+                stored_amount: Uint128::new(10_000_000),
+            }]
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        let event = Event::new("burn").add_attribute("owner", "owner");
+        assert_eq!(
+            Mint::parse(&event).unwrap_err(),
+            EventError::WrongType {
+                expected: "mint".to_owned(),
+                actual: "burn".to_owned()
+            }
+        );
+        assert_eq!(
+            Burn::parse(&event).unwrap_err(),
+            EventError::MissingAttribute {
+                event: "burn".to_owned(),
+                key: "amount".to_owned()
+            }
+        );
+
+        let event = event
+            .add_attribute("amount", "lots")
+            .add_attribute("stored_amount", "1");
+        assert_eq!(
+            Burn::parse(&event).unwrap_err(),
+            EventError::InvalidAttribute {
+                event: "burn".to_owned(),
+                key: "amount".to_owned(),
+                value: "lots".to_owned()
+            }
+        );
+    }
+
+    #[test]
+    fn parameter_change_from_msg() {
+        #[derive(Serialize)]
+        #[serde(rename_all = "snake_case")]
+        enum SudoMsg {
+            AdjustFee { new_fee: Decimal },
+            Pause {},
+            Unpause,
+            Rename { name: String },
+        }
+
+        let change = ParameterChange::from_msg(&SudoMsg::AdjustFee {
+            new_fee: Decimal::percent(5),
+        })
+        .unwrap();
+        assert_eq!(change.parameter, "adjust_fee");
+        assert_eq!(change.value, r#"{"new_fee":"0.05"}"#);
+
+        let change = ParameterChange::from_msg(&SudoMsg::Pause {}).unwrap();
+        assert_eq!(change.parameter, "pause");
+        assert_eq!(change.value, "{}");
+
+        let change = ParameterChange::from_msg(&SudoMsg::Unpause).unwrap();
+        assert_eq!(change.parameter, "unpause");
+        assert_eq!(change.value, "{}");
+
+        // Quotes and braces inside of the values don't confuse the parsing
+        let change = ParameterChange::from_msg(&SudoMsg::Rename {
+            name: r#"{"a":"}"#.to_owned(),
+        })
+        .unwrap();
+        assert_eq!(change.parameter, "rename");
+        assert_eq!(change.value, r#"{"name":"{\"a\":\"}"}"#);
+
+        ParameterChange::from_msg(&[1, 2]).unwrap_err();
+    }
+}
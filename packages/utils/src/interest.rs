@@ -3,6 +3,10 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+const SECONDS_IN_YEAR: u64 = 365 * 24 * 3600;
+/// Highest exponent of `Interest::Polynomial`
+pub const MAX_EXPONENT: u32 = 10;
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 #[serde(rename_all = "snake_case")]
 pub enum Interest {
@@ -31,20 +35,110 @@ pub enum Interest {
         /// *Uoptimal* in the Aave docs.
         optimal_utilisation: Decimal,
     },
+    /// A piecewise linear curve with any number of breakpoints. The rate is
+    /// interpolated linearly between neighbouring kinks and stays flat after
+    /// the last one.
+    MultiKink {
+        /// Base percentage, charged at 0% utilisation
+        base: Decimal,
+        /// Breakpoints ordered by utilisation, with rates that never decrease
+        kinks: Vec<Kink>,
+    },
+    /// `base + slope * utilisation ^ exponent`. The higher the exponent,
+    /// the flatter the curve is at low utilisation and the steeper at the top.
+    Polynomial {
+        /// Base percentage, charged at 0% utilisation
+        base: Decimal,
+        /// Rate charged on top of `base` at 100% utilisation
+        slope: Decimal,
+        /// Power the utilisation is raised to, between 1 and `MAX_EXPONENT`
+        exponent: u32,
+    },
+    /// The same rate regardless of utilisation
+    Fixed { rate: Decimal },
+    /// A curve around the target utilisation, where the rate charged at the target drifts
+    /// over time. It goes up while utilisation stays above the target, and down while it
+    /// stays below, so the rates follow demand without governance intervention.
+    /// The current rate at target is kept by the market between charges.
+    Adaptive {
+        /// Utilisation the curve steers towards, within the (0, 1) range
+        target_utilisation: Decimal,
+        /// Rate at target before any drift happened
+        initial_rate_at_target: Decimal,
+        /// Lower bound for the drifting rate at target
+        min_rate_at_target: Decimal,
+        /// Upper bound for the drifting rate at target
+        max_rate_at_target: Decimal,
+        /// Relative change of the rate at target per year, when utilisation is 0% or 100%.
+        /// The drift is proportional to the distance from the target.
+        adjustment_speed: Decimal,
+        /// Rate at 100% utilisation is `steepness` times the rate at target, and rate at
+        /// 0% utilisation is the rate at target divided by `steepness`. At least 1.
+        steepness: Decimal,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct Kink {
+    /// Utilisation at which this kink is placed, within the (0, 1] range
+    pub utilisation: Decimal,
+    /// Interest rate charged at this utilisation
+    pub rate: Decimal,
 }
 
 impl Interest {
     pub fn validate(self) -> Result<ValidatedInterest, InterestError> {
-        if let Interest::PiecewiseLinear {
-            optimal_utilisation,
-            ..
-        } = self
-        {
-            if optimal_utilisation.is_zero() || optimal_utilisation >= Decimal::one() {
-                return Err(InterestError::InvalidOptimalUtilisation(
-                    optimal_utilisation,
-                ));
+        match &self {
+            Interest::PiecewiseLinear {
+                optimal_utilisation,
+                ..
+            } => {
+                if optimal_utilisation.is_zero() || *optimal_utilisation >= Decimal::one() {
+                    return Err(InterestError::InvalidOptimalUtilisation(
+                        *optimal_utilisation,
+                    ));
+                }
+            }
+            Interest::MultiKink { base, kinks } => {
+                let first = kinks.first().ok_or(InterestError::InvalidKinks {})?;
+                let ordered = kinks
+                    .windows(2)
+                    .all(|w| w[0].utilisation < w[1].utilisation && w[0].rate <= w[1].rate);
+                let in_range = !first.utilisation.is_zero()
+                    && kinks.iter().all(|kink| kink.utilisation <= Decimal::one());
+
+                if !ordered || !in_range || *base > first.rate {
+                    return Err(InterestError::InvalidKinks {});
+                }
+            }
+            Interest::Polynomial { exponent, .. } => {
+                if *exponent == 0 || *exponent > MAX_EXPONENT {
+                    return Err(InterestError::InvalidExponent(*exponent));
+                }
             }
+            Interest::Adaptive {
+                target_utilisation,
+                initial_rate_at_target,
+                min_rate_at_target,
+                max_rate_at_target,
+                steepness,
+                ..
+            } => {
+                if target_utilisation.is_zero() || *target_utilisation >= Decimal::one() {
+                    return Err(InterestError::InvalidOptimalUtilisation(
+                        *target_utilisation,
+                    ));
+                }
+                if min_rate_at_target > initial_rate_at_target
+                    || initial_rate_at_target > max_rate_at_target
+                {
+                    return Err(InterestError::InvalidRateAtTarget {});
+                }
+                if *steepness < Decimal::one() {
+                    return Err(InterestError::InvalidSteepness(*steepness));
+                }
+            }
+            Interest::Linear { .. } | Interest::Fixed { .. } => {}
         }
 
         Ok(ValidatedInterest::unchecked(self))
@@ -59,7 +153,19 @@ pub struct ValidatedInterest {
 }
 
 impl ValidatedInterest {
+    /// Interest rate for the given utilisation. Adaptive curves are evaluated at their
+    /// initial rate at target, use `calculate_interest_rate_with` to account for the drift.
     pub fn calculate_interest_rate(&self, utilisation: Decimal) -> Decimal {
+        self.calculate_interest_rate_with(utilisation, None)
+    }
+
+    /// Interest rate for the given utilisation, with the current `rate_at_target` of adaptive
+    /// curves (their initial one if `None`). Other models ignore `rate_at_target`.
+    pub fn calculate_interest_rate_with(
+        &self,
+        utilisation: Decimal,
+        rate_at_target: Option<Decimal>,
+    ) -> Decimal {
         match self.inner {
             Interest::Linear { base, slope } => base + slope * utilisation,
             Interest::PiecewiseLinear {
@@ -79,6 +185,102 @@ impl ValidatedInterest {
                                 * (Decimal::one() - optimal_utilisation).inv().unwrap())
                 }
             }
+            Interest::MultiKink { base, ref kinks } => {
+                let start = Kink {
+                    utilisation: Decimal::zero(),
+                    rate: base,
+                };
+
+                // the type guarantees kinks are ordered and there is at least one of them
+                let mut prev = &start;
+                for kink in kinks {
+                    if utilisation < kink.utilisation {
+                        return prev.rate
+                            + (kink.rate - prev.rate)
+                                * ((utilisation - prev.utilisation)
+                                    / (kink.utilisation - prev.utilisation));
+                    }
+                    prev = kink;
+                }
+                prev.rate
+            }
+            Interest::Polynomial {
+                base,
+                slope,
+                exponent,
+            } => {
+                // Utilisation is not bounded by 1, the curve stays flat above it. Below 1 the
+                // power can't overflow.
+                let utilisation = std::cmp::min(utilisation, Decimal::one());
+                base + slope * utilisation.pow(exponent)
+            }
+            Interest::Fixed { rate } => rate,
+            Interest::Adaptive {
+                target_utilisation,
+                initial_rate_at_target,
+                steepness,
+                ..
+            } => {
+                let rate_at_target = rate_at_target.unwrap_or(initial_rate_at_target);
+                // unwrapping is okay - the type guarantees `0 < target_utilisation < 1`
+                // and `steepness >= 1`
+                if utilisation < target_utilisation {
+                    let distance =
+                        (target_utilisation - utilisation) * target_utilisation.inv().unwrap();
+                    let drop = Decimal::one() - steepness.inv().unwrap();
+                    rate_at_target * (Decimal::one() - drop * distance)
+                } else {
+                    let distance = std::cmp::min(
+                        (utilisation - target_utilisation)
+                            * (Decimal::one() - target_utilisation).inv().unwrap(),
+                        Decimal::one(),
+                    );
+                    rate_at_target * (Decimal::one() + (steepness - Decimal::one()) * distance)
+                }
+            }
+        }
+    }
+
+    /// The rate at target of an adaptive curve after `seconds` spent at `utilisation`,
+    /// starting from `rate_at_target` (the initial one if `None`). The drift is linear in time
+    /// and bounded by the curve limits. Returns `None` for models which do not drift.
+    pub fn drift_rate_at_target(
+        &self,
+        rate_at_target: Option<Decimal>,
+        utilisation: Decimal,
+        seconds: u64,
+    ) -> Option<Decimal> {
+        if let Interest::Adaptive {
+            target_utilisation,
+            initial_rate_at_target,
+            min_rate_at_target,
+            max_rate_at_target,
+            adjustment_speed,
+            ..
+        } = self.inner
+        {
+            let rate_at_target = rate_at_target.unwrap_or(initial_rate_at_target);
+            let elapsed = Decimal::from_ratio(seconds, SECONDS_IN_YEAR);
+
+            // unwrapping is okay - the type guarantees `0 < target_utilisation < 1`
+            let drifted = if utilisation < target_utilisation {
+                let distance =
+                    (target_utilisation - utilisation) * target_utilisation.inv().unwrap();
+                let change = adjustment_speed * distance * elapsed;
+                rate_at_target * Decimal::one().saturating_sub(change)
+            } else {
+                let distance = std::cmp::min(
+                    (utilisation - target_utilisation)
+                        * (Decimal::one() - target_utilisation).inv().unwrap(),
+                    Decimal::one(),
+                );
+                let change = adjustment_speed * distance * elapsed;
+                rate_at_target.saturating_mul(Decimal::one().saturating_add(change))
+            };
+
+            Some(drifted.clamp(min_rate_at_target, max_rate_at_target))
+        } else {
+            None
         }
     }
 
@@ -93,6 +295,18 @@ impl ValidatedInterest {
 pub enum InterestError {
     #[error("Optimal utilisation must be within the (0, 1) range, but it's {0}")]
     InvalidOptimalUtilisation(Decimal),
+
+    #[error("Kinks must be non-empty, ordered by utilisation within the (0, 1] range, with rates not lower than base and never decreasing")]
+    InvalidKinks {},
+
+    #[error("Exponent must be between 1 and {}, but it's {0}", MAX_EXPONENT)]
+    InvalidExponent(u32),
+
+    #[error("Initial rate at target must be within the min and max rates at target")]
+    InvalidRateAtTarget {},
+
+    #[error("Steepness must be at least 1, but it's {0}")]
+    InvalidSteepness(Decimal),
 }
 
 #[cfg(test)]
@@ -210,4 +424,276 @@ mod tests {
             InterestError::InvalidOptimalUtilisation(Decimal::percent(444))
         );
     }
+
+    fn kink(utilisation: u64, rate: u64) -> Kink {
+        Kink {
+            utilisation: Decimal::percent(utilisation),
+            rate: Decimal::percent(rate),
+        }
+    }
+
+    #[test]
+    fn multi_kink_interest_rate() {
+        let interest = Interest::MultiKink {
+            base: Decimal::percent(2),
+            kinks: vec![kink(50, 10), kink(80, 20), kink(90, 100)],
+        }
+        .validate()
+        .unwrap();
+
+        assert_eq!(
+            interest.calculate_interest_rate(Decimal::zero()),
+            Decimal::percent(2)
+        );
+        assert_eq!(
+            interest.calculate_interest_rate(Decimal::percent(25)),
+            Decimal::percent(6)
+        );
+        assert_eq!(
+            interest.calculate_interest_rate(Decimal::percent(50)),
+            Decimal::percent(10)
+        );
+        assert_eq!(
+            interest.calculate_interest_rate(Decimal::percent(65)),
+            Decimal::percent(15)
+        );
+        assert_eq!(
+            interest.calculate_interest_rate(Decimal::percent(85)),
+            Decimal::percent(60)
+        );
+        // flat after the last kink
+        assert_eq!(
+            interest.calculate_interest_rate(Decimal::one()),
+            Decimal::percent(100)
+        );
+    }
+
+    #[test]
+    fn multi_kink_validation() {
+        let invalid = [
+            vec![],
+            vec![kink(0, 10)],
+            vec![kink(50, 10), kink(40, 20)],
+            vec![kink(50, 10), kink(50, 20)],
+            vec![kink(50, 20), kink(80, 10)],
+            vec![kink(50, 10), kink(120, 20)],
+            vec![kink(50, 1)],
+        ];
+
+        for kinks in invalid {
+            let err = Interest::MultiKink {
+                base: Decimal::percent(2),
+                kinks,
+            }
+            .validate()
+            .unwrap_err();
+            assert_eq!(err, InterestError::InvalidKinks {});
+        }
+    }
+
+    #[test]
+    fn polynomial_interest_rate() {
+        let interest = Interest::Polynomial {
+            base: Decimal::percent(2),
+            slope: Decimal::percent(100),
+            exponent: 2,
+        }
+        .validate()
+        .unwrap();
+
+        assert_eq!(
+            interest.calculate_interest_rate(Decimal::zero()),
+            Decimal::percent(2)
+        );
+        assert_eq!(
+            interest.calculate_interest_rate(Decimal::percent(50)),
+            Decimal::percent(27)
+        );
+        assert_eq!(
+            interest.calculate_interest_rate(Decimal::one()),
+            Decimal::percent(102)
+        );
+
+        let err = Interest::Polynomial {
+            base: Decimal::percent(2),
+            slope: Decimal::percent(100),
+            exponent: 0,
+        }
+        .validate()
+        .unwrap_err();
+        assert_eq!(err, InterestError::InvalidExponent(0));
+
+        let err = Interest::Polynomial {
+            base: Decimal::percent(2),
+            slope: Decimal::percent(100),
+            exponent: MAX_EXPONENT + 1,
+        }
+        .validate()
+        .unwrap_err();
+        assert_eq!(err, InterestError::InvalidExponent(MAX_EXPONENT + 1));
+
+        // Utilisation above 1 is charged as full utilisation
+        let interest = Interest::Polynomial {
+            base: Decimal::percent(2),
+            slope: Decimal::percent(100),
+            exponent: MAX_EXPONENT,
+        }
+        .validate()
+        .unwrap();
+        assert_eq!(
+            interest.calculate_interest_rate(Decimal::from_ratio(1000u128, 1u128)),
+            Decimal::percent(102)
+        );
+    }
+
+    #[test]
+    fn fixed_interest_rate() {
+        let interest = Interest::Fixed {
+            rate: Decimal::percent(7),
+        }
+        .validate()
+        .unwrap();
+
+        assert_eq!(
+            interest.calculate_interest_rate(Decimal::zero()),
+            Decimal::percent(7)
+        );
+        assert_eq!(
+            interest.calculate_interest_rate(Decimal::one()),
+            Decimal::percent(7)
+        );
+    }
+
+    fn adaptive() -> ValidatedInterest {
+        Interest::Adaptive {
+            target_utilisation: Decimal::percent(80),
+            initial_rate_at_target: Decimal::percent(4),
+            min_rate_at_target: Decimal::percent(1),
+            max_rate_at_target: Decimal::percent(20),
+            adjustment_speed: Decimal::percent(1000),
+            steepness: Decimal::percent(400),
+        }
+        .validate()
+        .unwrap()
+    }
+
+    #[test]
+    fn adaptive_interest_rate() {
+        let interest = adaptive();
+
+        assert_eq!(
+            interest.calculate_interest_rate(Decimal::zero()),
+            Decimal::percent(1)
+        );
+        assert_eq!(
+            interest.calculate_interest_rate(Decimal::percent(40)),
+            Decimal::permille(25)
+        );
+        assert_eq!(
+            interest.calculate_interest_rate(Decimal::percent(80)),
+            Decimal::percent(4)
+        );
+        assert_eq!(
+            interest.calculate_interest_rate(Decimal::percent(90)),
+            Decimal::percent(10)
+        );
+        assert_eq!(
+            interest.calculate_interest_rate(Decimal::one()),
+            Decimal::percent(16)
+        );
+        assert_eq!(
+            interest.calculate_interest_rate_with(Decimal::one(), Some(Decimal::percent(5))),
+            Decimal::percent(20)
+        );
+    }
+
+    #[test]
+    fn adaptive_rate_at_target_drifts() {
+        let interest = adaptive();
+        let month = SECONDS_IN_YEAR / 12;
+
+        // at target nothing changes
+        assert_eq!(
+            interest.drift_rate_at_target(None, Decimal::percent(80), month),
+            Some(Decimal::percent(4))
+        );
+        // 1000% per year at full distance, 50% of the distance for 1/10 of a year
+        assert_eq!(
+            interest.drift_rate_at_target(None, Decimal::percent(90), SECONDS_IN_YEAR / 10),
+            Some(Decimal::percent(6))
+        );
+        assert_eq!(
+            interest.drift_rate_at_target(
+                Some(Decimal::percent(6)),
+                Decimal::percent(40),
+                SECONDS_IN_YEAR / 10
+            ),
+            Some(Decimal::percent(3))
+        );
+        // bounded by the limits
+        assert_eq!(
+            interest.drift_rate_at_target(None, Decimal::one(), SECONDS_IN_YEAR),
+            Some(Decimal::percent(20))
+        );
+        assert_eq!(
+            interest.drift_rate_at_target(None, Decimal::zero(), SECONDS_IN_YEAR),
+            Some(Decimal::percent(1))
+        );
+
+        let linear = Interest::Linear {
+            base: Decimal::percent(10),
+            slope: Decimal::percent(90),
+        }
+        .validate()
+        .unwrap();
+        assert_eq!(
+            linear.drift_rate_at_target(None, Decimal::one(), month),
+            None
+        );
+    }
+
+    #[test]
+    fn adaptive_validation() {
+        let valid = Interest::Adaptive {
+            target_utilisation: Decimal::percent(80),
+            initial_rate_at_target: Decimal::percent(4),
+            min_rate_at_target: Decimal::percent(1),
+            max_rate_at_target: Decimal::percent(20),
+            adjustment_speed: Decimal::percent(1000),
+            steepness: Decimal::percent(400),
+        };
+
+        let mut invalid = valid.clone();
+        if let Interest::Adaptive {
+            target_utilisation, ..
+        } = &mut invalid
+        {
+            *target_utilisation = Decimal::one();
+        }
+        assert_eq!(
+            invalid.validate().unwrap_err(),
+            InterestError::InvalidOptimalUtilisation(Decimal::one())
+        );
+
+        let mut invalid = valid.clone();
+        if let Interest::Adaptive {
+            min_rate_at_target, ..
+        } = &mut invalid
+        {
+            *min_rate_at_target = Decimal::percent(5);
+        }
+        assert_eq!(
+            invalid.validate().unwrap_err(),
+            InterestError::InvalidRateAtTarget {}
+        );
+
+        let mut invalid = valid;
+        if let Interest::Adaptive { steepness, .. } = &mut invalid {
+            *steepness = Decimal::percent(50);
+        }
+        assert_eq!(
+            invalid.validate().unwrap_err(),
+            InterestError::InvalidSteepness(Decimal::percent(50))
+        );
+    }
 }
@@ -1,6 +1,8 @@
 pub mod coin;
 pub mod credit_line;
+pub mod events;
 pub mod interest;
+pub mod oracle;
 pub mod price;
 pub mod tests;
 pub mod time;
@@ -0,0 +1,47 @@
+use cosmwasm_std::{Decimal, Timestamp};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Queries every price oracle used by the markets has to support. Oracle contracts are free to
+/// handle other queries as well, but markets only rely on these.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OracleQueryMsg {
+    /// Queries the exchange rate between two denoms. Returns `PriceResponse`
+    Price { sell: String, buy: String },
+    /// Same as `Price`, but also tells when the rate was last updated.
+    /// Returns `PriceWithTimestampResponse`
+    PriceWithTimestamp { sell: String, buy: String },
+    /// Queries the swap route to follow when selling `sell` for `buy`. Returns `RouteResponse`
+    Route { sell: String, buy: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct PriceResponse {
+    pub rate: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct PriceWithTimestampResponse {
+    pub rate: Decimal,
+    /// Time the rate was last updated at
+    pub updated_at: Timestamp,
+}
+
+/// Single swap within a route, through the `pool_id` liquidity pool
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct RouteStep {
+    pub pool_id: u64,
+    pub denom_in: String,
+    pub denom_out: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct RouteResponse {
+    /// Swaps to perform in order, the `denom_out` of a step being the `denom_in` of the next one
+    pub route: Vec<RouteStep>,
+}
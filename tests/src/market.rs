@@ -76,6 +76,9 @@ impl MarketBuilder {
             collateral_ratio: self.collateral_ratio,
             price_oracle: price_oracle.to_string(),
             reserve_factor: self.reserve_factor,
+            origination_fee: Decimal::zero(),
+            borrowing_enabled: true,
+            collateral_enabled: true,
         }
     }
 }
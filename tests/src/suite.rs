@@ -10,6 +10,9 @@ use isotonic_market::msg::{
     ExecuteMsg as MarketExecuteMsg, MigrateMsg as MarketMigrateMsg, QueryMsg as MarketQueryMsg,
     TokensBalanceResponse,
 };
+use isotonic_mock_oracle::msg::{
+    ExecuteMsg as MockOracleExecuteMsg, InstantiateMsg as MockOracleInstantiateMsg,
+};
 use isotonic_osmosis_oracle::msg::{
     ExecuteMsg as OracleExecuteMsg, InstantiateMsg as OracleInstantiateMsg,
 };
@@ -35,6 +38,16 @@ fn contract_osmosis_oracle() -> Box<dyn Contract<OsmosisMsg, OsmosisQuery>> {
     Box::new(contract)
 }
 
+fn contract_mock_oracle() -> Box<dyn Contract<OsmosisMsg, OsmosisQuery>> {
+    let contract = ContractWrapper::new_with_empty(
+        isotonic_mock_oracle::contract::execute,
+        isotonic_mock_oracle::contract::instantiate,
+        isotonic_mock_oracle::contract::query,
+    );
+
+    Box::new(contract)
+}
+
 fn contract_credit_agency() -> Box<dyn Contract<OsmosisMsg, OsmosisQuery>> {
     let contract = ContractWrapper::new(
         isotonic_credit_agency::contract::execute,
@@ -87,6 +100,10 @@ pub struct SuiteBuilder {
     common_token: String,
     pools: HashMap<u64, (Coin, Coin)>,
     markets: Vec<MarketBuilder>,
+    /// Use the mock oracle instead of pricing through the Osmosis pools
+    mock_oracle: bool,
+    /// Initial mock oracle prices as (sell, buy, rate)
+    prices: Vec<(String, String, Decimal)>,
 }
 
 impl SuiteBuilder {
@@ -131,15 +148,40 @@ impl SuiteBuilder {
         self
     }
 
+    /// Uses the mock oracle for prices. Pools are still created, so swaps keep working,
+    /// but they no longer affect prices.
+    pub fn with_mock_oracle(mut self) -> Self {
+        self.mock_oracle = true;
+        self
+    }
+
+    /// Sets the initial price of `sell` in `buy` (and the inverse). Implies `with_mock_oracle`.
+    pub fn with_price(mut self, sell: &str, buy: &str, rate: Decimal) -> Self {
+        self.mock_oracle = true;
+        self.prices.push((sell.to_owned(), buy.to_owned(), rate));
+        self
+    }
+
     #[track_caller]
     pub fn build(self) -> Suite {
         let mut app = OsmosisApp::default();
         let owner = Addr::unchecked(self.gov_contract.clone());
         let common_token = self.common_token.clone();
 
-        let oracle_id = app.store_code(contract_osmosis_oracle());
-        let oracle_contract = app
-            .instantiate_contract(
+        let oracle_contract = if self.mock_oracle {
+            let oracle_id = app.store_code(contract_mock_oracle());
+            app.instantiate_contract(
+                oracle_id,
+                owner.clone(),
+                &MockOracleInstantiateMsg {},
+                &[],
+                "oracle",
+                Some(owner.to_string()),
+            )
+            .unwrap()
+        } else {
+            let oracle_id = app.store_code(contract_osmosis_oracle());
+            app.instantiate_contract(
                 oracle_id,
                 owner.clone(),
                 &OracleInstantiateMsg {
@@ -149,7 +191,8 @@ impl SuiteBuilder {
                 "oracle",
                 Some(owner.to_string()),
             )
-            .unwrap();
+            .unwrap()
+        };
         dbg!(&oracle_contract);
 
         // initialize the pools for osmosis oracle
@@ -164,14 +207,22 @@ impl SuiteBuilder {
         })
         .unwrap();
         for (pool_id, (coin1, coin2)) in self.pools.clone() {
+            register_pool(
+                &mut app,
+                &owner,
+                &oracle_contract,
+                self.mock_oracle,
+                pool_id,
+                coin1.denom,
+                coin2.denom,
+            )
+            .unwrap();
+        }
+        for (sell, buy, rate) in self.prices {
             app.execute_contract(
                 owner.clone(),
                 oracle_contract.clone(),
-                &OracleExecuteMsg::RegisterPool {
-                    pool_id,
-                    denom1: coin1.denom,
-                    denom2: coin2.denom,
-                },
+                &MockOracleExecuteMsg::SetPrice { sell, buy, rate },
                 &[],
             )
             .unwrap();
@@ -203,9 +254,9 @@ impl SuiteBuilder {
             app.execute_contract(
                 owner.clone(),
                 credit_agency.clone(),
-                &isotonic_credit_agency::msg::ExecuteMsg::CreateMarket(
+                &isotonic_credit_agency::msg::ExecuteMsg::CreateMarket(Box::new(
                     market.build(oracle_contract.as_str()),
-                ),
+                )),
                 &[],
             )
             .unwrap();
@@ -228,11 +279,47 @@ impl SuiteBuilder {
             credit_agency,
             common_token: Token::Native(common_token),
             oracle_contract,
+            mock_oracle: self.mock_oracle,
             starting_pools: self.pools,
         }
     }
 }
 
+/// Makes the oracle aware of the pool, using the message the oracle in use understands
+fn register_pool(
+    app: &mut OsmosisApp,
+    owner: &Addr,
+    oracle: &Addr,
+    mock_oracle: bool,
+    pool_id: u64,
+    denom1: String,
+    denom2: String,
+) -> AnyResult<AppResponse> {
+    if mock_oracle {
+        app.execute_contract(
+            owner.clone(),
+            oracle.clone(),
+            &MockOracleExecuteMsg::SetPool {
+                pool_id,
+                denom1,
+                denom2,
+            },
+            &[],
+        )
+    } else {
+        app.execute_contract(
+            owner.clone(),
+            oracle.clone(),
+            &OracleExecuteMsg::RegisterPool {
+                pool_id,
+                denom1,
+                denom2,
+            },
+            &[],
+        )
+    }
+}
+
 /// Test suite
 pub struct Suite {
     /// The multitest app
@@ -245,6 +332,8 @@ pub struct Suite {
     common_token: Token,
     /// Address of isotonic price oracle
     pub oracle_contract: Addr,
+    /// Whether the oracle is the mock oracle
+    mock_oracle: bool,
     /// The pool values as defined by the builder, useful for resetting
     starting_pools: HashMap<u64, (Coin, Coin)>,
 }
@@ -392,22 +481,53 @@ impl Suite {
             })
             .unwrap();
         for (pool_id, (coin1, coin2)) in pools {
-            self.app
-                .execute_contract(
-                    owner.clone(),
-                    oracle.clone(),
-                    &OracleExecuteMsg::RegisterPool {
-                        pool_id: *pool_id,
-                        denom1: coin1.denom.clone(),
-                        denom2: coin2.denom.clone(),
-                    },
-                    &[],
-                )
-                .unwrap();
+            register_pool(
+                &mut self.app,
+                &owner,
+                &oracle,
+                self.mock_oracle,
+                *pool_id,
+                coin1.denom.clone(),
+                coin2.denom.clone(),
+            )
+            .unwrap();
         }
         Ok(())
     }
 
+    /// Sets the price of `sell` in `buy` (and the inverse). Requires the mock oracle.
+    pub fn set_price(&mut self, sell: &str, buy: &str, rate: Decimal) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            self.gov.clone(),
+            self.oracle_contract.clone(),
+            &MockOracleExecuteMsg::SetPrice {
+                sell: sell.to_owned(),
+                buy: buy.to_owned(),
+                rate,
+            },
+            &[],
+        )
+    }
+
+    /// Makes price queries for the trading pair fail. Requires the mock oracle.
+    pub fn set_price_error(
+        &mut self,
+        denom1: &str,
+        denom2: &str,
+        error: &str,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            self.gov.clone(),
+            self.oracle_contract.clone(),
+            &MockOracleExecuteMsg::SetPriceError {
+                denom1: denom1.to_owned(),
+                denom2: denom2.to_owned(),
+                error: error.to_owned(),
+            },
+            &[],
+        )
+    }
+
     pub fn swap_exact_in(&mut self, sender: &str, sell: Coin, buy: &str) -> AnyResult<AppResponse> {
         let common_token = self.common_token.clone().native().unwrap();
 
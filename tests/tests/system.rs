@@ -291,3 +291,66 @@ fn receive_reward_in_different_denoms_with_six_months_interests() {
         .make_response(suite.common_token().clone())
     );
 }
+
+#[test]
+fn credit_line_follows_mocked_price() {
+    let debtor = "debtor";
+    let lender = "lender";
+
+    let common = "COMMON";
+    let osmo = "OSMO";
+    let eth = "ETH";
+
+    let mut suite = SuiteBuilder::new()
+        .with_common_token(common)
+        .with_funds(lender, &coins(100_000, eth))
+        .with_funds(debtor, &coins(5_000, osmo))
+        .with_price(osmo, common, Decimal::percent(400))
+        .with_price(eth, common, Decimal::percent(10))
+        .with_market(MarketBuilder::new(osmo))
+        .with_market(MarketBuilder::new(eth))
+        .build();
+
+    suite.deposit(debtor, coin(4_000, osmo)).unwrap();
+    suite.deposit(lender, coin(100_000, eth)).unwrap();
+    suite.borrow(debtor, coin(75_000, eth)).unwrap();
+
+    let total_credit_line = suite.query_total_credit_line(debtor).unwrap();
+    assert_eq!(
+        total_credit_line,
+        CreditLineValues {
+            collateral: Uint128::new(16_000), // 4000 deposited * 4.0
+            credit_line: Uint128::new(8_000), // 16000 collateral * 0.5 collateral price
+            debt: Uint128::new(7_500)         // 75_000 * 0.1
+        }
+        .make_response(suite.common_token().clone())
+    );
+
+    // The collateral price drops without touching any pool
+    suite
+        .set_price(osmo, common, Decimal::percent(300))
+        .unwrap();
+    let total_credit_line = suite.query_total_credit_line(debtor).unwrap();
+    assert_eq!(
+        total_credit_line,
+        CreditLineValues {
+            collateral: Uint128::new(12_000), // 4000 deposited * 3.0
+            credit_line: Uint128::new(6_000), // 12000 collateral * 0.5 collateral price
+            debt: Uint128::new(7_500)         // 75_000 * 0.1
+        }
+        .make_response(suite.common_token().clone())
+    );
+    suite.assert_borrowable(debtor, coin(0, eth));
+
+    // A zero price makes the collateral worthless
+    suite.set_price(osmo, common, Decimal::zero()).unwrap();
+    let total_credit_line = suite.query_total_credit_line(debtor).unwrap();
+    assert_eq!(total_credit_line.collateral.amount, Uint128::zero());
+
+    // Oracle failures are propagated
+    suite
+        .set_price_error(osmo, common, "Oracle is down")
+        .unwrap();
+    let err = suite.query_total_credit_line(debtor).unwrap_err();
+    assert!(err.to_string().contains("Oracle is down"));
+}